@@ -0,0 +1,298 @@
+// this_file: svgn/src/a11y.rs
+
+//! Accessibility support
+//!
+//! With [`Config::a11y`](crate::config::Config::a11y) enabled, the plugins
+//! that would strip accessible names are adjusted: `removeTitle` and
+//! `removeDesc` keep `<title>` and `<desc>` elements that name or describe
+//! something, `removeUnknownsAndDefaults` keeps `aria-*` and `role`, and
+//! `addAccessibilityAttrs` marks up the root element. [`check`] reports
+//! documents that assistive technology can't make sense of.
+
+use crate::ast::{Document, Element, Node};
+use crate::plugin::PluginConfig;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fmt;
+
+/// An accessibility problem found by [`check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum A11yIssue {
+    /// The root has neither an accessible name nor `aria-hidden="true"`
+    MissingName,
+    /// An ID in `aria-labelledby` or `aria-describedby` matches no element
+    MissingReference {
+        /// Attribute holding the reference
+        attribute: String,
+        /// The missing ID
+        id: String,
+    },
+}
+
+impl fmt::Display for A11yIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            A11yIssue::MissingName => write!(
+                f,
+                "SVG has neither an accessible name nor aria-hidden=\"true\""
+            ),
+            A11yIssue::MissingReference { attribute, id } => {
+                write!(f, "{} references missing element #{}", attribute, id)
+            }
+        }
+    }
+}
+
+/// Find accessibility problems in a document
+pub fn check(document: &Document) -> Vec<A11yIssue> {
+    let root = &document.root;
+    let mut issues = Vec::new();
+
+    if !is_hidden(root) && accessible_name(root).is_none() {
+        issues.push(A11yIssue::MissingName);
+    }
+
+    let mut ids = HashSet::new();
+    collect_ids(root, &mut ids);
+    for attribute in ["aria-labelledby", "aria-describedby"] {
+        let mut referenced: Vec<String> = referenced_ids(root, attribute).into_iter().collect();
+        referenced.sort();
+        issues.extend(
+            referenced
+                .into_iter()
+                .filter(|id| !ids.contains(id))
+                .map(|id| A11yIssue::MissingReference {
+                    attribute: attribute.to_string(),
+                    id,
+                }),
+        );
+    }
+
+    issues
+}
+
+/// Whether the element is hidden from assistive technology or decorative
+pub fn is_hidden(element: &Element) -> bool {
+    element.attr("aria-hidden").map(|v| v.trim()) == Some("true")
+        || matches!(
+            element.attr("role").map(|v| v.trim()),
+            Some("presentation" | "none")
+        )
+}
+
+/// The accessible name of an `<svg>` element
+///
+/// Taken, in order, from `aria-labelledby`, `aria-label` and the first
+/// `<title>` child.
+pub fn accessible_name(root: &Element) -> Option<String> {
+    let non_empty = |name: String| {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!name.is_empty()).then_some(name)
+    };
+
+    if let Some(ids) = root.attr("aria-labelledby") {
+        let labels: Vec<String> = ids
+            .split_whitespace()
+            .filter_map(|id| find_by_id(root, id))
+            .map(text_content)
+            .collect();
+        if let Some(name) = non_empty(labels.join(" ")) {
+            return Some(name);
+        }
+    }
+    if let Some(name) = root.attr("aria-label").cloned().and_then(non_empty) {
+        return Some(name);
+    }
+    first_child(root, "title")
+        .map(text_content)
+        .and_then(non_empty)
+}
+
+/// IDs listed in an ID reference attribute, such as `aria-labelledby`,
+/// anywhere in the tree
+pub fn referenced_ids(root: &Element, attribute: &str) -> HashSet<String> {
+    fn collect(element: &Element, attribute: &str, ids: &mut HashSet<String>) {
+        if let Some(value) = element.attr(attribute) {
+            ids.extend(value.split_whitespace().map(str::to_string));
+        }
+        for child in element.child_elements() {
+            collect(child, attribute, ids);
+        }
+    }
+
+    let mut ids = HashSet::new();
+    collect(root, attribute, &mut ids);
+    ids
+}
+
+/// Which `<title>` or `<desc>` elements carry accessible text
+///
+/// These are the first such child of the root and those referenced by
+/// `attribute` (`aria-labelledby` or `aria-describedby`).
+pub struct AccessibleText {
+    name: &'static str,
+    referenced: HashSet<String>,
+}
+
+impl AccessibleText {
+    /// Find the `name` elements to keep in the document
+    pub fn new(root: &Element, name: &'static str, attribute: &str) -> Self {
+        Self {
+            name,
+            referenced: referenced_ids(root, attribute),
+        }
+    }
+
+    /// Whether `element` has to be kept, given whether it is the first
+    /// `<title>` or `<desc>` child of the root
+    pub fn keeps(&self, element: &Element, first_root_child: bool) -> bool {
+        element.name == self.name
+            && (first_root_child
+                || element
+                    .attr("id")
+                    .is_some_and(|id| self.referenced.contains(id)))
+    }
+}
+
+/// Adjust a plugin list for a11y mode
+///
+/// Returns the plugins with parameters that keep accessible names, followed
+/// by `addAccessibilityAttrs` if it isn't in the list already.
+pub fn adjust_plugins(plugins: &[PluginConfig]) -> Vec<PluginConfig> {
+    let mut plugins = plugins.to_vec();
+
+    for plugin in &mut plugins {
+        let overrides = match plugin.name.as_str() {
+            "removeTitle" | "removeDesc" => json!({"keepAccessible": true}),
+            "removeUnknownsAndDefaults" => json!({"keepAriaAttrs": true, "keepRoleAttr": true}),
+            _ => continue,
+        };
+        let mut params = match plugin.params.take() {
+            Some(Value::Object(params)) => params,
+            _ => Default::default(),
+        };
+        if let Value::Object(overrides) = overrides {
+            params.extend(overrides);
+        }
+        plugin.params = Some(Value::Object(params));
+    }
+
+    if !plugins.iter().any(|p| p.name == "addAccessibilityAttrs") {
+        plugins.push(PluginConfig::new("addAccessibilityAttrs".to_string()));
+    }
+
+    plugins
+}
+
+fn first_child<'a>(element: &'a Element, name: &str) -> Option<&'a Element> {
+    element.child_elements().find(|child| child.name == name)
+}
+
+fn find_by_id<'a>(element: &'a Element, id: &str) -> Option<&'a Element> {
+    if element.attr("id").map(String::as_str) == Some(id) {
+        return Some(element);
+    }
+    element
+        .child_elements()
+        .find_map(|child| find_by_id(child, id))
+}
+
+fn collect_ids(element: &Element, ids: &mut HashSet<String>) {
+    if let Some(id) = element.attr("id") {
+        ids.insert(id.clone());
+    }
+    for child in element.child_elements() {
+        collect_ids(child, ids);
+    }
+}
+
+fn text_content(element: &Element) -> String {
+    let mut text = String::new();
+    for child in &element.children {
+        match child {
+            Node::Text(content) | Node::CData(content) => text.push_str(content),
+            Node::Element(child) => text.push_str(&text_content(child)),
+            _ => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn parse(svg: &str) -> Document {
+        Parser::new().parse(svg).unwrap()
+    }
+
+    #[test]
+    fn test_accessible_name() {
+        let named = |svg: &str| accessible_name(&parse(svg).root);
+
+        assert_eq!(
+            named(r#"<svg><title> A  cat </title></svg>"#),
+            Some("A cat".to_string())
+        );
+        assert_eq!(
+            named(r#"<svg aria-label="Dog"><title>Cat</title></svg>"#),
+            Some("Dog".to_string())
+        );
+        assert_eq!(
+            named(
+                r#"<svg aria-labelledby="a b"><text id="a">Big</text><text id="b">dog</text></svg>"#
+            ),
+            Some("Big dog".to_string())
+        );
+        assert_eq!(named(r#"<svg><g><title>Nested</title></g></svg>"#), None);
+        assert_eq!(named(r#"<svg aria-label=" "/>"#), None);
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(
+            check(&parse("<svg><path/></svg>")),
+            vec![A11yIssue::MissingName]
+        );
+        assert!(check(&parse(r#"<svg aria-hidden="true"><path/></svg>"#)).is_empty());
+        assert!(check(&parse(r#"<svg role="img"><title>Icon</title></svg>"#)).is_empty());
+
+        let issues = check(&parse(
+            r#"<svg aria-labelledby="t"><title id="t">Icon</title><path aria-describedby="d"/></svg>"#,
+        ));
+        assert_eq!(
+            issues,
+            vec![A11yIssue::MissingReference {
+                attribute: "aria-describedby".to_string(),
+                id: "d".to_string()
+            }]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "aria-describedby references missing element #d"
+        );
+    }
+
+    #[test]
+    fn test_adjust_plugins() {
+        let plugins = vec![
+            PluginConfig::with_params("removeDesc".to_string(), json!({"removeAny": true})),
+            PluginConfig::new("removeUnknownsAndDefaults".to_string()),
+            PluginConfig::new("sortAttrs".to_string()),
+        ];
+        let adjusted = adjust_plugins(&plugins);
+
+        assert_eq!(
+            adjusted[0].params,
+            Some(json!({"removeAny": true, "keepAccessible": true}))
+        );
+        assert_eq!(
+            adjusted[1].params,
+            Some(json!({"keepAriaAttrs": true, "keepRoleAttr": true}))
+        );
+        assert_eq!(adjusted[2].params, None);
+        assert_eq!(adjusted[3].name, "addAccessibilityAttrs");
+        assert_eq!(adjust_plugins(&adjusted).len(), 4);
+    }
+}
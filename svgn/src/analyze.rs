@@ -0,0 +1,628 @@
+// this_file: svgn/src/analyze.rs
+
+//! Document statistics and size attribution
+//!
+//! [`analyze`] walks a [`Document`] and attributes every byte of its
+//! serialized form to a category (path data, styles, editor data, embedded
+//! images and so on), counts elements by type, collects a histogram of the
+//! decimal places used by numbers, and finds unreferenced IDs and `<defs>`
+//! content. Sizes are measured on the document as the stringifier writes it
+//! without formatting, so they don't include indentation of the source.
+//! [`Analysis::merge`] adds analyses up for a folder of files.
+
+use crate::ast::{Document, Element, NamespaceScope, Node};
+use crate::collections::EDITOR_NAMESPACES;
+use crate::config::Config;
+use crate::parser::{ParseError, Parser};
+use crate::plugins::cleanup_ids::find_references;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+/// A number, capturing its decimal places
+static NUMBER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[-+]?(?:\d*\.(\d+)|\d+\.?)(?:[eE][-+]?\d+)?").unwrap());
+
+/// A whole attribute value that is a number with an optional unit
+static NUMERIC_VALUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*[-+]?(?:\d*\.\d+|\d+\.?)(?:[eE][-+]?\d+)?(?:px|pt|pc|mm|cm|in|em|ex|%)?\s*$")
+        .unwrap()
+});
+
+/// Attributes holding lists of numbers
+const NUMBER_LIST_ATTRS: &[&str] = &[
+    "d",
+    "points",
+    "transform",
+    "gradientTransform",
+    "patternTransform",
+    "viewBox",
+];
+
+/// Bytes of the serialized document by what they are spent on
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ByteBreakdown {
+    /// Tag names and brackets
+    pub markup: usize,
+    /// Path geometry: `d` and `points` attributes
+    pub path_data: usize,
+    /// Attributes not counted in another category
+    pub attributes: usize,
+    /// `style` attributes and `<style>` elements
+    pub styles: usize,
+    /// Text and CDATA content
+    pub text: usize,
+    /// Comments
+    pub comments: usize,
+    /// `<metadata>` elements
+    pub metadata: usize,
+    /// Elements, attributes and namespace declarations of editors
+    pub editor: usize,
+    /// Embedded images in `data:` URLs
+    pub images: usize,
+    /// XML declaration, DOCTYPE and other processing instructions
+    pub prolog: usize,
+}
+
+impl ByteBreakdown {
+    /// Sum of all categories
+    pub fn total(&self) -> usize {
+        self.categories().iter().map(|(_, bytes)| bytes).sum()
+    }
+
+    /// Categories with their names, in declaration order
+    pub fn categories(&self) -> [(&'static str, usize); 10] {
+        [
+            ("markup", self.markup),
+            ("path data", self.path_data),
+            ("attributes", self.attributes),
+            ("styles", self.styles),
+            ("text", self.text),
+            ("comments", self.comments),
+            ("metadata", self.metadata),
+            ("editor data", self.editor),
+            ("images", self.images),
+            ("prolog", self.prolog),
+        ]
+    }
+
+    fn merge(&mut self, other: &ByteBreakdown) {
+        self.markup += other.markup;
+        self.path_data += other.path_data;
+        self.attributes += other.attributes;
+        self.styles += other.styles;
+        self.text += other.text;
+        self.comments += other.comments;
+        self.metadata += other.metadata;
+        self.editor += other.editor;
+        self.images += other.images;
+        self.prolog += other.prolog;
+    }
+
+    fn slot(&mut self, category: Category) -> &mut usize {
+        match category {
+            Category::Markup => &mut self.markup,
+            Category::PathData => &mut self.path_data,
+            Category::Attributes => &mut self.attributes,
+            Category::Styles => &mut self.styles,
+            Category::Text => &mut self.text,
+            Category::Comments => &mut self.comments,
+            Category::Metadata => &mut self.metadata,
+            Category::Editor => &mut self.editor,
+            Category::Images => &mut self.images,
+            Category::Prolog => &mut self.prolog,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Markup,
+    PathData,
+    Attributes,
+    Styles,
+    Text,
+    Comments,
+    Metadata,
+    Editor,
+    Images,
+    Prolog,
+}
+
+/// Count and size of one element type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ElementStats {
+    pub count: usize,
+    /// Bytes of the elements' own tags and attributes, without their content
+    pub bytes: usize,
+}
+
+/// Defined IDs and references to them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IdUsage {
+    /// Number of distinct IDs
+    pub defined: usize,
+    /// Number of those IDs that are referenced
+    pub referenced: usize,
+    /// IDs nothing references
+    pub unreferenced: Vec<String>,
+    /// Referenced IDs that no element has
+    pub missing: Vec<String>,
+}
+
+/// A child of `<defs>` that nothing references
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnusedDef {
+    pub element: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Serialized size, including content
+    pub bytes: usize,
+}
+
+/// An image embedded in a `data:` URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EmbeddedImage {
+    /// Media type, e.g. `image/png`
+    pub media_type: String,
+    /// Length of the URL
+    pub bytes: usize,
+}
+
+/// Statistics of one document, or the sum over several
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Analysis {
+    /// Number of documents analyzed
+    pub files: usize,
+    /// Length of the source text, when analyzed from source
+    pub source_bytes: usize,
+    pub bytes: ByteBreakdown,
+    /// Element name to statistics
+    pub elements: BTreeMap<String, ElementStats>,
+    /// Decimal places to how many numbers have that many
+    pub precision: BTreeMap<usize, usize>,
+    pub ids: IdUsage,
+    pub unused_defs: Vec<UnusedDef>,
+    pub images: Vec<EmbeddedImage>,
+}
+
+impl Analysis {
+    /// Add another analysis to this one
+    ///
+    /// Counts and sizes are summed, lists are concatenated.
+    pub fn merge(&mut self, other: &Analysis) {
+        self.files += other.files;
+        self.source_bytes += other.source_bytes;
+        self.bytes.merge(&other.bytes);
+        for (name, stats) in &other.elements {
+            let entry = self.elements.entry(name.clone()).or_default();
+            entry.count += stats.count;
+            entry.bytes += stats.bytes;
+        }
+        for (places, count) in &other.precision {
+            *self.precision.entry(*places).or_default() += count;
+        }
+        self.ids.defined += other.ids.defined;
+        self.ids.referenced += other.ids.referenced;
+        self.ids
+            .unreferenced
+            .extend(other.ids.unreferenced.iter().cloned());
+        self.ids.missing.extend(other.ids.missing.iter().cloned());
+        self.unused_defs.extend(other.unused_defs.iter().cloned());
+        self.images.extend(other.images.iter().cloned());
+    }
+
+    /// Human-readable tables
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        let total = self.bytes.total();
+        let share = |bytes: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                bytes as f64 * 100.0 / total as f64
+            }
+        };
+
+        if self.files > 1 {
+            let _ = writeln!(out, "Files: {}", self.files);
+        }
+        let _ = write!(out, "Size: {} bytes", total);
+        if self.source_bytes > 0 {
+            let _ = write!(out, " (source: {} bytes)", self.source_bytes);
+        }
+        out.push_str("\n\n");
+
+        let _ = writeln!(out, "{:<16} {:>10} {:>7}", "Category", "Bytes", "Share");
+        for (name, bytes) in self.bytes.categories() {
+            if bytes > 0 {
+                let _ = writeln!(out, "{:<16} {:>10} {:>6.1}%", name, bytes, share(bytes));
+            }
+        }
+
+        let mut elements: Vec<_> = self.elements.iter().collect();
+        elements.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(a.0.cmp(b.0)));
+        let _ = writeln!(out, "\n{:<16} {:>10} {:>10}", "Element", "Count", "Bytes");
+        for (name, stats) in elements {
+            let _ = writeln!(out, "{:<16} {:>10} {:>10}", name, stats.count, stats.bytes);
+        }
+
+        if !self.precision.is_empty() {
+            let histogram: Vec<String> = self
+                .precision
+                .iter()
+                .map(|(places, count)| format!("{}: {}", places, count))
+                .collect();
+            let _ = writeln!(out, "\nDecimal places: {}", histogram.join(", "));
+        }
+
+        let ids = &self.ids;
+        let _ = write!(
+            out,
+            "\nIDs: {} defined, {} referenced",
+            ids.defined, ids.referenced
+        );
+        if !ids.unreferenced.is_empty() {
+            let _ = write!(out, "; unreferenced: {}", ids.unreferenced.join(", "));
+        }
+        if !ids.missing.is_empty() {
+            let _ = write!(out, "; missing: {}", ids.missing.join(", "));
+        }
+        out.push('\n');
+
+        if !self.unused_defs.is_empty() {
+            let defs: Vec<String> = self
+                .unused_defs
+                .iter()
+                .map(|def| match &def.id {
+                    Some(id) => format!("<{}> #{} ({} bytes)", def.element, id, def.bytes),
+                    None => format!("<{}> ({} bytes)", def.element, def.bytes),
+                })
+                .collect();
+            let _ = writeln!(out, "Unused defs: {}", defs.join(", "));
+        }
+        if !self.images.is_empty() {
+            let images: Vec<String> = self
+                .images
+                .iter()
+                .map(|image| format!("{} ({} bytes)", image.media_type, image.bytes))
+                .collect();
+            let _ = writeln!(out, "Embedded images: {}", images.join(", "));
+        }
+
+        out
+    }
+}
+
+/// Analysis of a named file, for reports over several files
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAnalysis {
+    pub path: String,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<Analysis>,
+    /// Error message if the file could not be read or parsed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Analyses of several files and their sum
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AnalysisReport {
+    pub total: Analysis,
+    pub files: Vec<FileAnalysis>,
+}
+
+impl AnalysisReport {
+    /// Build a report, summing the analyses of all files that succeeded
+    pub fn new(files: Vec<FileAnalysis>) -> Self {
+        let mut total = Analysis::default();
+        for analysis in files.iter().filter_map(|file| file.analysis.as_ref()) {
+            total.merge(analysis);
+        }
+        Self { total, files }
+    }
+}
+
+/// Analyze SVG source, parsed with the parser options of `config`
+pub fn analyze_svg(input: &str, config: &Config) -> Result<Analysis, ParseError> {
+    let document = Parser::new()
+        .preserve_whitespace(config.parser.preserve_whitespace)
+        .preserve_comments(true)
+        .expand_entities(config.parser.expand_entities)
+        .entity_limits(config.parser.entity_limits.clone())
+        .parse(input)?;
+    Ok(Analysis {
+        source_bytes: input.len(),
+        ..analyze(&document)
+    })
+}
+
+/// Analyze a parsed document
+pub fn analyze(document: &Document) -> Analysis {
+    let mut analysis = Analysis {
+        files: 1,
+        ..Default::default()
+    };
+
+    for node in document.prologue.iter().chain(&document.epilogue) {
+        let category = match node {
+            Node::Comment(_) => Category::Comments,
+            _ => Category::Prolog,
+        };
+        *analysis.bytes.slot(category) += node_bytes(node);
+    }
+    if let Some(version) = &document.metadata.version {
+        // <?xml version="..."?>, with encoding="UTF-8" for transcoded input
+        let encoding = document.metadata.encoding.is_some();
+        analysis.bytes.prolog += version.len() + 18 + if encoding { 17 } else { 0 };
+    }
+
+    let mut walker = Walker {
+        analysis: &mut analysis,
+        ids: Vec::new(),
+        references: HashSet::new(),
+    };
+    walker.element(&document.root, &NamespaceScope::new(), None);
+    let Walker {
+        ids, references, ..
+    } = walker;
+
+    let defined: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    let mut unreferenced: Vec<String> = defined
+        .iter()
+        .filter(|id| !references.contains(**id))
+        .map(|id| id.to_string())
+        .collect();
+    unreferenced.sort();
+    let mut missing: Vec<String> = references
+        .iter()
+        .filter(|id| !defined.contains(id.as_str()))
+        .cloned()
+        .collect();
+    missing.sort();
+    analysis.ids = IdUsage {
+        defined: defined.len(),
+        referenced: defined.len() - unreferenced.len(),
+        unreferenced,
+        missing,
+    };
+
+    find_unused_defs(&document.root, &references, &mut analysis.unused_defs);
+    analysis
+}
+
+struct Walker<'a> {
+    analysis: &'a mut Analysis,
+    ids: Vec<String>,
+    references: HashSet<String>,
+}
+
+impl Walker<'_> {
+    /// Count `element` and its content; `context` is the category everything
+    /// inside a `<metadata>` or editor element belongs to
+    fn element(&mut self, element: &Element, scope: &NamespaceScope, context: Option<Category>) {
+        let scope = scope.enter(element);
+        let is_editor =
+            |uri: Option<String>| uri.is_some_and(|uri| EDITOR_NAMESPACES.contains(uri.as_str()));
+
+        let context = context.or(if is_editor(element.qname(&scope).uri) {
+            Some(Category::Editor)
+        } else if element.name == "metadata" {
+            Some(Category::Metadata)
+        } else if element.name == "style" {
+            Some(Category::Styles)
+        } else {
+            None
+        });
+
+        let mut own_bytes = tag_bytes(element);
+        *self.slot(context, Category::Markup) += own_bytes;
+
+        for (name, value) in &element.attributes {
+            let bytes = attribute_bytes(name, value);
+            own_bytes += bytes;
+
+            let category = if (name.starts_with("xmlns:")
+                && EDITOR_NAMESPACES.contains(value.as_str()))
+                || is_editor(scope.attribute_name(name).uri)
+            {
+                Category::Editor
+            } else if name == "style" {
+                Category::Styles
+            } else if name == "d" || name == "points" {
+                Category::PathData
+            } else if scope.attribute_name(name).is_href() && value.starts_with("data:image/") {
+                let media_type = value["data:".len()..]
+                    .split([';', ','])
+                    .next()
+                    .unwrap_or_default();
+                self.analysis.images.push(EmbeddedImage {
+                    media_type: media_type.to_string(),
+                    bytes: value.len(),
+                });
+                Category::Images
+            } else {
+                Category::Attributes
+            };
+            *self.slot(context, category) += bytes;
+
+            if name == "id" {
+                self.ids.push(value.clone());
+            }
+            self.references.extend(find_references(&scope, name, value));
+            if NUMBER_LIST_ATTRS.contains(&name.as_str()) || NUMERIC_VALUE_RE.is_match(value) {
+                self.count_numbers(value);
+            }
+        }
+
+        let stats = self
+            .analysis
+            .elements
+            .entry(element.name.clone())
+            .or_default();
+        stats.count += 1;
+        stats.bytes += own_bytes;
+
+        for child in &element.children {
+            match child {
+                Node::Element(child) => self.element(child, &scope, context),
+                Node::Text(text) | Node::CData(text) => {
+                    if element.name == "style" {
+                        self.references
+                            .extend(find_references(&scope, "style", text));
+                    }
+                    *self.slot(context, Category::Text) += node_bytes(child);
+                }
+                Node::Comment(_) => *self.slot(context, Category::Comments) += node_bytes(child),
+                _ => *self.slot(context, Category::Prolog) += node_bytes(child),
+            }
+        }
+    }
+
+    fn slot(&mut self, context: Option<Category>, category: Category) -> &mut usize {
+        self.analysis.bytes.slot(context.unwrap_or(category))
+    }
+
+    fn count_numbers(&mut self, value: &str) {
+        for number in NUMBER_RE.captures_iter(value) {
+            let places = number.get(1).map_or(0, |fraction| fraction.len());
+            *self.analysis.precision.entry(places).or_default() += 1;
+        }
+    }
+}
+
+/// Collect `<defs>` children that neither are nor contain a referenced element
+fn find_unused_defs(element: &Element, references: &HashSet<String>, unused: &mut Vec<UnusedDef>) {
+    fn is_referenced(element: &Element, references: &HashSet<String>) -> bool {
+        element.attr("id").is_some_and(|id| references.contains(id))
+            || element
+                .child_elements()
+                .any(|child| is_referenced(child, references))
+    }
+
+    for child in element.child_elements() {
+        if element.name == "defs" && !is_referenced(child, references) {
+            unused.push(UnusedDef {
+                element: child.name.clone(),
+                id: child.attr("id").cloned(),
+                bytes: element_bytes(child),
+            });
+        } else {
+            find_unused_defs(child, references, unused);
+        }
+    }
+}
+
+/// `<name>` and `</name>`, or `<name/>`
+fn tag_bytes(element: &Element) -> usize {
+    if element.children.is_empty() {
+        element.name.len() + 3
+    } else {
+        2 * element.name.len() + 5
+    }
+}
+
+/// ` name="value"`
+fn attribute_bytes(name: &str, value: &str) -> usize {
+    name.len() + value.len() + 4
+}
+
+fn element_bytes(element: &Element) -> usize {
+    tag_bytes(element)
+        + element
+            .attributes
+            .iter()
+            .map(|(name, value)| attribute_bytes(name, value))
+            .sum::<usize>()
+        + element.children.iter().map(node_bytes).sum::<usize>()
+}
+
+fn node_bytes(node: &Node) -> usize {
+    match node {
+        Node::Element(element) => element_bytes(element),
+        Node::Text(text) => text.len(),
+        Node::CData(text) => text.len() + 12,
+        Node::Comment(comment) => comment.len() + 7,
+        Node::ProcessingInstruction { target, data } => target.len() + data.len() + 5,
+        Node::DocType(doctype) => doctype.len() + 11,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze_str(svg: &str) -> Analysis {
+        analyze_svg(svg, &Config::default()).unwrap()
+    }
+
+    #[test]
+    fn test_byte_breakdown() {
+        let svg = r#"<svg xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" inkscape:version="1"><!--x--><metadata><rdf/></metadata><path d="M0 0h10" style="fill:red"/><image href="data:image/png;base64,AAAA"/><text>Hi</text></svg>"#;
+        let analysis = analyze_str(svg);
+        let bytes = &analysis.bytes;
+
+        assert_eq!(bytes.total(), svg.len());
+        assert_eq!(
+            bytes.editor,
+            r#" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" inkscape:version="1""#
+                .len()
+        );
+        assert_eq!(bytes.metadata, "<metadata><rdf/></metadata>".len());
+        assert_eq!(bytes.path_data, r#" d="M0 0h10""#.len());
+        assert_eq!(bytes.styles, r#" style="fill:red""#.len());
+        assert_eq!(bytes.images, r#" href="data:image/png;base64,AAAA""#.len());
+        assert_eq!(bytes.comments, "<!--x-->".len());
+        assert_eq!(bytes.text, 2);
+        assert_eq!(
+            analysis.images,
+            [EmbeddedImage {
+                media_type: "image/png".to_string(),
+                bytes: 26
+            }]
+        );
+        assert_eq!(analysis.elements["path"].count, 1);
+        assert_eq!(
+            analysis.elements["path"].bytes,
+            svg.find("<image").unwrap() - svg.find("<path").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_precision_and_ids() {
+        let analysis = analyze_str(
+            r##"<svg viewBox="0 0 24 24"><defs><linearGradient id="used"/><linearGradient id="unused"/><g><clipPath id="c"/></g></defs><path d="M1.5.25l2.125 3" fill="url(#used)" clip-path="url(#missing)" class="icon-2"/></svg>"##,
+        );
+
+        assert_eq!(
+            analysis.precision,
+            BTreeMap::from([(0, 5), (1, 1), (2, 1), (3, 1)])
+        );
+        assert_eq!(analysis.ids.defined, 3);
+        assert_eq!(analysis.ids.referenced, 1);
+        assert_eq!(analysis.ids.unreferenced, ["c", "unused"]);
+        assert_eq!(analysis.ids.missing, ["missing"]);
+        let unused: Vec<_> = analysis
+            .unused_defs
+            .iter()
+            .map(|def| (def.element.as_str(), def.id.as_deref()))
+            .collect();
+        assert_eq!(unused, [("linearGradient", Some("unused")), ("g", None)]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut total = analyze_str(r#"<svg><path d="M0 0"/></svg>"#);
+        total.merge(&analyze_str(
+            r#"<svg><path d="M0 0"/><path d="M1 1"/></svg>"#,
+        ));
+
+        assert_eq!(total.files, 2);
+        assert_eq!(total.elements["path"].count, 3);
+        assert_eq!(total.elements["svg"].count, 2);
+        assert_eq!(total.precision[&0], 6);
+        assert!(total.to_table().starts_with("Files: 2\nSize: "));
+    }
+}
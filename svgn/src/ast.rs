@@ -6,8 +6,9 @@
 //! in memory. The AST is designed for efficient traversal and mutation during
 //! optimization passes.
 
+use crate::plugins::sanitize::SanitizeReport;
 use indexmap::IndexMap;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// A complete SVG document
 #[derive(Debug, Clone, PartialEq)]
@@ -31,6 +32,13 @@ pub struct DocumentMetadata {
     pub encoding: Option<String>,
     /// XML version
     pub version: Option<String>,
+    /// Source text of the XML declaration (recorded when preserving formatting)
+    pub declaration: Option<String>,
+    /// Entity references left as written, such as external entities or
+    /// all declared entities when expansion is disabled
+    pub unexpanded_entities: BTreeSet<String>,
+    /// What the `sanitize` plugin removed, if it ran
+    pub sanitize_report: Option<SanitizeReport>,
 }
 
 /// An XML/SVG element
@@ -42,8 +50,81 @@ pub struct Element {
     pub attributes: IndexMap<String, String>,
     /// Child nodes
     pub children: Vec<Node>,
-    /// Namespace declarations
-    pub namespaces: HashMap<String, String>,
+    /// Source formatting (recorded when preserving formatting)
+    ///
+    /// Plugins that tidy up whitespace-only text leave it alone when this is
+    /// set, since that whitespace is the source layout being kept.
+    pub format: Option<Box<ElementFormat>>,
+}
+
+/// How an element was written in the source document
+///
+/// Recorded by the parser in preserve-formatting mode so the stringifier can
+/// re-emit unmodified elements byte-for-byte and keep the attribute spacing
+/// and quoting of modified ones.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ElementFormat {
+    /// Tag name as parsed
+    pub name: String,
+    /// Attributes in source order
+    pub attributes: Vec<AttributeFormat>,
+    /// Whitespace between the last attribute and `>` or `/>`
+    pub trailing: String,
+    /// Source text of the start tag, including `<` and `>` or `/>`
+    pub start_tag: String,
+    /// Source text of the end tag; `None` for self-closing elements
+    pub end_tag: Option<String>,
+    /// Source text of text children, keyed by their unescaped content
+    pub texts: Vec<(String, String)>,
+}
+
+/// How an attribute was written in the source document
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AttributeFormat {
+    /// Attribute name
+    pub name: String,
+    /// Unescaped value as parsed
+    pub value: String,
+    /// Whitespace before the attribute
+    pub leading: String,
+    /// Source text from the name to the closing quote, e.g. `x = '1'`
+    pub source: String,
+}
+
+impl ElementFormat {
+    /// Whether the element's start tag still matches the source exactly
+    pub fn matches(&self, element: &Element) -> bool {
+        self.name == element.name
+            && self.attributes.len() == element.attributes.len()
+            && self
+                .attributes
+                .iter()
+                .zip(&element.attributes)
+                .all(|(format, (name, value))| &format.name == name && &format.value == value)
+    }
+
+    /// Whether the element was written as `<name/>`
+    pub fn is_self_closing(&self) -> bool {
+        self.end_tag.is_none()
+    }
+
+    /// Source text of a text child with the given content
+    pub fn text_source(&self, text: &str) -> Option<&str> {
+        self.texts
+            .iter()
+            .find(|(content, _)| content == text)
+            .map(|(_, source)| source.as_str())
+    }
+}
+
+impl AttributeFormat {
+    /// Quote character used in the source
+    pub fn quote(&self) -> char {
+        match self.source.chars().last() {
+            Some('\'') => '\'',
+            _ => '"',
+        }
+    }
 }
 
 /// A node in the SVG tree
@@ -92,7 +173,7 @@ impl Element {
             name: name.to_string(),
             attributes: IndexMap::new(),
             children: Vec::new(),
-            namespaces: HashMap::new(),
+            format: None,
         }
     }
 
@@ -161,6 +242,184 @@ impl Element {
             _ => false,
         })
     }
+
+    /// Namespace declarations made on this element, as `(prefix, uri)` pairs
+    ///
+    /// The default namespace (`xmlns="..."`) has an empty prefix.
+    pub fn namespace_declarations(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes.iter().filter_map(|(name, uri)| {
+            if name == "xmlns" {
+                Some(("", uri.as_str()))
+            } else {
+                name.strip_prefix("xmlns:")
+                    .map(|prefix| (prefix, uri.as_str()))
+            }
+        })
+    }
+
+    /// Resolve the element's name against the namespaces in scope
+    pub fn qname(&self, scope: &NamespaceScope) -> QName {
+        scope.element_name(&self.name)
+    }
+
+    /// Find an attribute by namespace URI and local name, whatever its prefix
+    ///
+    /// Returns the attribute's name as written and its value.
+    pub fn attr_ns(
+        &self,
+        scope: &NamespaceScope,
+        uri: &str,
+        local: &str,
+    ) -> Option<(&String, &String)> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| scope.attribute_name(name).is(uri, local))
+    }
+}
+
+/// Well-known namespace URIs
+pub mod ns {
+    /// SVG
+    pub const SVG: &str = "http://www.w3.org/2000/svg";
+    /// XLink, deprecated in SVG 2 but still common in `xlink:href`
+    pub const XLINK: &str = "http://www.w3.org/1999/xlink";
+    /// XHTML, used for HTML content inside `<foreignObject>`
+    pub const XHTML: &str = "http://www.w3.org/1999/xhtml";
+    /// The `xml:` prefix, bound implicitly
+    pub const XML: &str = "http://www.w3.org/XML/1998/namespace";
+    /// Namespace declarations (`xmlns` and `xmlns:*`), bound implicitly
+    pub const XMLNS: &str = "http://www.w3.org/2000/xmlns/";
+}
+
+/// A namespace-qualified element or attribute name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QName {
+    /// Prefix as written, if any
+    pub prefix: Option<String>,
+    /// Local part of the name
+    pub local: String,
+    /// Namespace URI the name resolves to, if any
+    pub uri: Option<String>,
+}
+
+impl QName {
+    /// Whether the name is `local` in the namespace `uri`
+    pub fn is(&self, uri: &str, local: &str) -> bool {
+        self.local == local && self.in_namespace(uri)
+    }
+
+    /// Whether the name is in the namespace `uri`
+    pub fn in_namespace(&self, uri: &str) -> bool {
+        self.uri.as_deref() == Some(uri)
+    }
+
+    /// Whether the attribute name is a link, `href` or XLink's `href`
+    pub fn is_href(&self) -> bool {
+        self.local == "href" && (self.prefix.is_none() || self.in_namespace(ns::XLINK))
+    }
+}
+
+/// Namespace prefixes in scope at some point of the tree
+///
+/// Start from [`NamespaceScope::new`] and call [`NamespaceScope::enter`] for
+/// each element on the way down, so that declarations on descendants shadow
+/// those of their ancestors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceScope {
+    /// Prefix to URI; the default namespace has an empty prefix
+    bindings: HashMap<String, String>,
+}
+
+impl NamespaceScope {
+    /// Create a scope with only the implicit `xml` and `xmlns` prefixes bound
+    ///
+    /// `xlink` is bound to the XLink namespace as well, the way HTML parsers
+    /// do, since `xlink:href` is often used without declaring the prefix. A
+    /// document declaring `xmlns:xlink` itself overrides this.
+    pub fn new() -> Self {
+        Self {
+            bindings: HashMap::from([
+                ("xml".to_string(), ns::XML.to_string()),
+                ("xmlns".to_string(), ns::XMLNS.to_string()),
+                ("xlink".to_string(), ns::XLINK.to_string()),
+            ]),
+        }
+    }
+
+    /// Scope inside `element`, including its own declarations
+    pub fn enter(&self, element: &Element) -> Self {
+        let mut scope = self.clone();
+        for (prefix, uri) in element.namespace_declarations() {
+            if uri.is_empty() && !prefix.is_empty() {
+                // `xmlns:p=""` is not allowed in XML 1.0; treat it as unbinding
+                scope.bindings.remove(prefix);
+            } else {
+                scope.bindings.insert(prefix.to_string(), uri.to_string());
+            }
+        }
+        scope
+    }
+
+    /// URI bound to `prefix` (empty for the default namespace)
+    pub fn uri(&self, prefix: &str) -> Option<&str> {
+        self.bindings
+            .get(prefix)
+            .map(String::as_str)
+            .filter(|uri| !uri.is_empty())
+    }
+
+    /// Prefixes bound to `uri`, excluding the default namespace
+    pub fn prefixes_for<'a>(&'a self, uri: &'a str) -> impl Iterator<Item = &'a str> {
+        self.bindings
+            .iter()
+            .filter(move |(prefix, bound)| !prefix.is_empty() && *bound == uri)
+            .map(|(prefix, _)| prefix.as_str())
+    }
+
+    /// Resolve an element name; unprefixed names are in the default namespace
+    pub fn element_name(&self, name: &str) -> QName {
+        match name.split_once(':') {
+            Some((prefix, local)) => self.prefixed(prefix, local),
+            None => QName {
+                prefix: None,
+                local: name.to_string(),
+                uri: self.uri("").map(str::to_string),
+            },
+        }
+    }
+
+    /// Resolve an attribute name; unprefixed attributes have no namespace
+    pub fn attribute_name(&self, name: &str) -> QName {
+        if name == "xmlns" {
+            return QName {
+                prefix: None,
+                local: name.to_string(),
+                uri: Some(ns::XMLNS.to_string()),
+            };
+        }
+        match name.split_once(':') {
+            Some((prefix, local)) => self.prefixed(prefix, local),
+            None => QName {
+                prefix: None,
+                local: name.to_string(),
+                uri: None,
+            },
+        }
+    }
+
+    fn prefixed(&self, prefix: &str, local: &str) -> QName {
+        QName {
+            prefix: Some(prefix.to_string()),
+            local: local.to_string(),
+            uri: self.uri(prefix).map(str::to_string),
+        }
+    }
+}
+
+impl Default for NamespaceScope {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Node {
@@ -268,4 +527,43 @@ mod tests {
         element.add_child(Node::Text("content".to_string()));
         assert!(!element.is_whitespace_only());
     }
+
+    #[test]
+    fn test_namespace_scope_resolution() {
+        let mut root = Element::new("svg");
+        root.set_attr("xmlns".to_string(), ns::SVG.to_string());
+        root.set_attr("xmlns:x".to_string(), ns::XLINK.to_string());
+        let mut child = Element::new("x:foo");
+        child.set_attr("xmlns:x".to_string(), "http://example.com/x".to_string());
+
+        let scope = NamespaceScope::new().enter(&root);
+        assert!(root.qname(&scope).is(ns::SVG, "svg"));
+        assert!(scope.attribute_name("x:href").is(ns::XLINK, "href"));
+        assert_eq!(scope.attribute_name("href").uri, None);
+        assert!(scope.attribute_name("xml:space").in_namespace(ns::XML));
+        assert!(scope.attribute_name("xmlns:x").in_namespace(ns::XMLNS));
+        assert!(scope.prefixes_for(ns::XLINK).any(|prefix| prefix == "x"));
+
+        // Declarations on descendants shadow those of their ancestors
+        let inner = scope.enter(&child);
+        assert!(child.qname(&inner).is("http://example.com/x", "foo"));
+        assert_eq!(scope.uri("x"), Some(ns::XLINK));
+    }
+
+    #[test]
+    fn test_attr_ns_ignores_prefix() {
+        let mut element = Element::new("use");
+        element.set_attr("xmlns:l".to_string(), ns::XLINK.to_string());
+        element.set_attr("l:href".to_string(), "#a".to_string());
+        let scope = NamespaceScope::new().enter(&element);
+
+        let (name, value) = element.attr_ns(&scope, ns::XLINK, "href").unwrap();
+        assert_eq!(name, "l:href");
+        assert_eq!(value, "#a");
+        assert!(element.attr_ns(&scope, ns::XML, "href").is_none());
+
+        assert!(scope.attribute_name("href").is_href());
+        assert!(scope.attribute_name("l:href").is_href());
+        assert!(!scope.attribute_name("unbound:href").is_href());
+    }
 }
@@ -6,10 +6,26 @@
 //! options for SVG optimization.
 
 use clap::{Arg, ArgAction, Command};
+use notify::{PollWatcher, RecursiveMode, Watcher};
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
-use svgn::{optimize_with_config, Config, VERSION};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use svgn::cache::Cache;
+use svgn::export::{self, OutputFormat};
+use svgn::import::InputFormat;
+use svgn::optimizer::{decode_input, OptimizationInfo, OptimizationResult};
+use svgn::report::{FileReport, FileStatus, Report, ReportFormat};
+use svgn::svgz::{self, SvgzOptions, DEFAULT_COMPRESSION_LEVEL};
+use svgn::{
+    optimize_bytes, optimize_bytes_with_registry, optimize_with_config, Config, OptimizeOptions,
+    PluginRegistry, VERSION,
+};
 
 fn main() {
     let matches = Command::new("svgn")
@@ -56,6 +72,12 @@ fn main() {
                 .value_name("OUTPUT")
                 .num_args(1..),
         )
+        .arg(
+            Arg::new("output-ext")
+                .help("Extension for output files instead of .svg, e.g. \".min.svg\"")
+                .long("output-ext")
+                .value_name("EXT"),
+        )
         .arg(
             Arg::new("precision")
                 .help("Set number of digits in the fractional part, overrides plugins params")
@@ -90,6 +112,31 @@ fn main() {
                 .long("final-newline")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("preserve-formatting")
+                .help("Keep the source formatting of elements the plugins leave unchanged")
+                .long("preserve-formatting")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("xml-declaration")
+                .help("XML declaration handling: keep, strip, force")
+                .long("xml-declaration")
+                .value_name("MODE")
+                .value_parser(["keep", "strip", "force"]),
+        )
+        .arg(
+            Arg::new("html")
+                .help("Output SVG for inlining into HTML5 (no XML declaration, DOCTYPE or xmlns)")
+                .long("html")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("keep-entities")
+                .help("Keep references to entities declared in the DOCTYPE instead of expanding them, along with the DOCTYPE")
+                .long("keep-entities")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("config")
                 .help("Custom config file, only .js is supported")
@@ -110,6 +157,25 @@ fn main() {
                 .value_name("PLUGIN")
                 .action(ArgAction::Append),
         )
+        .arg(
+            Arg::new("a11y")
+                .help("Keep <title>/<desc> that name or describe the image, keep aria-* and role attributes, and add role=\"img\" or aria-hidden=\"true\" to the root")
+                .long("a11y")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("a11y-lint")
+                .help("Write nothing; report inputs that have neither an accessible name nor aria-hidden=\"true\", or broken ARIA references")
+                .long("a11y-lint")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["output", "check", "diff", "stream"]),
+        )
+        .arg(
+            Arg::new("sanitize")
+                .help("Remove scripts, external references and other active content before optimizing, and list what was removed")
+                .long("sanitize")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("datauri")
                 .help("Output as Data URI string (base64), URI encoded (enc) or unencoded (unenc)")
@@ -117,6 +183,59 @@ fn main() {
                 .value_name("FORMAT")
                 .value_parser(["base64", "enc", "unenc"]),
         )
+        .arg(
+            Arg::new("input-format")
+                .help("Read SVG or an Android VectorDrawable (vector-drawable); detected from the file extension if not given, where .xml is a VectorDrawable. With vector-drawable, folders are searched for .xml files")
+                .long("input-format")
+                .value_name("FORMAT")
+                .value_parser(["svg", "vector-drawable"]),
+        )
+        .arg(
+            Arg::new("format")
+                .help("Write SVG, a React component in JavaScript (jsx) or TypeScript (tsx), a Vue or Svelte component, an Android VectorDrawable (vector-drawable) or a Jetpack Compose ImageVector in Kotlin (compose); derived output files get the format's extension")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["svg", "jsx", "tsx", "vue", "svelte", "vector-drawable", "compose"])
+                .conflicts_with_all(["datauri", "svgz", "stream"]),
+        )
+        .arg(
+            Arg::new("title-prop")
+                .help("Use with '--format jsx|tsx'. Give the component a title prop that sets its <title>")
+                .long("title-prop")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("size-prop")
+                .help("Use with '--format vue|svelte'. Give the component a size prop that sets its width and height")
+                .long("size-prop")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("package")
+                .help("Use with '--format compose'. Kotlin package of the generated files")
+                .long("package")
+                .value_name("NAME"),
+        )
+        .arg(
+            Arg::new("svgz")
+                .help("Gzip-compress the output (SVGZ); derived output files get the .svgz extension")
+                .long("svgz")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("compression-level")
+                .help("Gzip compression level for SVGZ output, 0-9 (default: 9)")
+                .long("compression-level")
+                .value_name("LEVEL")
+                .value_parser(clap::value_parser!(u32).range(0..=9)),
+        )
+        .arg(
+            Arg::new("size-metric")
+                .help("Tune ordering and encoding choices for the smallest raw, gzip or brotli output (slower)")
+                .long("size-metric")
+                .value_name("METRIC")
+                .value_parser(["raw", "gzip", "brotli"]),
+        )
         .arg(
             Arg::new("multipass")
                 .help("Pass over SVGs multiple times to ensure all optimizations are applied")
@@ -137,6 +256,84 @@ fn main() {
                 .value_name("PATTERN")
                 .action(ArgAction::Append),
         )
+        .arg(
+            Arg::new("jobs")
+                .help("Number of files to optimize in parallel (default: number of CPUs)")
+                .short('j')
+                .long("jobs")
+                .value_name("INTEGER")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("check")
+                .help("Write nothing; exit with an error if any input is not already optimized")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["output", "datauri"]),
+        )
+        .arg(
+            Arg::new("diff")
+                .help("Write nothing; print a diff between each input and its optimized output")
+                .long("diff")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["output", "datauri", "check"]),
+        )
+        .arg(
+            Arg::new("diff-format")
+                .help("Use with '--diff'. unified: pretty-printed unified diff, json: changes made by each plugin")
+                .long("diff-format")
+                .value_name("FORMAT")
+                .value_parser(["unified", "json"])
+                .default_value("unified")
+                .requires("diff"),
+        )
+        .arg(
+            Arg::new("stream")
+                .help("Optimize a single file or stdin as it is read, running only plugins that work on one element at a time. For very large files.")
+                .long("stream")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["folder", "string", "check", "diff", "svgz", "multipass"]),
+        )
+        .arg(
+            Arg::new("watch")
+                .help("Use with '--folder'. Keep running and re-optimize files as they change.")
+                .long("watch")
+                .action(ArgAction::SetTrue)
+                .requires("folder")
+                .conflicts_with_all(["check", "diff"]),
+        )
+        .arg(
+            Arg::new("cache")
+                .help("Reuse results for unchanged files from an on-disk cache (.svgn-cache)")
+                .long("cache")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("cache-location")
+                .help("Directory of the result cache; implies '--cache'")
+                .long("cache-location")
+                .value_name("DIR"),
+        )
+        .arg(
+            Arg::new("no-cache")
+                .help("Disable the result cache, overriding '--cache' and '--cache-location'")
+                .long("no-cache")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("report")
+                .help("Write a machine-readable report of a file or folder run")
+                .long("report")
+                .value_name("FORMAT")
+                .value_parser(["json", "csv", "junit"]),
+        )
+        .arg(
+            Arg::new("report-file")
+                .help("Use with '--report'. File to write the report to (default: STDOUT)")
+                .long("report-file")
+                .value_name("FILE")
+                .requires("report"),
+        )
         .arg(
             Arg::new("quiet")
                 .help("Only output error messages, not regular status messages")
@@ -156,6 +353,111 @@ fn main() {
                 .long("no-color")
                 .action(ArgAction::SetTrue),
         )
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("lint")
+                .about("Check SVG files against asset rules without modifying them")
+                .arg(
+                    Arg::new("PATH")
+                        .help("Input files or folders, \"-\" for STDIN")
+                        .value_name("PATH")
+                        .num_args(1..)
+                        .required_unless_present("show-rules"),
+                )
+                .arg(
+                    Arg::new("recursive")
+                        .help("Lint *.svg files in folders recursively")
+                        .short('r')
+                        .long("recursive")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("exclude")
+                        .help("Exclude files in folders matching regular expression pattern")
+                        .long("exclude")
+                        .value_name("PATTERN")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("config")
+                        .help("Config file with lint rule severities ([lint.rules])")
+                        .long("config")
+                        .value_name("CONFIG"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .help("Output format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .value_parser(["text", "json", "sarif"])
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("File to write the results to (default: STDOUT)")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("fix")
+                        .help("Fix violations in place with the plugins for the violated rules, then report what is left")
+                        .long("fix")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("show-rules")
+                        .help("Show available rules with their severities and exit")
+                        .long("show-rules")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("analyze")
+                .about("Show what the bytes of SVG files are spent on, summed over all inputs")
+                .arg(
+                    Arg::new("PATH")
+                        .help("Input files or folders, \"-\" for STDIN")
+                        .value_name("PATH")
+                        .num_args(1..)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("recursive")
+                        .help("Analyze *.svg files in folders recursively")
+                        .short('r')
+                        .long("recursive")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("exclude")
+                        .help("Exclude files in folders matching regular expression pattern")
+                        .long("exclude")
+                        .value_name("PATTERN")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("config")
+                        .help("Config file with parser options")
+                        .long("config")
+                        .value_name("CONFIG"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .help("Output format; JSON includes each file's analysis")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .value_parser(["table", "json"])
+                        .default_value("table"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("File to write the results to (default: STDOUT)")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE"),
+                ),
+        )
         .get_matches();
 
     // Check if we should show plugins and exit
@@ -164,7 +466,11 @@ fn main() {
         std::process::exit(0);
     }
 
-    let result = run_cli(matches);
+    let result = match matches.subcommand() {
+        Some(("lint", lint_matches)) => run_lint(lint_matches),
+        Some(("analyze", analyze_matches)) => run_analyze(analyze_matches),
+        _ => run_cli(matches),
+    };
 
     if let Err(e) = result {
         eprintln!("Error: {}", e);
@@ -174,9 +480,121 @@ fn main() {
 
 fn run_cli(matches: clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
     let quiet = matches.get_flag("quiet");
-    let no_color = matches.get_flag("no-color") || std::env::var("NO_COLOR").is_ok();
+    let mut config = load_config(&matches)?;
+    // --check and --diff compare the optimized SVG with its source, whatever
+    // format it would be written in
+    if matches.get_flag("check") || matches.get_flag("diff") {
+        config.format = OutputFormat::Svg;
+    }
+    let batch_options = BatchOptions {
+        quiet,
+        check: matches.get_flag("check"),
+        diff: if matches.get_flag("diff") {
+            match matches.get_one::<String>("diff-format").map(|s| s.as_str()) {
+                Some("json") => Some(DiffFormat::Json),
+                _ => Some(DiffFormat::Unified),
+            }
+        } else {
+            None
+        },
+        jobs: match matches.get_one::<usize>("jobs") {
+            Some(&jobs) if jobs > 0 => jobs,
+            _ => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        },
+        output_extension: match matches.get_one::<String>("output-ext") {
+            Some(ext) if ext.starts_with('.') => Some(ext.clone()),
+            Some(ext) => Some(format!(".{}", ext)),
+            None if matches.get_flag("svgz") => Some(".svgz".to_string()),
+            None if !config.format.is_svg() => Some(config.format.extension().to_string()),
+            // SVG converted from a VectorDrawable gets the .svg extension
+            None if reads_vector_drawables(&matches, &config) => Some(".svg".to_string()),
+            None => None,
+        },
+        vector_drawable_folders: config.input_format == Some(InputFormat::VectorDrawable),
+        compression_level: matches.get_one::<u32>("compression-level").copied(),
+        cache: match matches.get_one::<String>("cache-location") {
+            _ if matches.get_flag("no-cache") => None,
+            Some(location) => Some(Cache::new(location)),
+            None if matches.get_flag("cache") => Some(Cache::default()),
+            None => None,
+        },
+        report: matches.get_one::<String>("report").map(|format| {
+            let format = match format.as_str() {
+                "csv" => ReportFormat::Csv,
+                "junit" => ReportFormat::Junit,
+                _ => ReportFormat::Json,
+            };
+            (
+                format,
+                matches.get_one::<String>("report-file").map(PathBuf::from),
+            )
+        }),
+    };
+
+    // Determine input mode
+    let (input_mode, output_mode) = determine_io_mode(&matches)?;
+
+    match input_mode {
+        input_mode if matches.get_flag("a11y-lint") => {
+            lint_a11y(input_mode, &matches, &config, quiet)?;
+        }
+        input_mode if matches.get_flag("stream") => {
+            process_stream(input_mode, output_mode, &config, &batch_options)?;
+        }
+        InputMode::String(svg_string) => {
+            process_string(&svg_string, None, output_mode, &config, &batch_options)?;
+        }
+        InputMode::Stdin => {
+            let mut buffer = Vec::new();
+            io::stdin().read_to_end(&mut buffer)?;
+            let content = decode_input(&buffer)?;
+            process_string(
+                &content,
+                Some(&buffer),
+                output_mode,
+                &config,
+                &batch_options,
+            )?;
+        }
+        InputMode::Files(files) => {
+            process_files(&files, output_mode, &config, &batch_options)?;
+        }
+        InputMode::Folder(folder, recursive) if matches.get_flag("watch") => {
+            let exclude_patterns = matches
+                .get_many::<String>("exclude")
+                .map(|v| v.map(|s| s.as_str()).collect::<Vec<_>>())
+                .unwrap_or_default();
+            watch_folder(
+                &folder,
+                output_mode,
+                &matches,
+                &batch_options,
+                recursive,
+                &exclude_patterns,
+            )?;
+        }
+        InputMode::Folder(folder, recursive) => {
+            let exclude_patterns = matches
+                .get_many::<String>("exclude")
+                .map(|v| v.map(|s| s.as_str()).collect::<Vec<_>>())
+                .unwrap_or_default();
+            process_folder(
+                &folder,
+                output_mode,
+                &config,
+                &batch_options,
+                recursive,
+                &exclude_patterns,
+            )?;
+        }
+    }
+
+    Ok(())
+}
 
-    // Load configuration
+/// Load the config file (explicit or from the current directory) and apply
+/// command-line overrides
+fn load_config(matches: &clap::ArgMatches) -> Result<Config, Box<dyn std::error::Error>> {
     let mut config = if let Some(config_path) = matches.get_one::<String>("config") {
         Config::from_file(config_path)?
     } else {
@@ -206,10 +624,69 @@ fn run_cli(matches: clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>>
         config.js2svg.final_newline = true;
     }
 
+    if matches.get_flag("preserve-formatting") {
+        config.js2svg.preserve_formatting = true;
+    }
+
+    if let Some(mode) = matches.get_one::<String>("xml-declaration") {
+        use svgn::config::XmlDeclaration;
+        config.js2svg.xml_declaration = match mode.as_str() {
+            "keep" => XmlDeclaration::Keep,
+            "strip" => XmlDeclaration::Strip,
+            "force" => XmlDeclaration::Force,
+            _ => unreachable!(), // Clap validates this
+        };
+    }
+
+    if matches.get_flag("html") {
+        config.js2svg.html = true;
+    }
+
+    if matches.get_flag("keep-entities") {
+        config.parser.expand_entities = false;
+    }
+
     if matches.get_flag("multipass") {
         config.multipass = true;
     }
 
+    if matches.get_flag("a11y") {
+        config.a11y = true;
+    }
+
+    if let Some(format) = matches.get_one::<String>("input-format") {
+        config.input_format = Some(match format.as_str() {
+            "svg" => InputFormat::Svg,
+            "vector-drawable" => InputFormat::VectorDrawable,
+            _ => unreachable!(), // Clap validates this
+        });
+    }
+
+    if let Some(format) = matches.get_one::<String>("format") {
+        config.format = match format.as_str() {
+            "svg" => OutputFormat::Svg,
+            "jsx" => OutputFormat::Jsx,
+            "tsx" => OutputFormat::Tsx,
+            "vue" => OutputFormat::Vue,
+            "svelte" => OutputFormat::Svelte,
+            "vector-drawable" => OutputFormat::VectorDrawable,
+            "compose" => OutputFormat::Compose,
+            _ => unreachable!(), // Clap validates this
+        };
+    }
+
+    if matches.get_flag("title-prop") {
+        config.component.title_prop = true;
+    }
+
+    if matches.get_flag("size-prop") {
+        config.component.size_prop = true;
+    }
+
+    if let Some(package) = matches.get_one::<String>("package") {
+        config.component.package = Some(package.clone());
+    }
+
     // Apply precision override
     if let Some(precision) = matches.get_one::<u8>("precision") {
         apply_precision_override(&mut config, *precision);
@@ -225,6 +702,26 @@ fn run_cli(matches: clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>>
         });
     }
 
+    if let Some(size_metric) = matches.get_one::<String>("size-metric") {
+        use svgn::config::SizeMetric;
+        config.size_metric = match size_metric.as_str() {
+            "raw" => SizeMetric::Raw,
+            "gzip" => SizeMetric::Gzip,
+            "brotli" => SizeMetric::Brotli,
+            _ => unreachable!(), // Clap validates this
+        };
+    }
+
+    let compression_level = matches.get_one::<u32>("compression-level").copied();
+    if matches.get_flag("svgz") {
+        config.svgz = Some(SvgzOptions::new(
+            compression_level.unwrap_or(DEFAULT_COMPRESSION_LEVEL),
+        ));
+    }
+    if let (Some(svgz), Some(level)) = (&mut config.svgz, compression_level) {
+        svgz.level = level;
+    }
+
     // Handle plugin enable/disable
     if let Some(disabled_plugins) = matches.get_many::<String>("disable") {
         for plugin_name in disabled_plugins {
@@ -232,6 +729,16 @@ fn run_cli(matches: clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>>
         }
     }
 
+    // Sanitize before any other plugin sees the document
+    if matches.get_flag("sanitize") {
+        match config.get_plugin("sanitize") {
+            Some(_) => config.set_plugin_enabled("sanitize", true),
+            None => config
+                .plugins
+                .insert(0, svgn::plugin::PluginConfig::new("sanitize".to_string())),
+        }
+    }
+
     if let Some(enabled_plugins) = matches.get_many::<String>("enable") {
         for plugin_name in enabled_plugins {
             // Add plugin if it doesn't exist
@@ -243,31 +750,7 @@ fn run_cli(matches: clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>>
         }
     }
 
-    // Determine input mode
-    let (input_mode, output_mode) = determine_io_mode(&matches)?;
-
-    match input_mode {
-        InputMode::String(svg_string) => {
-            process_string(&svg_string, output_mode, &config, quiet)?;
-        }
-        InputMode::Stdin => {
-            let mut buffer = String::new();
-            io::stdin().read_to_string(&mut buffer)?;
-            process_string(&buffer, output_mode, &config, quiet)?;
-        }
-        InputMode::Files(files) => {
-            process_files(&files, output_mode, &config, quiet, no_color)?;
-        }
-        InputMode::Folder(folder, recursive) => {
-            let exclude_patterns = matches
-                .get_many::<String>("exclude")
-                .map(|v| v.map(|s| s.as_str()).collect::<Vec<_>>())
-                .unwrap_or_default();
-            process_folder(&folder, &config, quiet, recursive, &exclude_patterns)?;
-        }
-    }
-
-    Ok(())
+    Ok(config)
 }
 
 enum InputMode {
@@ -343,196 +826,1360 @@ fn determine_io_mode(
     Ok((input_mode, output_mode))
 }
 
+/// Optimize `--string` input, or `content` decoded from the `bytes` read
+/// from STDIN
 fn process_string(
     content: &str,
+    bytes: Option<&[u8]>,
     output_mode: OutputMode,
     config: &Config,
-    quiet: bool,
+    options: &BatchOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let result = optimize_with_config(content, config.clone())?;
+    let quiet = options.quiet;
+    let mut config = config.clone();
+    if let OutputMode::File(path) = &output_mode {
+        enable_svgz_for_output(&mut config, Path::new(path), options);
+    }
+    let result = match bytes {
+        // Bytes read from STDIN keep their encoding, like files
+        Some(bytes) => optimize_bytes(bytes, OptimizeOptions::new(config.clone()))?,
+        None => optimize_with_config(content, config.clone())?,
+    };
+
+    if options.check {
+        if result.data != content {
+            let reduction = result.info.size_reduction();
+            return Err(if reduction >= 0 {
+                format!("Input is not optimized (would save {} bytes)", reduction)
+            } else {
+                format!(
+                    "Input is not optimized (would grow by {} bytes)",
+                    -reduction
+                )
+            }
+            .into());
+        }
+        return Ok(());
+    }
+
+    match options.diff {
+        Some(DiffFormat::Unified) => {
+            print!(
+                "{}",
+                svgn::diff::unified_diff(content, &result.data, "stdin")?
+            );
+            return Ok(());
+        }
+        Some(DiffFormat::Json) => {
+            let changes = svgn::diff::trace_changes(
+                content,
+                &config,
+                &mut svgn::plugin::create_default_registry(),
+            )?;
+            println!("{}", serde_json::to_string_pretty(&changes)?);
+            return Ok(());
+        }
+        None => {}
+    }
 
     match output_mode {
         OutputMode::Stdout => {
-            print!("{}", result.data);
+            io::stdout().write_all(output_bytes(&result))?;
             io::stdout().flush()?;
         }
         OutputMode::File(path) => {
-            fs::write(&path, &result.data)?;
+            fs::write(&path, output_bytes(&result))?;
             if !quiet {
                 eprintln!(
-                    "Optimized: {} → {} ({:.1}% reduction)",
+                    "Optimized: {} → {} ({:.1}% reduction{})",
                     format_bytes(result.info.original_size),
                     format_bytes(result.info.optimized_size),
-                    result.info.compression_percentage()
+                    result.info.compression_percentage(),
+                    compressed_sizes(&result.info)
                 );
             }
         }
         _ => return Err("Invalid output mode for string input".into()),
     }
+    if !quiet {
+        print_removals(&result);
+    }
 
     Ok(())
 }
 
-fn process_files(
-    files: &[String],
-    output_mode: OutputMode,
+/// Report accessibility problems in the inputs without optimizing them
+///
+/// Returns an error if any input has a problem or can't be parsed.
+fn lint_a11y(
+    input_mode: InputMode,
+    matches: &clap::ArgMatches,
     config: &Config,
     quiet: bool,
-    _no_color: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    match output_mode {
-        OutputMode::Stdout => {
-            if files.len() > 1 {
-                return Err("Cannot output multiple files to stdout".into());
-            }
-            let content = fs::read_to_string(&files[0])?;
-            let mut file_config = config.clone();
-            file_config.path = Some(files[0].clone());
-            let result = optimize_with_config(&content, file_config)?;
-            print!("{}", result.data);
-            io::stdout().flush()?;
+    let paths = match input_mode {
+        InputMode::String(svg) => vec![(String::from("<string>"), Ok(svg))],
+        InputMode::Stdin => {
+            let mut buffer = Vec::new();
+            io::stdin().read_to_end(&mut buffer)?;
+            vec![(String::from("<stdin>"), Ok(decode_input(&buffer)?))]
         }
-        OutputMode::File(output_path) => {
-            if files.len() > 1 {
-                return Err("Cannot output multiple files to a single file".into());
-            }
-            let content = fs::read_to_string(&files[0])?;
-            let mut file_config = config.clone();
-            file_config.path = Some(files[0].clone());
-            let result = optimize_with_config(&content, file_config)?;
-            fs::write(&output_path, &result.data)?;
-            if !quiet {
-                eprintln!(
-                    "Optimized: {} → {} ({:.1}% reduction)",
-                    format_bytes(result.info.original_size),
-                    format_bytes(result.info.optimized_size),
-                    result.info.compression_percentage()
-                );
-            }
+        InputMode::Files(files) => files
+            .into_iter()
+            .map(PathBuf::from)
+            .map(read_input)
+            .collect(),
+        InputMode::Folder(folder, recursive) => {
+            let exclude_patterns = matches
+                .get_many::<String>("exclude")
+                .map(|v| v.map(|s| s.as_str()).collect::<Vec<_>>())
+                .unwrap_or_default();
+            list_svg_files(Path::new(&folder), recursive, &exclude_patterns)?
+                .into_iter()
+                .map(read_input)
+                .collect()
         }
-        OutputMode::Directory(output_dir) => {
-            for input_file in files {
-                let content = fs::read_to_string(input_file)?;
-                let mut file_config = config.clone();
-                file_config.path = Some(input_file.clone());
-                let result = optimize_with_config(&content, file_config)?;
-
-                let input_path = Path::new(input_file);
-                let file_name = input_path.file_name().ok_or("Invalid input file path")?;
-                let output_path = Path::new(&output_dir).join(file_name);
+    };
 
-                fs::write(&output_path, &result.data)?;
-                if !quiet {
-                    eprintln!(
-                        "Optimized: {} → {} ({:.1}% reduction)",
-                        input_path.display(),
-                        output_path.display(),
-                        result.info.compression_percentage()
-                    );
+    let parser = svgn::parser::Parser::new()
+        .expand_entities(config.parser.expand_entities)
+        .entity_limits(config.parser.entity_limits.clone());
+    let mut problems = 0;
+    for (name, content) in &paths {
+        match content
+            .as_ref()
+            .map_err(|e| e.to_string())
+            .and_then(|content| parser.parse(content).map_err(|e| e.to_string()))
+        {
+            Ok(document) => {
+                for issue in svgn::a11y::check(&document) {
+                    problems += 1;
+                    eprintln!("{}: {}", name, issue);
                 }
             }
-        }
-        OutputMode::InPlace => {
-            for input_file in files {
-                let content = fs::read_to_string(input_file)?;
-                let mut file_config = config.clone();
-                file_config.path = Some(input_file.clone());
-                let result = optimize_with_config(&content, file_config)?;
-                fs::write(input_file, &result.data)?;
-                if !quiet {
-                    eprintln!(
-                        "Optimized: {} ({:.1}% reduction)",
-                        input_file,
-                        result.info.compression_percentage()
-                    );
-                }
+            Err(e) => {
+                problems += 1;
+                eprintln!("Error processing {}: {}", name, e);
             }
         }
     }
 
+    if !quiet {
+        eprintln!(
+            "\nChecked: {} files, {} accessibility problems",
+            paths.len(),
+            problems
+        );
+    }
+
+    if problems > 0 {
+        return Err(format!("{} accessibility problems found", problems).into());
+    }
     Ok(())
 }
 
-fn process_folder(
-    folder_path: &str,
-    config: &Config,
-    quiet: bool,
-    recursive: bool,
-    exclude_patterns: &[&str],
-) -> Result<(), Box<dyn std::error::Error>> {
-    let folder = PathBuf::from(folder_path);
+/// Run the `lint` subcommand
+///
+/// Returns an error if a rule with error severity is violated or an input
+/// can't be linted.
+fn run_lint(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    use svgn::lint::output::{FileLint, LintFormat, LintReport};
 
-    if !folder.is_dir() {
-        return Err(format!("{} is not a directory", folder_path).into());
-    }
-
-    let svg_files = if recursive {
-        find_svg_files_recursive(&folder, exclude_patterns)?
-    } else {
-        find_svg_files(&folder, exclude_patterns)?
+    let config = match matches.get_one::<String>("config") {
+        Some(config_path) => Config::from_file(config_path)?,
+        None => svgn::config::load_config_from_directory(".")?.unwrap_or_default(),
     };
-
-    if svg_files.is_empty() {
-        if !quiet {
-            eprintln!("No SVG files found in {}", folder_path);
+    let registry = svgn::lint::create_default_rule_registry();
+
+    if matches.get_flag("show-rules") {
+        println!("Available rules:");
+        for rule in registry.rules() {
+            println!(
+                "  {:<22} {:<8} {}",
+                rule.id(),
+                registry.severity(rule, &config.lint),
+                rule.description()
+            );
         }
         return Ok(());
     }
 
-    let mut total_original = 0;
-    let mut total_optimized = 0;
-    let mut processed_count = 0;
+    let fix = matches.get_flag("fix");
+    let inputs = subcommand_inputs(matches)?;
+    if fix && inputs.iter().any(|(path, _)| path.is_none()) {
+        return Err("--fix needs files, not STDIN".into());
+    }
 
-    for svg_file in svg_files {
-        let input_content = fs::read_to_string(&svg_file)?;
+    let files = inputs
+        .into_par_iter()
+        .map(|(path, stdin)| {
+            let name = input_name(path.as_deref());
+            lint_input(&name, path.as_deref(), stdin, fix, &config, &registry)
+                .unwrap_or_else(|e| FileLint::failed(&name, &e.to_string()))
+        })
+        .collect();
+    let report = LintReport::new(files);
+
+    let format = match matches.get_one::<String>("format").map(|s| s.as_str()) {
+        Some("json") => LintFormat::Json,
+        Some("sarif") => LintFormat::Sarif,
+        _ => LintFormat::Text,
+    };
+    write_subcommand_output(matches, report.render(format, &registry, &config)?)?;
 
-        let mut file_config = config.clone();
-        file_config.path = Some(svg_file.to_string_lossy().to_string());
+    if report.has_errors() {
+        let summary = &report.summary;
+        return Err(format!(
+            "lint failed: {} errors, {} files could not be linted",
+            summary.errors, summary.failed
+        )
+        .into());
+    }
+    Ok(())
+}
 
-        match optimize_with_config(&input_content, file_config) {
-            Ok(result) => {
-                fs::write(&svg_file, &result.data)?;
+/// Run the `analyze` subcommand
+fn run_analyze(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    use svgn::analyze::{analyze_svg, AnalysisReport, FileAnalysis};
 
-                total_original += result.info.original_size;
-                total_optimized += result.info.optimized_size;
-                processed_count += 1;
+    let config = match matches.get_one::<String>("config") {
+        Some(config_path) => Config::from_file(config_path)?,
+        None => svgn::config::load_config_from_directory(".")?.unwrap_or_default(),
+    };
 
-                if !quiet {
-                    println!(
-                        "Optimized: {} ({:.1}% reduction)",
-                        svg_file.display(),
-                        result.info.compression_percentage()
-                    );
-                }
+    let files = subcommand_inputs(matches)?
+        .into_par_iter()
+        .map(|(path, stdin)| {
+            let content = match &path {
+                Some(path) => fs::read(path).map_err(|e| e.to_string()),
+                None => Ok(stdin),
+            };
+            let analysis = content
+                .and_then(|content| decode_input(&content).map_err(|e| e.to_string()))
+                .and_then(|svg| analyze_svg(&svg, &config).map_err(|e| e.to_string()));
+            let path = input_name(path.as_deref());
+            match analysis {
+                Ok(analysis) => FileAnalysis {
+                    path,
+                    analysis: Some(analysis),
+                    error: None,
+                },
+                Err(e) => FileAnalysis {
+                    path,
+                    analysis: None,
+                    error: Some(e),
+                },
             }
-            Err(e) => {
-                eprintln!("Error processing {}: {}", svg_file.display(), e);
+        })
+        .collect();
+    let report = AnalysisReport::new(files);
+
+    let rendered = match matches.get_one::<String>("format").map(|s| s.as_str()) {
+        Some("json") => serde_json::to_string_pretty(&report)?,
+        _ => {
+            let mut table = String::new();
+            if let [file] = report.files.as_slice() {
+                table.push_str(&format!("{}\n", file.path));
+            }
+            table.push_str(&report.total.to_table());
+            for file in report.files.iter().filter(|file| file.error.is_some()) {
+                table.push_str(&format!(
+                    "Error processing {}: {}\n",
+                    file.path,
+                    file.error.as_deref().unwrap_or_default()
+                ));
             }
+            table
         }
+    };
+    write_subcommand_output(matches, rendered)?;
+
+    let failed = report
+        .files
+        .iter()
+        .filter(|file| file.error.is_some())
+        .count();
+    if failed > 0 {
+        return Err(format!("{} files could not be analyzed", failed).into());
     }
+    Ok(())
+}
 
-    if !quiet && processed_count > 0 {
-        let total_reduction = if total_original > 0 {
-            ((total_original - total_optimized) as f64 / total_original as f64) * 100.0
+/// A file to read, or STDIN content with no path
+type SubcommandInput = (Option<PathBuf>, Vec<u8>);
+
+/// Inputs named by the `PATH` arguments of a subcommand
+///
+/// Folders expand to the SVG files in them and `-` to the content of STDIN.
+fn subcommand_inputs(
+    matches: &clap::ArgMatches,
+) -> Result<Vec<SubcommandInput>, Box<dyn std::error::Error>> {
+    let recursive = matches.get_flag("recursive");
+    let exclude_patterns = matches
+        .get_many::<String>("exclude")
+        .map(|v| v.map(|s| s.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let mut inputs = Vec::new();
+    for path in matches.get_many::<String>("PATH").into_iter().flatten() {
+        if path == "-" {
+            let mut buffer = Vec::new();
+            io::stdin().read_to_end(&mut buffer)?;
+            inputs.push((None, buffer));
+        } else if Path::new(path).is_dir() {
+            for file in list_svg_files(Path::new(path), recursive, &exclude_patterns)? {
+                inputs.push((Some(file), Vec::new()));
+            }
         } else {
-            0.0
-        };
+            inputs.push((Some(PathBuf::from(path)), Vec::new()));
+        }
+    }
+    Ok(inputs)
+}
+
+fn input_name(path: Option<&Path>) -> String {
+    path.map_or_else(|| String::from("<stdin>"), |p| p.display().to_string())
+}
+
+/// Write a subcommand's output to its `--output` file, or to STDOUT
+fn write_subcommand_output(
+    matches: &clap::ArgMatches,
+    mut rendered: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !rendered.ends_with('\n') {
+        rendered.push('\n');
+    }
+    match matches.get_one::<String>("output") {
+        Some(path) => fs::write(path, rendered)?,
+        None => io::stdout().write_all(rendered.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Lint one file, or STDIN content if `path` is `None`, fixing it in place
+/// if asked to
+fn lint_input(
+    name: &str,
+    path: Option<&Path>,
+    stdin: Vec<u8>,
+    fix: bool,
+    config: &Config,
+    registry: &svgn::lint::RuleRegistry,
+) -> Result<svgn::lint::output::FileLint, Box<dyn std::error::Error + Send + Sync>> {
+    use svgn::lint::output::FileLint;
+
+    let content = match path {
+        Some(path) => fs::read(path)?,
+        None => stdin,
+    };
+    match path {
+        Some(path) if fix => {
+            let fixed = svgn::lint::fix_with_registry(&content, config, registry)?;
+            let mut file = FileLint::new(name, fixed.diagnostics);
+            if fixed.data != content {
+                fs::write(path, &fixed.data)?;
+                file.fixed = fixed.plugins;
+            }
+            Ok(file)
+        }
+        _ => {
+            let diagnostics =
+                svgn::lint::lint_with_registry(&decode_input(&content)?, config, registry)?;
+            Ok(FileLint::new(name, diagnostics))
+        }
+    }
+}
+
+/// Read and decode an input file, keeping its path for messages
+fn read_input(path: PathBuf) -> (String, Result<String, String>) {
+    let content = fs::read(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| decode_input(&bytes).map_err(|e| e.to_string()));
+    (path.display().to_string(), content)
+}
+
+fn process_stream(
+    input_mode: InputMode,
+    output_mode: OutputMode,
+    config: &Config,
+    options: &BatchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let input: Box<dyn io::BufRead> = match &input_mode {
+        InputMode::Stdin => Box::new(io::stdin().lock()),
+        InputMode::Files(files) if files.len() == 1 => {
+            Box::new(io::BufReader::new(fs::File::open(&files[0])?))
+        }
+        _ => return Err("--stream takes a single input file or stdin".into()),
+    };
+    let output: Box<dyn Write> = match &output_mode {
+        OutputMode::Stdout => Box::new(io::stdout().lock()),
+        OutputMode::File(path) => Box::new(fs::File::create(path)?),
+        // The input is still being read while the output is written
+        _ => return Err("--stream needs an output file or '-' for stdout".into()),
+    };
+
+    let info = svgn::streaming::optimize_stream(input, io::BufWriter::new(output), config)?;
+
+    if !options.quiet {
+        if !info.skipped_plugins.is_empty() {
+            eprintln!(
+                "Skipped plugins that need the whole document: {}",
+                info.skipped_plugins.join(", ")
+            );
+        }
+        if let OutputMode::File(_) = output_mode {
+            eprintln!(
+                "Optimized: {} → {}",
+                format_bytes(info.original_size),
+                format_bytes(info.optimized_size)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn process_files(
+    files: &[String],
+    output_mode: OutputMode,
+    config: &Config,
+    options: &BatchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let quiet = options.quiet;
+    match output_mode {
+        _ if options.dry_run() => {
+            let jobs = files
+                .iter()
+                .map(|input_file| FileJob {
+                    input: PathBuf::from(input_file),
+                    output: PathBuf::from(input_file),
+                })
+                .collect();
+            let outcomes = run_jobs(jobs, config, options)?;
+            report_outcomes(&outcomes, options)?;
+        }
+        OutputMode::Stdout => {
+            if files.len() > 1 {
+                return Err("Cannot output multiple files to stdout".into());
+            }
+            let content = fs::read(&files[0])?;
+            let mut file_config = config.clone();
+            file_config.path = Some(files[0].clone());
+            let result = optimize_bytes(&content, OptimizeOptions::new(file_config))?;
+            io::stdout().write_all(output_bytes(&result))?;
+            io::stdout().flush()?;
+            if !quiet {
+                print_removals(&result);
+            }
+        }
+        OutputMode::File(output_path) => {
+            if files.len() > 1 {
+                return Err("Cannot output multiple files to a single file".into());
+            }
+            let content = fs::read(&files[0])?;
+            let mut file_config = config.clone();
+            file_config.path = Some(files[0].clone());
+            enable_svgz_for_output(&mut file_config, Path::new(&output_path), options);
+            let result = optimize_bytes(&content, OptimizeOptions::new(file_config))?;
+            fs::write(&output_path, output_bytes(&result))?;
+            if !quiet {
+                eprintln!(
+                    "Optimized: {} → {} ({:.1}% reduction{})",
+                    format_bytes(result.info.original_size),
+                    format_bytes(result.info.optimized_size),
+                    result.info.compression_percentage(),
+                    compressed_sizes(&result.info)
+                );
+                print_removals(&result);
+            }
+        }
+        OutputMode::Directory(output_dir) => {
+            let output_dir = Path::new(&output_dir);
+            let mut jobs = Vec::with_capacity(files.len());
+            for input_file in files {
+                let input_path = PathBuf::from(input_file);
+                if input_path.file_name().is_none() {
+                    return Err(format!("Invalid input file path: {}", input_file).into());
+                }
+                jobs.push(FileJob {
+                    output: output_path_for(&input_path, None, Some(output_dir), options),
+                    input: input_path,
+                });
+            }
+            check_output_collisions(&jobs)?;
+            let outcomes = run_jobs(jobs, config, options)?;
+            report_outcomes(&outcomes, options)?;
+        }
+        OutputMode::InPlace => {
+            let jobs: Vec<FileJob> = files
+                .iter()
+                .map(|input_file| FileJob {
+                    input: PathBuf::from(input_file),
+                    output: output_path_for(Path::new(input_file), None, None, options),
+                })
+                .collect();
+            check_output_collisions(&jobs)?;
+            let outcomes = run_jobs(jobs, config, options)?;
+            report_outcomes(&outcomes, options)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn process_folder(
+    folder_path: &str,
+    output_mode: OutputMode,
+    config: &Config,
+    options: &BatchOptions,
+    recursive: bool,
+    exclude_patterns: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let folder = PathBuf::from(folder_path);
+
+    if !folder.is_dir() {
+        return Err(format!("{} is not a directory", folder_path).into());
+    }
+
+    let output_dir = match output_mode {
+        _ if options.dry_run() => None,
+        OutputMode::Directory(dir) => Some(PathBuf::from(dir)),
+        OutputMode::InPlace => None,
+        OutputMode::File(_) | OutputMode::Stdout => {
+            return Err("Folder output must be a directory".into());
+        }
+    };
+
+    let mut svg_files = list_files(&folder, recursive, exclude_patterns, |path| {
+        options.is_folder_input(path)
+    })?;
+    svg_files.retain(|path| !is_previous_output(path, options));
+    if let Some(dir) = output_dir
+        .as_deref()
+        .and_then(|dir| fs::canonicalize(dir).ok())
+    {
+        // Don't pick up results of an earlier run when the output is nested in the input
+        svg_files
+            .retain(|path| fs::canonicalize(path).map_or(true, |path| !path.starts_with(&dir)));
+    }
+
+    if svg_files.is_empty() {
+        if !options.quiet {
+            eprintln!("No SVG files found in {}", folder_path);
+        }
+        return Ok(());
+    }
+
+    let jobs: Vec<FileJob> = svg_files
+        .into_iter()
+        .map(|svg_file| FileJob {
+            output: output_path_for(&svg_file, Some(&folder), output_dir.as_deref(), options),
+            input: svg_file,
+        })
+        .collect();
+    check_output_collisions(&jobs)?;
+    let outcomes = run_jobs(jobs, config, options)?;
+    if !options.dry_run() {
+        write_index(
+            &outcomes,
+            output_dir.as_deref().unwrap_or(&folder),
+            config.format,
+        )?;
+    }
+    report_outcomes(&outcomes, options)
+}
+
+/// Write a module that re-exports the components generated from a folder,
+/// for formats that have one
+fn write_index(
+    outcomes: &[FileOutcome],
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(file_name) = format.index_file_name() else {
+        return Ok(());
+    };
+
+    let mut components: Vec<(String, String)> = outcomes
+        .iter()
+        .filter(|outcome| outcome.result.is_ok())
+        .filter_map(|outcome| {
+            let relative = outcome.job.output.strip_prefix(dir).ok()?;
+            Some((
+                export::component_name(Some(&outcome.job.input)),
+                relative.to_string_lossy().to_string(),
+            ))
+        })
+        .collect();
+    components.sort_by(|a, b| a.1.cmp(&b.1));
+
+    if let Some(module) = export::index_module(format, &components) {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(file_name), module)?;
+    }
+    Ok(())
+}
+
+/// Compute the path the optimized `input` is written to
+///
+/// With an output directory, inputs found under `base` keep their location
+/// relative to it and all other inputs are flattened to their file name.
+/// Without one, the output sits next to the input.
+fn output_path_for(
+    input: &Path,
+    base: Option<&Path>,
+    output_dir: Option<&Path>,
+    options: &BatchOptions,
+) -> PathBuf {
+    let output = match output_dir {
+        Some(dir) => match base.and_then(|base| input.strip_prefix(base).ok()) {
+            Some(relative) => dir.join(relative),
+            None => dir.join(input.file_name().unwrap_or_default()),
+        },
+        None => input.to_path_buf(),
+    };
+
+    match &options.output_extension {
+        Some(extension) => {
+            let file_name = output.file_name().unwrap_or_default().to_string_lossy();
+            let extension_len = output.extension().map_or(0, |ext| ext.len() + 1);
+            let stem = match file_name.len().checked_sub(extension_len) {
+                Some(end)
+                    if file_name.is_char_boundary(end)
+                        && (is_svg_file(&output) || is_xml_file(&output)) =>
+                {
+                    &file_name[..end]
+                }
+                _ => file_name.as_ref(),
+            };
+            output.with_file_name(format!("{}{}", stem, extension))
+        }
+        None => output,
+    }
+}
+
+/// Whether `path` looks like the output of an earlier run with a custom
+/// output extension, so it is not optimized again as an input
+fn is_previous_output(path: &Path, options: &BatchOptions) -> bool {
+    match &options.output_extension {
+        Some(extension) if !extension.eq_ignore_ascii_case(".svg") => path
+            .file_name()
+            .map(|name| {
+                name.to_string_lossy()
+                    .to_ascii_lowercase()
+                    .ends_with(&extension.to_ascii_lowercase())
+            })
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Refuse to run when two inputs would be written to the same output path
+fn check_output_collisions(jobs: &[FileJob]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut seen: HashMap<&Path, &Path> = HashMap::new();
+    let mut collisions = Vec::new();
+
+    for job in jobs {
+        if let Some(previous) = seen.insert(&job.output, &job.input) {
+            collisions.push(format!(
+                "  {} and {} both write to {}",
+                previous.display(),
+                job.input.display(),
+                job.output.display()
+            ));
+        }
+    }
+
+    if collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Output path collisions:\n{}", collisions.join("\n")).into())
+    }
+}
+
+/// Settings shared by the multi-file processing modes
+struct BatchOptions {
+    /// Suppress per-file status messages and the summary
+    quiet: bool,
+    /// Only compare optimized output against the input, never write
+    check: bool,
+    /// Print a diff of the changes instead of writing
+    diff: Option<DiffFormat>,
+    /// Number of worker threads
+    jobs: usize,
+    /// Replacement for the `.svg` extension of output files (e.g. `.min.svg`)
+    output_extension: Option<String>,
+    /// Gzip level for outputs that are compressed because of their `.svgz` extension
+    compression_level: Option<u32>,
+    /// Cache of previous results, if enabled
+    cache: Option<Cache>,
+    /// Machine-readable report format and file (stdout if `None`)
+    report: Option<(ReportFormat, Option<PathBuf>)>,
+    /// Optimize the `.xml` files of folders as VectorDrawables, instead of
+    /// their SVG files
+    vector_drawable_folders: bool,
+}
 
-        eprintln!("\nTotal: {} files processed", processed_count);
+impl BatchOptions {
+    /// Whether a file in an input folder is optimized
+    fn is_folder_input(&self, path: &Path) -> bool {
+        match self.vector_drawable_folders {
+            true => is_xml_file(path),
+            false => is_svg_file(path),
+        }
+    }
+
+    /// Whether this run must leave all files untouched
+    fn dry_run(&self) -> bool {
+        self.check || self.diff.is_some()
+    }
+}
+
+/// Output formats for `--diff`
+#[derive(Clone, Copy)]
+enum DiffFormat {
+    /// Unified diff of the pretty-printed input and output
+    Unified,
+    /// JSON list of changes attributed to plugins
+    Json,
+}
+
+/// A single file to optimize and the path its result is written to
+struct FileJob {
+    input: PathBuf,
+    output: PathBuf,
+}
+
+/// Outcome of processing a single [`FileJob`]
+struct FileOutcome {
+    job: FileJob,
+    result: Result<FileResult, String>,
+    /// Wall-clock processing time
+    duration: Duration,
+}
+
+/// Result of successfully optimizing a single file
+struct FileResult {
+    info: OptimizationInfo,
+    /// Whether the optimized output differs from the input
+    changed: bool,
+    /// Rendered diff, when running with `--diff`
+    diff: Option<FileDiff>,
+    /// Entry for `--report`
+    report: FileReport,
+    /// Hash of the output bytes, which watch mode uses to recognize its own
+    /// writes
+    output_hash: u64,
+}
+
+/// Diff of a single file in the requested [`DiffFormat`]
+enum FileDiff {
+    Unified(String),
+    Changes(Vec<svgn::diff::Change>),
+}
+
+/// Optimize all jobs on a pool of `options.jobs` worker threads
+///
+/// Every worker owns its own plugin registry. Outcomes are returned in the
+/// same order as `jobs`, and a failing file never aborts the others.
+fn run_jobs(
+    jobs: Vec<FileJob>,
+    config: &Config,
+    options: &BatchOptions,
+) -> Result<Vec<FileOutcome>, Box<dyn std::error::Error>> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs)
+        .build()?;
+
+    Ok(pool.install(|| {
+        jobs.into_par_iter()
+            .map_init(svgn::plugin::create_default_registry, |registry, job| {
+                let start = Instant::now();
+                let result =
+                    optimize_file(&job, config, options, registry).map_err(|e| e.to_string());
+                FileOutcome {
+                    job,
+                    result,
+                    duration: start.elapsed(),
+                }
+            })
+            .collect()
+    }))
+}
+
+/// Read, optimize and (unless running dry) write a single file
+fn optimize_file(
+    job: &FileJob,
+    config: &Config,
+    options: &BatchOptions,
+    registry: &mut PluginRegistry,
+) -> Result<FileResult, Box<dyn std::error::Error>> {
+    let bytes = fs::read(&job.input)?;
+    let content = decode_input(&bytes)?;
+    let mut file_config = config.clone();
+    file_config.path = Some(job.input.to_string_lossy().to_string());
+    if !options.dry_run() {
+        enable_svgz_for_output(&mut file_config, &job.output, options);
+    }
+
+    // The cache only holds the output, not what sanitizing removed
+    let sanitizing = file_config
+        .get_plugin("sanitize")
+        .is_some_and(|plugin| plugin.enabled);
+    let cache_key = match &options.cache {
+        Some(_) if !sanitizing => Some(Cache::key(&bytes, &file_config)?),
+        _ => None,
+    };
+    let cached = options
+        .cache
+        .as_ref()
+        .zip(cache_key.as_deref())
+        .and_then(|(cache, key)| cache.get(key));
+    let cache_hit = cached.is_some();
+
+    let mut result = match cached {
+        Some(data) => {
+            let compressed = match &file_config.svgz {
+                Some(svgz) => Some(svgz::compress(data.as_bytes(), svgz.level)?),
+                None => None,
+            };
+            let mut info = OptimizationInfo::new(content.len(), data.len(), 0, 0);
+            info.compressed_size = compressed.as_ref().map(Vec::len);
+            OptimizationResult {
+                info,
+                data,
+                error: None,
+                modern: true,
+                compressed,
+                sanitize_report: None,
+            }
+        }
+        None => {
+            let result = optimize_bytes_with_registry(&bytes, &file_config, registry)?;
+            if let (Some(cache), Some(key)) = (&options.cache, &cache_key) {
+                // The output is still written, just not cached
+                if let Err(e) = cache.put(key, &result.data) {
+                    eprintln!("Warning: could not cache {}: {}", job.input.display(), e);
+                }
+            }
+            result
+        }
+    };
+    if svgz::is_gzip(&bytes) {
+        result.info.original_compressed_size = Some(bytes.len());
+    }
+    let changed = result.data != content;
+    let mut report = FileReport::from_result(&job.input.to_string_lossy(), &result, changed);
+    report.cache_hit = cache_hit;
+    if job.output != job.input {
+        report.output = Some(job.output.to_string_lossy().to_string());
+    }
+    if options.check && changed {
+        report.status = FileStatus::NotOptimized;
+    }
+    let diff = match options.diff {
+        Some(DiffFormat::Unified) => Some(FileDiff::Unified(svgn::diff::unified_diff(
+            &content,
+            &result.data,
+            &job.input.to_string_lossy(),
+        )?)),
+        Some(DiffFormat::Json) => Some(FileDiff::Changes(svgn::diff::trace_changes(
+            &content,
+            &file_config,
+            registry,
+        )?)),
+        None => None,
+    };
+    // On a cache hit, an output that is already up to date is left untouched
+    let up_to_date =
+        cache_hit && fs::read(&job.output).is_ok_and(|existing| existing == output_bytes(&result));
+    if !options.dry_run() && !up_to_date {
+        if let Some(parent) = job.output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&job.output, output_bytes(&result))?;
+    }
+
+    Ok(FileResult {
+        output_hash: content_hash(output_bytes(&result)),
+        info: result.info,
+        changed,
+        diff,
+        report,
+    })
+}
+
+/// Print per-file results followed by an aggregated summary
+///
+/// Returns an error if any file failed, so the process exits non-zero.
+fn report_outcomes(
+    outcomes: &[FileOutcome],
+    options: &BatchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some((format, path)) = &options.report {
+        write_report(outcomes, *format, path.as_deref())?;
+    }
+    if options.check {
+        return report_check_outcomes(outcomes, options.quiet);
+    }
+    if options.diff.is_some() {
+        return report_diff_outcomes(outcomes);
+    }
+
+    let quiet = options.quiet;
+    let mut total_original = 0;
+    let mut total_optimized = 0;
+    let mut processed_count = 0;
+    let mut failed_count = 0;
+    let mut cache_hits = 0;
+
+    for outcome in outcomes {
+        let job = &outcome.job;
+        match &outcome.result {
+            Ok(FileResult { info, report, .. }) => {
+                total_original += info.original_size;
+                total_optimized += info.optimized_size;
+                processed_count += 1;
+                if report.cache_hit {
+                    cache_hits += 1;
+                }
+
+                if !quiet {
+                    if job.input == job.output {
+                        eprintln!(
+                            "Optimized: {} ({:.1}% reduction{})",
+                            job.input.display(),
+                            info.compression_percentage(),
+                            compressed_sizes(info)
+                        );
+                    } else {
+                        eprintln!(
+                            "Optimized: {} → {} ({:.1}% reduction{})",
+                            job.input.display(),
+                            job.output.display(),
+                            info.compression_percentage(),
+                            compressed_sizes(info)
+                        );
+                    }
+                    for removal in &report.removed {
+                        eprintln!("  Removed {}", removal);
+                    }
+                }
+            }
+            Err(e) => {
+                failed_count += 1;
+                eprintln!("Error processing {}: {}", job.input.display(), e);
+            }
+        }
+    }
+
+    if !quiet {
+        let summary = OptimizationInfo::new(total_original, total_optimized, 0, 0);
+
+        if failed_count > 0 {
+            eprintln!(
+                "\nTotal: {} files processed, {} failed",
+                processed_count, failed_count
+            );
+        } else {
+            eprintln!("\nTotal: {} files processed", processed_count);
+        }
         eprintln!(
             "Size: {} → {} ({:.1}% reduction)",
             format_bytes(total_original),
             format_bytes(total_optimized),
-            total_reduction
+            summary.compression_percentage()
         );
+        if options.cache.is_some() {
+            eprintln!(
+                "Cache: {} hits, {} misses",
+                cache_hits,
+                processed_count - cache_hits
+            );
+        }
+    }
+
+    if failed_count > 0 {
+        return Err(format!("{} of {} files failed", failed_count, outcomes.len()).into());
+    }
+
+    Ok(())
+}
+
+/// Render the `--report` for a batch run to a file or stdout
+fn write_report(
+    outcomes: &[FileOutcome],
+    format: ReportFormat,
+    path: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let files = outcomes
+        .iter()
+        .map(|outcome| {
+            let mut report = match &outcome.result {
+                Ok(result) => result.report.clone(),
+                Err(e) => FileReport::failed(&outcome.job.input.to_string_lossy(), e),
+            };
+            report.duration_secs = outcome.duration.as_secs_f64();
+            report
+        })
+        .collect();
+    let rendered = Report::new(files).render(format)?;
+
+    match path {
+        Some(path) => fs::write(path, rendered)?,
+        None => {
+            print!("{}", rendered);
+            if !rendered.ends_with('\n') {
+                println!();
+            }
+            io::stdout().flush()?;
+        }
     }
 
     Ok(())
 }
 
-fn find_svg_files(
+/// Print the files whose optimized output differs from what is on disk
+///
+/// Returns an error if any file is not optimized or failed to process.
+fn report_check_outcomes(
+    outcomes: &[FileOutcome],
+    quiet: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut unoptimized_count = 0;
+    let mut failed_count = 0;
+    let mut total_savings = 0;
+
+    for outcome in outcomes {
+        let input = outcome.job.input.display();
+        match &outcome.result {
+            Ok(result) if result.changed => {
+                unoptimized_count += 1;
+                let reduction = result.info.size_reduction();
+                if reduction >= 0 {
+                    total_savings += reduction;
+                    eprintln!("Not optimized: {} (would save {} bytes)", input, reduction);
+                } else {
+                    // Rewriting can grow a file, e.g. with pretty printing
+                    eprintln!(
+                        "Not optimized: {} (would grow by {} bytes)",
+                        input, -reduction
+                    );
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                failed_count += 1;
+                eprintln!("Error processing {}: {}", input, e);
+            }
+        }
+    }
+
+    if !quiet {
+        eprintln!(
+            "\nChecked: {} files, {} not optimized, {} failed",
+            outcomes.len(),
+            unoptimized_count,
+            failed_count
+        );
+        if unoptimized_count > 0 {
+            eprintln!("Potential savings: {} bytes", total_savings);
+        }
+    }
+
+    if unoptimized_count > 0 || failed_count > 0 {
+        return Err(format!(
+            "{} of {} files are not optimized",
+            unoptimized_count + failed_count,
+            outcomes.len()
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Print the diff of every file to stdout, in input order
+///
+/// Returns an error if any file failed to process.
+fn report_diff_outcomes(outcomes: &[FileOutcome]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut failed_count = 0;
+    let mut json_reports = Vec::new();
+
+    for outcome in outcomes {
+        match &outcome.result {
+            Ok(result) => match &result.diff {
+                Some(FileDiff::Unified(diff)) => print!("{}", diff),
+                Some(FileDiff::Changes(changes)) => json_reports.push(serde_json::json!({
+                    "path": outcome.job.input.to_string_lossy(),
+                    "changes": changes,
+                })),
+                None => {}
+            },
+            Err(e) => {
+                failed_count += 1;
+                eprintln!("Error processing {}: {}", outcome.job.input.display(), e);
+            }
+        }
+    }
+
+    if !json_reports.is_empty() {
+        println!("{}", serde_json::to_string_pretty(&json_reports)?);
+    }
+    io::stdout().flush()?;
+
+    if failed_count > 0 {
+        return Err(format!("{} of {} files failed", failed_count, outcomes.len()).into());
+    }
+
+    Ok(())
+}
+
+/// How long the watcher waits for further events before processing a batch
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Poll interval used when native filesystem notifications are unavailable
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Optimize a folder, then keep re-optimizing files as they change
+///
+/// Events are debounced so that an editor writing a file in several chunks
+/// triggers a single run. Files whose content matches what svgn last wrote are
+/// skipped, so in-place output does not retrigger itself. Changes to the
+/// config file reload it and re-optimize the whole folder.
+fn watch_folder(
+    folder_path: &str,
+    output_mode: OutputMode,
+    matches: &clap::ArgMatches,
+    options: &BatchOptions,
+    recursive: bool,
+    exclude_patterns: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let folder = PathBuf::from(folder_path);
+    if !folder.is_dir() {
+        return Err(format!("{} is not a directory", folder_path).into());
+    }
+
+    let output_dir = match output_mode {
+        OutputMode::Directory(dir) => {
+            fs::create_dir_all(&dir)?;
+            Some(fs::canonicalize(dir)?)
+        }
+        OutputMode::InPlace => None,
+        _ => return Err("Watch mode writes in place or to an output directory".into()),
+    };
+    let config_file = match matches.get_one::<String>("config") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => svgn::config::find_config_file("."),
+    };
+    let config_file = config_file.map(fs::canonicalize).transpose()?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = create_watcher(tx)?;
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher.watch(&folder, mode)?;
+    if let Some(parent) = config_file.as_ref().and_then(|path| path.parent()) {
+        // Watch the directory: editors often replace the file instead of writing to it
+        watcher.watch(parent, RecursiveMode::NonRecursive)?;
+    }
+
+    let folder = fs::canonicalize(&folder)?;
+    let mut config = load_config(matches)?;
+    let mut written: HashMap<PathBuf, u64> = HashMap::new();
+    let mut pending = list_files(&folder, recursive, exclude_patterns, |path| {
+        options.is_folder_input(path)
+    })?;
+
+    if !options.quiet {
+        eprintln!("Watching {} for changes...", folder_path);
+    }
+
+    loop {
+        let jobs: Vec<FileJob> = pending
+            .iter()
+            .filter(|path| {
+                output_dir.as_ref().is_none_or(|dir| !path.starts_with(dir))
+                    && !is_previous_output(path, options)
+                    && !is_own_write(&written, path, fs::read(path).ok().as_deref())
+            })
+            .map(|path| FileJob {
+                input: path.clone(),
+                output: output_path_for(path, Some(&folder), output_dir.as_deref(), options),
+            })
+            .collect();
+
+        if !jobs.is_empty() {
+            let outcomes = run_jobs(jobs, &config, options)?;
+            // Hash what was written rather than reading it back, which
+            // could pick up an edit made in the meantime
+            for outcome in &outcomes {
+                if let Ok(result) = &outcome.result {
+                    written.insert(outcome.job.output.clone(), result.output_hash);
+                }
+            }
+            if let Err(e) = report_outcomes(&outcomes, options) {
+                eprintln!("Error: {}", e);
+            }
+        }
+
+        // Block for the next event, then drain everything that follows closely
+        let changed = debounce_events(rx.recv()?, &rx, WATCH_DEBOUNCE);
+
+        let config_changed = config_file
+            .as_ref()
+            .is_some_and(|path| changed.contains(path));
+        if config_changed {
+            match load_config(matches) {
+                Ok(new_config) => {
+                    config = new_config;
+                    written.clear();
+                    pending = list_files(&folder, recursive, exclude_patterns, |path| {
+                        options.is_folder_input(path)
+                    })?;
+                    if !options.quiet {
+                        eprintln!("Config changed, re-optimizing {}", folder_path);
+                    }
+                    continue;
+                }
+                Err(e) => eprintln!("Error reloading config: {}", e),
+            }
+        }
+
+        pending = Vec::new();
+        for path in changed {
+            if in_watch_scope(&path, &folder, recursive)
+                && path.is_file()
+                && options.is_folder_input(&path)
+                && !is_excluded(&path, exclude_patterns)?
+            {
+                pending.push(path);
+            }
+        }
+    }
+}
+
+/// Create a native filesystem watcher, falling back to polling
+fn create_watcher(
+    tx: mpsc::Sender<notify::Result<notify::Event>>,
+) -> Result<Box<dyn Watcher>, Box<dyn std::error::Error>> {
+    let native = notify::recommended_watcher(tx.clone())
+        .map(|watcher| Box::new(watcher) as Box<dyn Watcher>);
+    watcher_or_poll(native, tx)
+}
+
+/// The `native` watcher, or a polling one sending to `tx` if it failed
+fn watcher_or_poll(
+    native: notify::Result<Box<dyn Watcher>>,
+    tx: mpsc::Sender<notify::Result<notify::Event>>,
+) -> Result<Box<dyn Watcher>, Box<dyn std::error::Error>> {
+    match native {
+        Ok(watcher) => Ok(watcher),
+        Err(e) => {
+            eprintln!(
+                "Native file watching unavailable ({}), polling every {}s",
+                e,
+                WATCH_POLL_INTERVAL.as_secs()
+            );
+            let config = notify::Config::default().with_poll_interval(WATCH_POLL_INTERVAL);
+            Ok(Box::new(PollWatcher::new(tx, config)?))
+        }
+    }
+}
+
+/// Paths changed by `first` and the events that follow it, each within
+/// `debounce` of the last change
+///
+/// Other events, such as reads of the watched files, don't extend the wait.
+fn debounce_events(
+    first: notify::Result<notify::Event>,
+    rx: &mpsc::Receiver<notify::Result<notify::Event>>,
+    debounce: Duration,
+) -> BTreeSet<PathBuf> {
+    let mut changed = BTreeSet::new();
+    collect_event_paths(first, &mut changed);
+    let mut deadline = Instant::now() + debounce;
+    while let Ok(event) = rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        if collect_event_paths(event, &mut changed) {
+            deadline = Instant::now() + debounce;
+        }
+    }
+    changed
+}
+
+/// Whether a changed `path` is one the watch of `folder` covers
+fn in_watch_scope(path: &Path, folder: &Path, recursive: bool) -> bool {
+    if recursive {
+        path.starts_with(folder)
+    } else {
+        path.parent() == Some(folder)
+    }
+}
+
+/// Whether `path` still has the `content` the watcher wrote to it, so that
+/// writing an output doesn't trigger another run
+fn is_own_write(written: &HashMap<PathBuf, u64>, path: &Path, content: Option<&[u8]>) -> bool {
+    written
+        .get(path)
+        .is_some_and(|&hash| content.map(content_hash) == Some(hash))
+}
+
+/// Add the paths touched by a create or modify event to `paths`, returning
+/// whether it was one
+fn collect_event_paths(
+    event: notify::Result<notify::Event>,
+    paths: &mut BTreeSet<PathBuf>,
+) -> bool {
+    match event {
+        Ok(event) if event.kind.is_create() || event.kind.is_modify() => {
+            for path in event.paths {
+                // Resolve symlinks and relative components so paths compare equal
+                paths.insert(fs::canonicalize(&path).unwrap_or(path));
+            }
+            true
+        }
+        Ok(_) => false,
+        Err(e) => {
+            eprintln!("Watch error: {}", e);
+            false
+        }
+    }
+}
+
+fn content_hash(content: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn list_svg_files(
+    folder: &Path,
+    recursive: bool,
+    exclude_patterns: &[&str],
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    list_files(folder, recursive, exclude_patterns, is_svg_file)
+}
+
+/// Files in `folder` for which `is_input` holds, sorted
+fn list_files(
+    folder: &Path,
+    recursive: bool,
+    exclude_patterns: &[&str],
+    is_input: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    if recursive {
+        find_files_recursive(folder, exclude_patterns, is_input)
+    } else {
+        find_files(folder, exclude_patterns, is_input)
+    }
+}
+
+fn find_files(
     dir: &Path,
     exclude_patterns: &[&str],
+    is_input: impl Fn(&Path) -> bool,
 ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
     let mut svg_files = Vec::new();
 
@@ -540,7 +2187,7 @@ fn find_svg_files(
         let entry = entry?;
         let path = entry.path();
 
-        if path.is_file() && is_svg_file(&path) && !is_excluded(&path, exclude_patterns)? {
+        if path.is_file() && is_input(&path) && !is_excluded(&path, exclude_patterns)? {
             svg_files.push(path);
         }
     }
@@ -549,9 +2196,10 @@ fn find_svg_files(
     Ok(svg_files)
 }
 
-fn find_svg_files_recursive(
+fn find_files_recursive(
     dir: &Path,
     exclude_patterns: &[&str],
+    is_input: impl Fn(&Path) -> bool,
 ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
     let mut svg_files = Vec::new();
     let mut dirs_to_process = vec![dir.to_path_buf()];
@@ -563,8 +2211,7 @@ fn find_svg_files_recursive(
 
             if path.is_dir() {
                 dirs_to_process.push(path);
-            } else if path.is_file() && is_svg_file(&path) && !is_excluded(&path, exclude_patterns)?
-            {
+            } else if path.is_file() && is_input(&path) && !is_excluded(&path, exclude_patterns)? {
                 svg_files.push(path);
             }
         }
@@ -577,10 +2224,85 @@ fn find_svg_files_recursive(
 fn is_svg_file(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
-        .map(|ext| ext.eq_ignore_ascii_case("svg"))
+        .map(|ext| ext.eq_ignore_ascii_case("svg") || ext.eq_ignore_ascii_case("svgz"))
+        .unwrap_or(false)
+}
+
+fn is_xml_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("xml"))
+        .unwrap_or(false)
+}
+
+/// Whether the inputs are VectorDrawables, given with `--input-format` or as
+/// `.xml` files
+fn reads_vector_drawables(matches: &clap::ArgMatches, config: &Config) -> bool {
+    match config.input_format {
+        Some(format) => format == InputFormat::VectorDrawable,
+        None => ["INPUT", "input"]
+            .iter()
+            .flat_map(|id| matches.get_many::<String>(id).into_iter().flatten())
+            .any(|input| is_xml_file(Path::new(input))),
+    }
+}
+
+fn is_svgz_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("svgz"))
         .unwrap_or(false)
 }
 
+/// Keep `.svgz` outputs compressed even without `--svgz`
+fn enable_svgz_for_output(config: &mut Config, output: &Path, options: &BatchOptions) {
+    if config.svgz.is_none() && is_svgz_file(output) {
+        config.svgz = Some(SvgzOptions::new(
+            options
+                .compression_level
+                .unwrap_or(DEFAULT_COMPRESSION_LEVEL),
+        ));
+    }
+}
+
+/// The bytes to write for a result: gzip-compressed when SVGZ output is enabled
+fn output_bytes(result: &OptimizationResult) -> &[u8] {
+    result
+        .compressed
+        .as_deref()
+        .unwrap_or(result.data.as_bytes())
+}
+
+/// List what the `sanitize` plugin removed
+fn print_removals(result: &OptimizationResult) {
+    for removal in result
+        .sanitize_report
+        .iter()
+        .flat_map(|report| &report.removed)
+    {
+        eprintln!("  Removed {}", removal);
+    }
+}
+
+/// Compressed input/output sizes for status messages, if any
+fn compressed_sizes(info: &OptimizationInfo) -> String {
+    let brotli = info
+        .brotli_size
+        .map(|size| format!(", brotli {}", format_bytes(size)))
+        .unwrap_or_default();
+    let gzip = match (info.original_compressed_size, info.compressed_size) {
+        (Some(original), Some(compressed)) => format!(
+            ", gzip {} → {}",
+            format_bytes(original),
+            format_bytes(compressed)
+        ),
+        (None, Some(compressed)) => format!(", gzip {}", format_bytes(compressed)),
+        (Some(original), None) => format!(", gzip input {}", format_bytes(original)),
+        (None, None) => String::new(),
+    };
+    gzip + &brotli
+}
+
 fn is_excluded(path: &Path, patterns: &[&str]) -> Result<bool, Box<dyn std::error::Error>> {
     if patterns.is_empty() {
         return Ok(false);
@@ -651,3 +2373,103 @@ fn format_bytes(bytes: usize) -> String {
         format!("{:.1} {}", size, UNITS[i])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, EventKind, ModifyKind, RemoveKind};
+
+    fn event(kind: EventKind, path: &str) -> notify::Result<notify::Event> {
+        Ok(notify::Event::new(kind).add_path(PathBuf::from(path)))
+    }
+
+    #[test]
+    fn test_debounce_events() {
+        let (tx, rx) = mpsc::channel();
+        tx.send(event(EventKind::Modify(ModifyKind::Any), "/w/b.svg"))
+            .unwrap();
+        tx.send(event(EventKind::Remove(RemoveKind::File), "/w/c.svg"))
+            .unwrap();
+        tx.send(event(EventKind::Modify(ModifyKind::Any), "/w/a.svg"))
+            .unwrap();
+        drop(tx);
+
+        let changed = debounce_events(
+            event(EventKind::Create(CreateKind::File), "/w/a.svg"),
+            &rx,
+            WATCH_DEBOUNCE,
+        );
+        assert_eq!(
+            changed.into_iter().collect::<Vec<_>>(),
+            [PathBuf::from("/w/a.svg"), PathBuf::from("/w/b.svg")]
+        );
+    }
+
+    #[test]
+    fn test_debounce_ignores_other_events() {
+        let (tx, rx) = mpsc::channel();
+        // Reads keep coming, but only changes extend the wait
+        std::thread::spawn(move || {
+            while tx
+                .send(event(EventKind::Access(AccessKind::Any), "/w/a.svg"))
+                .is_ok()
+            {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let start = Instant::now();
+        let changed = debounce_events(
+            event(EventKind::Create(CreateKind::File), "/w/a.svg"),
+            &rx,
+            Duration::from_millis(50),
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(changed.len(), 1);
+    }
+
+    #[test]
+    fn test_in_watch_scope() {
+        let folder = Path::new("/w");
+        assert!(in_watch_scope(Path::new("/w/a.svg"), folder, false));
+        assert!(!in_watch_scope(Path::new("/w/sub/a.svg"), folder, false));
+        assert!(in_watch_scope(Path::new("/w/sub/a.svg"), folder, true));
+        assert!(!in_watch_scope(Path::new("/other/a.svg"), folder, true));
+    }
+
+    #[test]
+    fn test_is_own_write() {
+        let path = Path::new("/w/a.svg");
+        let written = HashMap::from([(path.to_path_buf(), content_hash(b"<svg/>"))]);
+
+        assert!(is_own_write(&written, path, Some(b"<svg/>")));
+        // Edited since, unreadable, or never written by the watcher
+        assert!(!is_own_write(&written, path, Some(b"<svg></svg>")));
+        assert!(!is_own_write(&written, path, None));
+        assert!(!is_own_write(
+            &written,
+            Path::new("/w/b.svg"),
+            Some(b"<svg/>")
+        ));
+    }
+
+    #[test]
+    fn test_poll_fallback() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = watcher_or_poll(Err(notify::Error::generic("unavailable")), tx).unwrap();
+        watcher
+            .watch(dir.path(), RecursiveMode::NonRecursive)
+            .unwrap();
+
+        let path = fs::canonicalize(dir.path()).unwrap().join("a.svg");
+        fs::write(&path, "<svg/>").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut changed = BTreeSet::new();
+        while !changed.contains(&path) {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let event = rx.recv_timeout(timeout).expect("no event for a.svg");
+            collect_event_paths(event, &mut changed);
+        }
+    }
+}
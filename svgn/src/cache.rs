@@ -0,0 +1,117 @@
+// this_file: svgn/src/cache.rs
+
+//! On-disk cache of optimization results
+//!
+//! Entries are keyed by a SHA-256 hash of the input bytes, the effective
+//! configuration and the library version, so a cached output is only reused
+//! when re-optimizing would produce exactly the same result.
+
+use crate::config::{Config, ConfigResult};
+use crate::VERSION;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Default cache directory, relative to the working directory
+pub const DEFAULT_CACHE_DIR: &str = ".svgn-cache";
+
+/// Content-addressed store of optimized SVG outputs
+#[derive(Debug, Clone)]
+pub struct Cache {
+    /// Root directory of the cache
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Open (without creating) a cache rooted at `dir`
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Root directory of the cache
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Compute the cache key for optimizing `input` with `config`
+    pub fn key(input: &[u8], config: &Config) -> ConfigResult<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(VERSION.as_bytes());
+        hasher.update([0]);
+        hasher.update(config.to_json()?.as_bytes());
+        hasher.update([0]);
+        hasher.update(input);
+
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    /// Look up a cached output
+    pub fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.entry_path(key)).ok()
+    }
+
+    /// Store an output under `key`
+    ///
+    /// The entry is written to a temporary file and renamed into place, so
+    /// concurrent readers never observe a partially written entry.
+    pub fn put(&self, key: &str, output: &str) -> io::Result<()> {
+        let path = self.entry_path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp_path, output)?;
+        fs::rename(&tmp_path, &path)
+    }
+
+    /// Entries are sharded by the first two hex digits to keep directories small
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let (shard, rest) = key.split_at(2.min(key.len()));
+        self.dir.join(shard).join(rest)
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_DIR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginConfig;
+
+    #[test]
+    fn test_key_depends_on_input_and_config() {
+        let config = Config::with_default_preset();
+        let key = Cache::key(b"<svg/>", &config).unwrap();
+
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, Cache::key(b"<svg/>", &config).unwrap());
+        assert_ne!(key, Cache::key(b"<svg></svg>", &config).unwrap());
+
+        let mut other_config = config.clone();
+        other_config.add_plugin(PluginConfig::new("removeDimensions".to_string()));
+        assert_ne!(key, Cache::key(b"<svg/>", &other_config).unwrap());
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = Cache::new(dir.path());
+        let key = Cache::key(b"<svg/>", &Config::new()).unwrap();
+
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, "<svg/>").unwrap();
+        assert_eq!(cache.get(&key).as_deref(), Some("<svg/>"));
+    }
+}
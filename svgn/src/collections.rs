@@ -563,6 +563,20 @@ pub static REFERENCES_PROPS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     ])
 });
 
+/// ARIA attributes whose value is a space-separated list of element IDs
+pub static ARIA_IDREF_ATTRS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    HashSet::from([
+        "aria-activedescendant",
+        "aria-controls",
+        "aria-describedby",
+        "aria-details",
+        "aria-errormessage",
+        "aria-flowto",
+        "aria-labelledby",
+        "aria-owns",
+    ])
+});
+
 /// Editor-specific namespaces in SVG
 pub static EDITOR_NAMESPACES: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     HashSet::from([
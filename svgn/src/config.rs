@@ -5,9 +5,15 @@
 //! This module provides structures and functions for handling configuration
 //! compatible with SVGO's configuration format.
 
+use crate::entities::EntityLimits;
+use crate::export::{ComponentOptions, OutputFormat};
+use crate::import::InputFormat;
+use crate::lint::LintConfig;
 use crate::plugin::PluginConfig;
+use crate::svgz::SvgzOptions;
 use serde::{Deserialize, Deserializer, Serialize};
-use std::path::Path;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Configuration error types
@@ -43,6 +49,11 @@ pub struct Config {
     #[serde(default)]
     pub multipass: bool,
 
+    /// Keep accessible names and mark up the root for assistive technology
+    /// (see [`crate::a11y`])
+    #[serde(default)]
+    pub a11y: bool,
+
     /// Output formatting options
     #[serde(default)]
     pub js2svg: Js2SvgOptions,
@@ -51,9 +62,34 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub datauri: Option<DataUriFormat>,
 
+    /// Gzip-compress the output (SVGZ)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub svgz: Option<SvgzOptions>,
+
+    /// Size the optimizer minimizes when choosing between equivalent outputs
+    #[serde(default, skip_serializing_if = "SizeMetric::is_raw")]
+    pub size_metric: SizeMetric,
+
     /// Parser options
     #[serde(default)]
     pub parser: ParserOptions,
+
+    /// Lint rule severities and options (see [`crate::lint`])
+    #[serde(default, skip_serializing_if = "LintConfig::is_empty")]
+    pub lint: LintConfig,
+
+    /// Read the input as SVG or as a VectorDrawable (see [`crate::import`]);
+    /// detected from the extension of [`Self::path`] if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_format: Option<InputFormat>,
+
+    /// Write the result as SVG or as a component (see [`crate::export`])
+    #[serde(default, skip_serializing_if = "OutputFormat::is_svg")]
+    pub format: OutputFormat,
+
+    /// Options for component output formats
+    #[serde(default, skip_serializing_if = "ComponentOptions::is_default")]
+    pub component: ComponentOptions,
 }
 
 /// Output formatting options (equivalent to SVGO's js2svg)
@@ -67,8 +103,8 @@ pub struct Js2SvgOptions {
     #[serde(default = "default_indent")]
     pub indent: usize,
 
-    /// Use self-closing tags for empty elements
-    #[serde(default = "default_true")]
+    /// Use self-closing tags for empty elements (SVGO's `useShortTags`)
+    #[serde(default = "default_true", alias = "use_short_tags")]
     pub self_closing: bool,
 
     /// Quote attributes (always, never, auto)
@@ -82,6 +118,56 @@ pub struct Js2SvgOptions {
     /// Ensure final newline
     #[serde(default)]
     pub final_newline: bool,
+
+    /// Keep the source formatting of unmodified elements
+    #[serde(default)]
+    pub preserve_formatting: bool,
+
+    /// Whether to keep, strip or force the XML declaration
+    #[serde(default)]
+    pub xml_declaration: XmlDeclaration,
+
+    /// Text between an attribute name and its value, e.g. `="`; overrides `quote_attrs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attr_start: Option<String>,
+
+    /// Text after an attribute value, e.g. `"`; overrides `quote_attrs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attr_end: Option<String>,
+
+    /// Text written before each text node
+    #[serde(default)]
+    pub text_start: String,
+
+    /// Text written after each text node
+    #[serde(default)]
+    pub text_end: String,
+
+    /// Start of the DOCTYPE declaration
+    #[serde(default = "default_doctype_start")]
+    pub doctype_start: String,
+
+    /// End of the DOCTYPE declaration
+    #[serde(default = "default_doctype_end")]
+    pub doctype_end: String,
+
+    /// Emit SVG for inlining into HTML5: no XML declaration, DOCTYPE or
+    /// `xmlns` attributes, and every attribute written with an explicit value
+    #[serde(default)]
+    pub html: bool,
+}
+
+/// How the XML declaration is written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum XmlDeclaration {
+    /// Write the declaration if the document has one
+    #[default]
+    Keep,
+    /// Never write a declaration
+    Strip,
+    /// Always write a declaration, even if the input had none
+    Force,
 }
 
 /// Data URI output formats
@@ -96,6 +182,29 @@ pub enum DataUriFormat {
     Unenc,
 }
 
+/// How output size is measured when tuning the optimization
+///
+/// With a compressed metric, alternative orderings and encodings (plugin
+/// order, `sortAttrs` order, minified ID alphabet, absolute vs. relative path
+/// commands) are tried and the output that compresses best is kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeMetric {
+    /// Uncompressed length; no tuning
+    #[default]
+    Raw,
+    /// Gzip-compressed length
+    Gzip,
+    /// Brotli-compressed length
+    Brotli,
+}
+
+impl SizeMetric {
+    pub fn is_raw(&self) -> bool {
+        *self == SizeMetric::Raw
+    }
+}
+
 /// Attribute quoting styles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -146,6 +255,15 @@ pub struct ParserOptions {
     /// Preserve comments
     #[serde(default)]
     pub preserve_comments: bool,
+
+    /// Expand entities declared in the DOCTYPE; when false, references are
+    /// kept as written and removeDoctype keeps the DOCTYPE
+    #[serde(default = "default_true")]
+    pub expand_entities: bool,
+
+    /// Limits on entity expansion
+    #[serde(default)]
+    pub entity_limits: EntityLimits,
 }
 
 // Default value functions for serde
@@ -158,6 +276,12 @@ fn default_true() -> bool {
 fn default_quote_attrs() -> QuoteAttrsStyle {
     QuoteAttrsStyle::Auto
 }
+fn default_doctype_start() -> String {
+    "<!DOCTYPE".to_string()
+}
+fn default_doctype_end() -> String {
+    ">".to_string()
+}
 
 impl Default for Js2SvgOptions {
     fn default() -> Self {
@@ -168,6 +292,15 @@ impl Default for Js2SvgOptions {
             quote_attrs: QuoteAttrsStyle::Auto,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            xml_declaration: XmlDeclaration::Keep,
+            attr_start: None,
+            attr_end: None,
+            text_start: String::new(),
+            text_end: String::new(),
+            doctype_start: default_doctype_start(),
+            doctype_end: default_doctype_end(),
+            html: false,
         }
     }
 }
@@ -177,6 +310,8 @@ impl Default for ParserOptions {
         Self {
             preserve_whitespace: false,
             preserve_comments: true, // Must be true for removeComments plugin to work
+            expand_entities: true,
+            entity_limits: EntityLimits::default(),
         }
     }
 }
@@ -250,6 +385,25 @@ impl Config {
         self.plugins.iter_mut().find(|p| p.name == name)
     }
 
+    /// The format to read the input in, from [`Self::input_format`] or the
+    /// extension of [`Self::path`]
+    pub fn effective_input_format(&self) -> InputFormat {
+        self.input_format.unwrap_or_else(|| {
+            self.path.as_deref().map_or(InputFormat::Svg, |path| {
+                InputFormat::from_path(Path::new(path))
+            })
+        })
+    }
+
+    /// The plugins to run, adjusted for [`Self::a11y`]
+    pub fn effective_plugins(&self) -> Cow<'_, [PluginConfig]> {
+        if self.a11y {
+            Cow::Owned(crate::a11y::adjust_plugins(&self.plugins))
+        } else {
+            Cow::Borrowed(&self.plugins)
+        }
+    }
+
     /// Enable or disable a plugin
     pub fn set_plugin_enabled(&mut self, name: &str, enabled: bool) {
         if let Some(plugin) = self.get_plugin_mut(name) {
@@ -301,28 +455,32 @@ impl Config {
     }
 }
 
-/// Load configuration from common file names in the given directory
-pub fn load_config_from_directory<P: AsRef<Path>>(dir: P) -> ConfigResult<Option<Config>> {
+/// Common config file names (in order of preference)
+const CONFIG_FILE_NAMES: [&str; 6] = [
+    "svgn.config.toml",
+    "svgn.config.json",
+    "svgo.config.json",
+    "svgo.config.js",
+    "svgo.config.cjs",
+    "svgo.config.mjs",
+];
+
+/// Find the config file that [`load_config_from_directory`] would load
+pub fn find_config_file<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
     let dir = dir.as_ref();
 
-    // Common config file names (in order of preference)
-    let config_names = [
-        "svgn.config.toml",
-        "svgn.config.json",
-        "svgo.config.json",
-        "svgo.config.js",
-        "svgo.config.cjs",
-        "svgo.config.mjs",
-    ];
-
-    for name in &config_names {
-        let path = dir.join(name);
-        if path.exists() {
-            return Ok(Some(Config::from_file(path)?));
-        }
-    }
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
 
-    Ok(None)
+/// Load configuration from common file names in the given directory
+pub fn load_config_from_directory<P: AsRef<Path>>(dir: P) -> ConfigResult<Option<Config>> {
+    match find_config_file(dir) {
+        Some(path) => Ok(Some(Config::from_file(path)?)),
+        None => Ok(None),
+    }
 }
 
 /// Custom deserializer for plugins that can handle both string and object formats
@@ -0,0 +1,449 @@
+// this_file: svgn/src/diff.rs
+
+//! Reviewable diffs of optimization results
+//!
+//! This module renders the difference between an input SVG and its optimized
+//! form, either as a line-based unified diff of pretty-printed markup or as a
+//! list of structural changes attributed to the plugin that made them.
+
+use crate::ast::{Document, Element, Node};
+use crate::config::Config;
+use crate::optimizer::OptimizeResult;
+use crate::parser::Parser;
+use crate::plugin::{PluginInfo, PluginObserver, PluginRegistry};
+use crate::stringifier::Stringifier;
+use serde::Serialize;
+use similar::{Algorithm, DiffOp, TextDiff};
+use std::ops::Range;
+
+/// A single structural change made by a plugin
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    /// Name of the plugin that made the change
+    pub plugin: String,
+    /// Optimization pass the change was made in (1-based)
+    pub pass: usize,
+    /// XPath-like location of the affected element, e.g. `/svg/g[2]/path[1]`
+    pub path: String,
+    /// What changed
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+}
+
+/// Kinds of structural changes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// An attribute was added to the element
+    AttributeAdded { name: String, value: String },
+    /// An attribute was removed from the element
+    AttributeRemoved { name: String, value: String },
+    /// An attribute value was rewritten
+    AttributeChanged {
+        name: String,
+        old_value: String,
+        new_value: String,
+    },
+    /// A child node was inserted; `node` is the element name or `#text`,
+    /// `#comment`, `#cdata`, `#doctype`, `?target`
+    NodeAdded { node: String },
+    /// A child node was removed
+    NodeRemoved { node: String },
+    /// The content of a text, comment or CDATA node was rewritten
+    ContentChanged {
+        node: String,
+        old_value: String,
+        new_value: String,
+    },
+}
+
+/// Parse an SVG string and re-serialize it pretty-printed
+///
+/// Comments are kept so that their removal shows up in diffs.
+pub fn pretty_print(svg: &str) -> OptimizeResult<String> {
+    let document = Parser::new().preserve_comments(true).parse(svg)?;
+    Ok(Stringifier::new().pretty(true).stringify(&document)?)
+}
+
+/// Render a unified diff between an input SVG and its optimized output
+///
+/// Both sides are pretty-printed first, so changes show up per element and
+/// attribute rather than as a single rewritten line.
+pub fn unified_diff(original: &str, optimized: &str, path: &str) -> OptimizeResult<String> {
+    let before = pretty_print(original)?;
+    let after = pretty_print(optimized)?;
+
+    Ok(TextDiff::from_lines(&before, &after)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string())
+}
+
+/// Optimize `input` plugin by plugin and record which plugin changed what
+///
+/// The optimizer runs as usual and the document is snapshotted before every
+/// plugin and compared afterwards, so this is considerably slower than
+/// [`crate::optimize`]; it is meant for reviewing a configuration, not for
+/// production runs.
+pub fn trace_changes(
+    input: &str,
+    config: &Config,
+    registry: &mut PluginRegistry,
+) -> OptimizeResult<Vec<Change>> {
+    let mut tracer = Tracer::default();
+    crate::optimizer::optimize_observed(input, config, registry, &mut tracer)?;
+    Ok(tracer.changes)
+}
+
+/// Records the changes of each plugin the optimizer applies
+#[derive(Default)]
+struct Tracer {
+    /// Document before the current plugin
+    before: Option<Document>,
+    changes: Vec<Change>,
+}
+
+impl PluginObserver for Tracer {
+    fn before_plugin(&mut self, _plugin: &str, _plugin_info: &PluginInfo, document: &Document) {
+        self.before = Some(document.clone());
+    }
+
+    fn after_plugin(&mut self, plugin: &str, plugin_info: &PluginInfo, document: &Document) {
+        let Some(before) = self.before.take() else {
+            return;
+        };
+        if before != *document {
+            let mut recorder = Recorder {
+                plugin,
+                pass: plugin_info.multipass_count + 1,
+                changes: &mut self.changes,
+            };
+            recorder.diff_documents(&before, document);
+        }
+    }
+}
+
+/// Collects changes for a single plugin application
+struct Recorder<'a> {
+    plugin: &'a str,
+    pass: usize,
+    changes: &'a mut Vec<Change>,
+}
+
+impl Recorder<'_> {
+    fn push(&mut self, path: String, kind: ChangeKind) {
+        self.changes.push(Change {
+            plugin: self.plugin.to_string(),
+            pass: self.pass,
+            path,
+            kind,
+        });
+    }
+
+    fn diff_documents(&mut self, before: &Document, after: &Document) {
+        if before.metadata.version != after.metadata.version
+            || before.metadata.encoding != after.metadata.encoding
+        {
+            let kind = match (&before.metadata.version, &after.metadata.version) {
+                (Some(_), None) => ChangeKind::NodeRemoved {
+                    node: "?xml".to_string(),
+                },
+                (None, Some(_)) => ChangeKind::NodeAdded {
+                    node: "?xml".to_string(),
+                },
+                _ => ChangeKind::ContentChanged {
+                    node: "?xml".to_string(),
+                    old_value: xml_declaration(before),
+                    new_value: xml_declaration(after),
+                },
+            };
+            self.push("/".to_string(), kind);
+        }
+
+        self.diff_children("", &before.prologue, &after.prologue);
+
+        let root_path = format!("/{}", after.root.name);
+        if before.root.name == after.root.name {
+            self.diff_elements(&root_path, &before.root, &after.root);
+        } else {
+            self.push(
+                "/".to_string(),
+                ChangeKind::NodeRemoved {
+                    node: before.root.name.clone(),
+                },
+            );
+            self.push(
+                "/".to_string(),
+                ChangeKind::NodeAdded {
+                    node: after.root.name.clone(),
+                },
+            );
+        }
+
+        self.diff_children("", &before.epilogue, &after.epilogue);
+    }
+
+    fn diff_elements(&mut self, path: &str, before: &Element, after: &Element) {
+        for (name, old_value) in &before.attributes {
+            match after.attributes.get(name) {
+                None => self.push(
+                    path.to_string(),
+                    ChangeKind::AttributeRemoved {
+                        name: name.clone(),
+                        value: old_value.clone(),
+                    },
+                ),
+                Some(new_value) if new_value != old_value => self.push(
+                    path.to_string(),
+                    ChangeKind::AttributeChanged {
+                        name: name.clone(),
+                        old_value: old_value.clone(),
+                        new_value: new_value.clone(),
+                    },
+                ),
+                Some(_) => {}
+            }
+        }
+        for (name, value) in &after.attributes {
+            if !before.attributes.contains_key(name) {
+                self.push(
+                    path.to_string(),
+                    ChangeKind::AttributeAdded {
+                        name: name.clone(),
+                        value: value.clone(),
+                    },
+                );
+            }
+        }
+
+        self.diff_children(path, &before.children, &after.children);
+    }
+
+    /// Align two child lists by node kind/name and diff matched pairs
+    fn diff_children(&mut self, path: &str, before: &[Node], after: &[Node]) {
+        if before == after {
+            return;
+        }
+
+        let parent = if path.is_empty() { "/" } else { path };
+        let before_keys: Vec<String> = before.iter().map(node_key).collect();
+        let after_keys: Vec<String> = after.iter().map(node_key).collect();
+        let ops = similar::capture_diff_slices(Algorithm::Myers, &before_keys, &after_keys);
+
+        for op in ops {
+            match op {
+                DiffOp::Equal {
+                    old_index,
+                    new_index,
+                    len,
+                } => {
+                    for offset in 0..len {
+                        let old_node = &before[old_index + offset];
+                        let new_node = &after[new_index + offset];
+                        self.diff_matched(path, after, new_index + offset, old_node, new_node);
+                    }
+                }
+                DiffOp::Delete {
+                    old_index, old_len, ..
+                } => self.record_removed(parent, before, old_index..old_index + old_len),
+                DiffOp::Insert {
+                    new_index, new_len, ..
+                } => self.record_added(parent, after, new_index..new_index + new_len),
+                DiffOp::Replace {
+                    old_index,
+                    old_len,
+                    new_index,
+                    new_len,
+                } => {
+                    self.record_removed(parent, before, old_index..old_index + old_len);
+                    self.record_added(parent, after, new_index..new_index + new_len);
+                }
+            }
+        }
+    }
+
+    fn diff_matched(
+        &mut self,
+        path: &str,
+        siblings: &[Node],
+        index: usize,
+        before: &Node,
+        after: &Node,
+    ) {
+        if before == after {
+            return;
+        }
+
+        match (before, after) {
+            (Node::Element(old_element), Node::Element(new_element)) => {
+                let child_path = element_path(path, siblings, index);
+                self.diff_elements(&child_path, old_element, new_element);
+            }
+            _ => {
+                let parent = if path.is_empty() { "/" } else { path };
+                self.push(
+                    parent.to_string(),
+                    ChangeKind::ContentChanged {
+                        node: node_key(before),
+                        old_value: node_content(before),
+                        new_value: node_content(after),
+                    },
+                );
+            }
+        }
+    }
+
+    fn record_removed(&mut self, parent: &str, nodes: &[Node], range: Range<usize>) {
+        for node in &nodes[range] {
+            self.push(
+                parent.to_string(),
+                ChangeKind::NodeRemoved {
+                    node: node_key(node),
+                },
+            );
+        }
+    }
+
+    fn record_added(&mut self, parent: &str, nodes: &[Node], range: Range<usize>) {
+        for node in &nodes[range] {
+            self.push(
+                parent.to_string(),
+                ChangeKind::NodeAdded {
+                    node: node_key(node),
+                },
+            );
+        }
+    }
+}
+
+/// Key used to align sibling nodes between two snapshots
+fn node_key(node: &Node) -> String {
+    match node {
+        Node::Element(element) => element.name.clone(),
+        Node::Text(_) => "#text".to_string(),
+        Node::Comment(_) => "#comment".to_string(),
+        Node::CData(_) => "#cdata".to_string(),
+        Node::DocType(_) => "#doctype".to_string(),
+        Node::ProcessingInstruction { target, .. } => format!("?{}", target),
+    }
+}
+
+/// Textual content of a non-element node
+fn node_content(node: &Node) -> String {
+    match node {
+        Node::Text(text) | Node::Comment(text) | Node::CData(text) | Node::DocType(text) => {
+            text.clone()
+        }
+        Node::ProcessingInstruction { data, .. } => data.clone(),
+        Node::Element(element) => element.name.clone(),
+    }
+}
+
+/// Build the path of the element at `index` among `siblings`
+fn element_path(parent: &str, siblings: &[Node], index: usize) -> String {
+    let name = match &siblings[index] {
+        Node::Element(element) => &element.name,
+        _ => return parent.to_string(),
+    };
+    let position = siblings[..index]
+        .iter()
+        .filter(|node| matches!(node, Node::Element(e) if &e.name == name))
+        .count()
+        + 1;
+
+    format!("{}/{}[{}]", parent, name, position)
+}
+
+fn xml_declaration(document: &Document) -> String {
+    format!(
+        "version={} encoding={}",
+        document.metadata.version.as_deref().unwrap_or(""),
+        document.metadata.encoding.as_deref().unwrap_or("")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{create_default_registry, PluginConfig};
+
+    #[test]
+    fn test_unified_diff_is_per_attribute() {
+        let original =
+            r##"<svg xmlns="http://www.w3.org/2000/svg"><rect x="1" fill="#ff0000"/></svg>"##;
+        let optimized = r#"<svg xmlns="http://www.w3.org/2000/svg"><rect x="1" fill="red"/></svg>"#;
+
+        let diff = unified_diff(original, optimized, "icon.svg").unwrap();
+        assert!(diff.contains("--- a/icon.svg"));
+        assert!(diff.contains("+++ b/icon.svg"));
+        assert!(diff.contains(r##"-  <rect x="1" fill="#ff0000"/>"##));
+        assert!(diff.contains(r#"+  <rect x="1" fill="red"/>"#));
+    }
+
+    #[test]
+    fn test_unified_diff_identical() {
+        let svg = r#"<svg><rect/></svg>"#;
+        assert!(unified_diff(svg, svg, "icon.svg").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trace_changes_attributes_plugins() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg"><!-- c --><g><rect fill="#ff0000"/></g><g><rect fill="#ff0000" stroke=""/></g></svg>"##;
+        let mut config = Config::new();
+        config.parser.preserve_comments = true;
+        config.add_plugin(PluginConfig::new("removeComments".to_string()));
+        config.add_plugin(PluginConfig::new("removeEmptyAttrs".to_string()));
+        config.add_plugin(PluginConfig::new("convertColors".to_string()));
+
+        let changes = trace_changes(svg, &config, &mut create_default_registry()).unwrap();
+
+        assert!(changes.contains(&Change {
+            plugin: "removeComments".to_string(),
+            pass: 1,
+            path: "/svg".to_string(),
+            kind: ChangeKind::NodeRemoved {
+                node: "#comment".to_string()
+            },
+        }));
+        assert!(changes.contains(&Change {
+            plugin: "removeEmptyAttrs".to_string(),
+            pass: 1,
+            path: "/svg/g[2]/rect[1]".to_string(),
+            kind: ChangeKind::AttributeRemoved {
+                name: "stroke".to_string(),
+                value: String::new()
+            },
+        }));
+        let color_changes: Vec<_> = changes
+            .iter()
+            .filter(|c| c.plugin == "convertColors")
+            .collect();
+        assert_eq!(color_changes.len(), 2);
+        assert_eq!(color_changes[0].path, "/svg/g[1]/rect[1]");
+    }
+
+    #[test]
+    fn test_trace_changes_of_imported_input() {
+        let drawable = r##"<vector xmlns:android="http://schemas.android.com/apk/res/android" android:viewportWidth="24" android:viewportHeight="24"><path android:fillColor="#FFFF0000" android:pathData="M0 0L24 0L24 24z"/></vector>"##;
+        let mut config = Config::new();
+        config.path = Some("ic_triangle.xml".to_string());
+        config.add_plugin(PluginConfig::new("convertColors".to_string()));
+
+        let changes = trace_changes(drawable, &config, &mut create_default_registry()).unwrap();
+
+        assert_eq!(
+            changes,
+            [Change {
+                plugin: "convertColors".to_string(),
+                pass: 1,
+                path: "/svg/path[1]".to_string(),
+                kind: ChangeKind::AttributeChanged {
+                    name: "fill".to_string(),
+                    old_value: "#ff0000".to_string(),
+                    new_value: "#f00".to_string(),
+                },
+            }]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+// this_file: svgn/src/encoding.rs
+
+//! Input encoding detection
+//!
+//! SVG files are not always UTF-8: older Windows tools export UTF-16 with a
+//! byte order mark, and some files declare a legacy encoding such as
+//! `ISO-8859-1` in their XML declaration. This module detects the encoding
+//! of raw input bytes the way an XML processor does and transcodes the
+//! document to UTF-8.
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use thiserror::Error;
+
+/// Encoding detection error types
+#[derive(Error, Debug)]
+pub enum EncodingError {
+    #[error("Unsupported encoding: {0}")]
+    UnsupportedEncoding(String),
+    #[error("Input is not valid {0}")]
+    InvalidData(String),
+}
+
+/// Encoding result type
+pub type EncodingResult<T> = Result<T, EncodingError>;
+
+/// Input transcoded to UTF-8
+#[derive(Debug, Clone)]
+pub struct DecodedInput {
+    /// Document text without byte order mark
+    pub text: String,
+    /// Original encoding, as declared or detected (e.g. `UTF-16LE`)
+    pub encoding: String,
+}
+
+impl DecodedInput {
+    /// Whether the input had to be transcoded
+    pub fn is_utf8(&self) -> bool {
+        Encoding::for_label(self.encoding.as_bytes()) == Some(UTF_8)
+    }
+}
+
+/// Detect the encoding of `input` and transcode it to UTF-8
+///
+/// Detection follows the XML specification: a byte order mark wins, then a
+/// UTF-16 `<` pattern without BOM, then the `encoding` pseudo-attribute of
+/// the XML declaration. Everything else is treated as UTF-8.
+pub fn decode(input: &[u8]) -> EncodingResult<DecodedInput> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(input) {
+        return transcode(&input[bom_length..], encoding, encoding.name());
+    }
+
+    match input {
+        [0x3c, 0x00, ..] => return transcode(input, UTF_16LE, UTF_16LE.name()),
+        [0x00, 0x3c, ..] => return transcode(input, UTF_16BE, UTF_16BE.name()),
+        _ => {}
+    }
+
+    match declared_encoding(input) {
+        Some(label) => {
+            let encoding = Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| EncodingError::UnsupportedEncoding(label.clone()))?;
+            // A UTF-16 label on ASCII-compatible bytes can only mean the file
+            // was re-saved as UTF-8 without updating the declaration
+            transcode(input, encoding.output_encoding(), &label)
+        }
+        None => transcode(input, UTF_8, UTF_8.name()),
+    }
+}
+
+fn transcode(
+    bytes: &[u8],
+    encoding: &'static Encoding,
+    name: &str,
+) -> EncodingResult<DecodedInput> {
+    let text = encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .ok_or_else(|| EncodingError::InvalidData(name.to_string()))?;
+
+    Ok(DecodedInput {
+        text: text.into_owned(),
+        encoding: name.to_string(),
+    })
+}
+
+/// Read the `encoding` pseudo-attribute of an ASCII-compatible XML declaration
+fn declared_encoding(input: &[u8]) -> Option<String> {
+    let rest = input.strip_prefix(b"<?xml")?;
+    let end = rest.windows(2).take(1024).position(|w| w == b"?>")?;
+    let declaration = std::str::from_utf8(&rest[..end]).ok()?;
+
+    let after = &declaration[declaration.find("encoding")? + "encoding".len()..];
+    let after = after.trim_start().strip_prefix('=')?.trim_start();
+    let quote = after.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &after[1..];
+    Some(value[..value.find(quote)?].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_without_declaration() {
+        let decoded = decode("<svg><text>é</text></svg>".as_bytes()).unwrap();
+        assert_eq!(decoded.text, "<svg><text>é</text></svg>");
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert!(decoded.is_utf8());
+    }
+
+    #[test]
+    fn test_utf8_bom_is_stripped() {
+        let decoded = decode(b"\xef\xbb\xbf<svg/>").unwrap();
+        assert_eq!(decoded.text, "<svg/>");
+        assert!(decoded.is_utf8());
+    }
+
+    #[test]
+    fn test_utf16() {
+        let svg = "<?xml version=\"1.0\" encoding=\"UTF-16\"?><svg>é</svg>";
+        let le: Vec<u8> = svg.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let be: Vec<u8> = svg.encode_utf16().flat_map(u16::to_be_bytes).collect();
+
+        let decoded = decode(&[&[0xff, 0xfe][..], &le].concat()).unwrap();
+        assert_eq!(decoded.text, svg);
+        assert_eq!(decoded.encoding, "UTF-16LE");
+
+        let decoded = decode(&be).unwrap();
+        assert_eq!(decoded.text, svg);
+        assert_eq!(decoded.encoding, "UTF-16BE");
+        assert!(!decoded.is_utf8());
+    }
+
+    #[test]
+    fn test_declared_latin1() {
+        let decoded =
+            decode(b"<?xml version=\"1.0\" encoding='ISO-8859-1' ?><svg>\xe9</svg>").unwrap();
+        assert!(decoded.text.ends_with("<svg>é</svg>"));
+        assert_eq!(decoded.encoding, "ISO-8859-1");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            decode(b"<?xml version=\"1.0\" encoding=\"x-unknown\"?><svg/>"),
+            Err(EncodingError::UnsupportedEncoding(_))
+        ));
+        assert!(matches!(
+            decode(b"<svg>\xe9</svg>"),
+            Err(EncodingError::InvalidData(_))
+        ));
+    }
+}
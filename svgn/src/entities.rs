@@ -0,0 +1,524 @@
+// this_file: svgn/src/entities.rs
+
+//! XML entity declarations and expansion
+//!
+//! Illustrator and a few other tools declare entities in the DOCTYPE internal
+//! subset (`<!ENTITY ns_svg "http://www.w3.org/2000/svg">`) and reference them
+//! from attribute values and text. This module reads those declarations and
+//! expands references, including entities that reference other entities.
+//! Expansion is bounded by [`EntityLimits`] so that a "billion laughs"
+//! document fails to parse instead of exhausting memory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error;
+
+/// Entity expansion error types
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EntityError {
+    #[error("entity `{0}` references itself")]
+    Recursive(String),
+    #[error("entity `{name}` is nested more than {max} levels deep")]
+    TooDeep { name: String, max: usize },
+    #[error("more than {0} entity references expanded")]
+    TooManyExpansions(usize),
+    #[error("entity expansion produced more than {0} bytes")]
+    TooLarge(usize),
+    #[error("invalid character reference `&{0};`")]
+    InvalidCharRef(String),
+}
+
+/// Entity result type
+pub type EntityResult<T> = Result<T, EntityError>;
+
+/// Limits on entity expansion
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityLimits {
+    /// Deepest chain of entities referencing entities
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    /// Number of entity references expanded in the whole document
+    #[serde(default = "default_max_expansions")]
+    pub max_expansions: usize,
+    /// Total bytes of replacement text inserted in the whole document
+    #[serde(default = "default_max_size")]
+    pub max_size: usize,
+}
+
+fn default_max_depth() -> usize {
+    16
+}
+fn default_max_expansions() -> usize {
+    10_000
+}
+fn default_max_size() -> usize {
+    10 * 1024 * 1024
+}
+
+impl Default for EntityLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: default_max_depth(),
+            max_expansions: default_max_expansions(),
+            max_size: default_max_size(),
+        }
+    }
+}
+
+/// A general entity declared in the internal subset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityDecl {
+    /// `<!ENTITY name "replacement text">`
+    Internal(String),
+    /// `<!ENTITY name SYSTEM "uri">`, which is never fetched
+    External,
+}
+
+/// Parse the general entity declarations of a DOCTYPE
+///
+/// `doctype` is the content of the declaration, as in
+/// `svg PUBLIC "..." "..." [ <!ENTITY ...> ]`. Parameter entities and other
+/// markup declarations are skipped. As in XML, the first declaration of a
+/// name wins.
+pub fn parse_declarations(doctype: &str) -> HashMap<String, EntityDecl> {
+    let mut declarations = HashMap::new();
+    let Some(mut rest) = internal_subset(doctype) else {
+        return declarations;
+    };
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() || rest.starts_with(']') {
+            break;
+        }
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+        } else if let Some(after) = rest.strip_prefix("<?") {
+            rest = after.find("?>").map_or("", |end| &after[end + 2..]);
+        } else if let Some(after) = rest.strip_prefix("<!ENTITY") {
+            let (declaration, after) = split_declaration(after);
+            if let Some((name, decl)) = parse_entity(declaration) {
+                declarations.entry(name).or_insert(decl);
+            }
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            rest = split_declaration(after).1;
+        } else if rest.starts_with('%') {
+            // Parameter entity reference; external subsets are not read
+            rest = rest.find(';').map_or("", |end| &rest[end + 1..]);
+        } else {
+            let skip = rest.chars().next().map_or(1, char::len_utf8);
+            rest = &rest[skip..];
+        }
+    }
+
+    declarations
+}
+
+/// Content of the `[...]` internal subset, if any
+fn internal_subset(doctype: &str) -> Option<&str> {
+    let mut quote = None;
+    for (index, ch) in doctype.char_indices() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '[') => return Some(&doctype[index + 1..]),
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+/// Split a markup declaration at its closing `>`, ignoring quoted text
+fn split_declaration(text: &str) -> (&str, &str) {
+    let mut quote = None;
+    for (index, ch) in text.char_indices() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '>') => return (&text[..index], &text[index + 1..]),
+            (None, _) => {}
+        }
+    }
+    (text, "")
+}
+
+/// Parse the part of `<!ENTITY ...>` after the keyword
+fn parse_entity(declaration: &str) -> Option<(String, EntityDecl)> {
+    let declaration = declaration.trim_start();
+    if declaration.starts_with('%') {
+        // Parameter entities only matter inside the DTD
+        return None;
+    }
+    let name_end = declaration.find(char::is_whitespace)?;
+    let name = &declaration[..name_end];
+    let definition = declaration[name_end..].trim_start();
+
+    let decl = match definition.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let value = &definition[1..];
+            let end = value.find(quote)?;
+            EntityDecl::Internal(value[..end].to_string())
+        }
+        _ if definition.starts_with("SYSTEM") || definition.starts_with("PUBLIC") => {
+            EntityDecl::External
+        }
+        _ => return None,
+    };
+    Some((name.to_string(), decl))
+}
+
+/// Whether `name` can be the name of an entity or character reference
+fn is_reference_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name
+            .chars()
+            .any(|ch| ch.is_whitespace() || matches!(ch, '&' | '<' | '>' | '"' | '\''))
+}
+
+/// Value of one of the five predefined XML entities
+fn predefined(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => None,
+    }
+}
+
+/// Character of a `&#...;` reference, given the name without the `#`
+fn char_ref(number: &str) -> Option<char> {
+    let code = match number.strip_prefix('x') {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => number.parse().ok()?,
+    };
+    char::from_u32(code).filter(|ch| *ch != '\0')
+}
+
+/// What a reference in element content stands for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Replacement {
+    /// Plain text, fully expanded
+    Text(String),
+    /// Replacement text containing elements, to be parsed as content
+    ///
+    /// The entity stays open until [`EntityExpander::leave`] is called, so
+    /// that references inside it count toward depth and recursion checks.
+    Markup(String),
+    /// Kept as written; the reference was recorded as unexpanded
+    Unexpanded,
+}
+
+/// Expands entity references against the declarations seen so far
+#[derive(Debug, Clone)]
+pub struct EntityExpander {
+    declarations: HashMap<String, EntityDecl>,
+    limits: EntityLimits,
+    /// Whether internal entities are expanded at all
+    enabled: bool,
+    expansions: usize,
+    size: usize,
+    /// Entities currently being expanded, innermost last
+    open: Vec<String>,
+    unexpanded: BTreeSet<String>,
+}
+
+impl EntityExpander {
+    /// Create an expander; with `enabled` false every declared entity is
+    /// kept as a reference
+    pub fn new(limits: EntityLimits, enabled: bool) -> Self {
+        Self {
+            declarations: HashMap::new(),
+            limits,
+            enabled,
+            expansions: 0,
+            size: 0,
+            open: Vec::new(),
+            unexpanded: BTreeSet::new(),
+        }
+    }
+
+    /// Add the entities declared in a DOCTYPE
+    pub fn declare(&mut self, doctype: &str) {
+        for (name, decl) in parse_declarations(doctype) {
+            self.declarations.entry(name).or_insert(decl);
+        }
+    }
+
+    /// Names of references left as written
+    pub fn unexpanded(&self) -> &BTreeSet<String> {
+        &self.unexpanded
+    }
+
+    /// Whether `&name;` expands to the replacement text of a declared entity
+    pub fn is_expanded(&self, name: &str) -> bool {
+        self.enabled && matches!(self.declarations.get(name), Some(EntityDecl::Internal(_)))
+    }
+
+    /// Whether raw text references any entity that [`Self::is_expanded`]
+    pub fn expands_any(&self, raw: &str) -> bool {
+        raw.split('&').skip(1).any(|after| {
+            after
+                .split_once(';')
+                .is_some_and(|(name, _)| self.is_expanded(name))
+        })
+    }
+
+    /// Declared entities that references will be left as written for
+    ///
+    /// These are the external entities, or all of them when expansion is
+    /// disabled.
+    pub fn unexpandable(&self) -> impl Iterator<Item = &str> {
+        self.declarations
+            .iter()
+            .filter(|(_, decl)| !self.enabled || **decl == EntityDecl::External)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Consume the expander, returning the names of references left as written
+    pub fn into_unexpanded(self) -> BTreeSet<String> {
+        self.unexpanded
+    }
+
+    /// Expand every reference in raw text, such as an attribute value
+    ///
+    /// References that can't be expanded are kept as written. A `&` that
+    /// doesn't start a reference is kept as a literal ampersand.
+    pub fn expand(&mut self, raw: &str) -> EntityResult<String> {
+        let mut output = String::with_capacity(raw.len());
+        let mut rest = raw;
+
+        while let Some(amp) = rest.find('&') {
+            output.push_str(&rest[..amp]);
+            let after = &rest[amp + 1..];
+            match after.find(';') {
+                Some(end) if is_reference_name(&after[..end]) => {
+                    self.expand_reference(&after[..end], &mut output)?;
+                    rest = &after[end + 1..];
+                }
+                _ => {
+                    output.push('&');
+                    rest = after;
+                }
+            }
+        }
+        output.push_str(rest);
+
+        Ok(output)
+    }
+
+    /// Resolve a reference found in element content
+    pub fn resolve(&mut self, name: &str) -> EntityResult<Replacement> {
+        if let Some(EntityDecl::Internal(value)) = self.declarations.get(name) {
+            if self.enabled && value.contains('<') {
+                let value = value.clone();
+                self.enter(name, value.len())?;
+                return Ok(Replacement::Markup(value));
+            }
+        }
+
+        let mut output = String::new();
+        if self.expand_reference(name, &mut output)? {
+            Ok(Replacement::Text(output))
+        } else {
+            Ok(Replacement::Unexpanded)
+        }
+    }
+
+    /// Close the entity opened by [`Replacement::Markup`]
+    pub fn leave(&mut self) {
+        self.open.pop();
+    }
+
+    /// Append the expansion of `&name;` to `output`
+    ///
+    /// Returns false when the reference was kept as written.
+    fn expand_reference(&mut self, name: &str, output: &mut String) -> EntityResult<bool> {
+        if let Some(number) = name.strip_prefix('#') {
+            let ch =
+                char_ref(number).ok_or_else(|| EntityError::InvalidCharRef(name.to_string()))?;
+            output.push(ch);
+            return Ok(true);
+        }
+        if let Some(ch) = predefined(name) {
+            output.push(ch);
+            return Ok(true);
+        }
+
+        let value = match self.declarations.get(name) {
+            Some(EntityDecl::Internal(value)) if self.enabled => value.clone(),
+            _ => {
+                // Undeclared, external or not to be expanded
+                output.push('&');
+                output.push_str(name);
+                output.push(';');
+                self.unexpanded.insert(name.to_string());
+                return Ok(false);
+            }
+        };
+
+        self.enter(name, value.len())?;
+        let expanded = self.expand(&value);
+        self.leave();
+        output.push_str(&expanded?);
+        Ok(true)
+    }
+
+    /// Start expanding `name`, checking the limits
+    fn enter(&mut self, name: &str, len: usize) -> EntityResult<()> {
+        if self.open.iter().any(|open| open == name) {
+            return Err(EntityError::Recursive(name.to_string()));
+        }
+        if self.open.len() >= self.limits.max_depth {
+            return Err(EntityError::TooDeep {
+                name: name.to_string(),
+                max: self.limits.max_depth,
+            });
+        }
+        self.expansions += 1;
+        if self.expansions > self.limits.max_expansions {
+            return Err(EntityError::TooManyExpansions(self.limits.max_expansions));
+        }
+        self.size += len;
+        if self.size > self.limits.max_size {
+            return Err(EntityError::TooLarge(self.limits.max_size));
+        }
+        self.open.push(name.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expander(doctype: &str) -> EntityExpander {
+        let mut expander = EntityExpander::new(EntityLimits::default(), true);
+        expander.declare(doctype);
+        expander
+    }
+
+    #[test]
+    fn test_parse_declarations() {
+        let declarations = parse_declarations(
+            r#"svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd" [
+                <!-- a comment with <!ENTITY fake "x"> inside -->
+                <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+                <!ENTITY quoted 'say "hi" > there'>
+                <!ENTITY % param "ignored">
+                <!ENTITY ext SYSTEM "http://example.com/ext.xml">
+                <!ENTITY ns_svg "second declaration loses">
+                <!ATTLIST svg xmlns:x CDATA #FIXED "x">
+            ]"#,
+        );
+
+        assert_eq!(
+            declarations.get("ns_svg"),
+            Some(&EntityDecl::Internal(
+                "http://www.w3.org/2000/svg".to_string()
+            ))
+        );
+        assert_eq!(
+            declarations.get("quoted"),
+            Some(&EntityDecl::Internal(r#"say "hi" > there"#.to_string()))
+        );
+        assert_eq!(declarations.get("ext"), Some(&EntityDecl::External));
+        assert!(!declarations.contains_key("fake"));
+        assert!(!declarations.contains_key("param"));
+    }
+
+    #[test]
+    fn test_expand_nested_entities() {
+        let mut expander = expander(
+            r##"svg [ <!ENTITY color "&red;"> <!ENTITY red "#f00"> <!ENTITY less "&#60;"> ]"##,
+        );
+
+        assert_eq!(expander.expand("&color; &amp; &#x41;").unwrap(), "#f00 & A");
+        assert_eq!(expander.expand("a &less; b & c").unwrap(), "a < b & c");
+        assert!(expander.unexpanded().is_empty());
+    }
+
+    #[test]
+    fn test_unexpanded_references_are_kept() {
+        let mut expander = expander(r#"svg [ <!ENTITY ext SYSTEM "ext.xml"> ]"#);
+        assert_eq!(expander.expand("&ext;&nbsp;").unwrap(), "&ext;&nbsp;");
+        assert_eq!(expander.resolve("ext").unwrap(), Replacement::Unexpanded);
+        assert_eq!(
+            expander.unexpanded().iter().collect::<Vec<_>>(),
+            vec!["ext", "nbsp"]
+        );
+
+        let mut disabled = EntityExpander::new(EntityLimits::default(), false);
+        disabled.declare(r#"svg [ <!ENTITY a "b"> ]"#);
+        assert_eq!(disabled.expand("&a;&lt;").unwrap(), "&a;<");
+    }
+
+    #[test]
+    fn test_markup_replacement() {
+        let mut expander = expander(r#"svg [ <!ENTITY dot "<circle r='1'/>"> ]"#);
+        assert_eq!(
+            expander.resolve("dot").unwrap(),
+            Replacement::Markup("<circle r='1'/>".to_string())
+        );
+        assert_eq!(
+            expander.resolve("dot").unwrap_err(),
+            EntityError::Recursive("dot".to_string())
+        );
+        expander.leave();
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut laughs = String::from(r#"svg [ <!ENTITY lol0 "lol">"#);
+        for level in 1..10 {
+            let previous = format!("&lol{};", level - 1);
+            laughs.push_str(&format!(
+                r#"<!ENTITY lol{level} "{}">"#,
+                previous.repeat(10)
+            ));
+        }
+        laughs.push(']');
+
+        let mut laughing = expander(&laughs);
+        assert_eq!(
+            laughing.expand("&lol9;").unwrap_err(),
+            EntityError::TooManyExpansions(10_000)
+        );
+
+        let mut small = EntityExpander::new(
+            EntityLimits {
+                max_size: 20,
+                ..Default::default()
+            },
+            true,
+        );
+        small.declare(&laughs);
+        assert_eq!(
+            small.expand("&lol2;").unwrap_err(),
+            EntityError::TooLarge(20)
+        );
+
+        let mut recursive = expander(r#"svg [ <!ENTITY a "&b;"> <!ENTITY b "&a;"> ]"#);
+        assert_eq!(
+            recursive.expand("&a;").unwrap_err(),
+            EntityError::Recursive("a".to_string())
+        );
+
+        let mut shallow = EntityExpander::new(
+            EntityLimits {
+                max_depth: 2,
+                ..Default::default()
+            },
+            true,
+        );
+        shallow.declare(&laughs);
+        assert!(matches!(
+            shallow.expand("&lol3;"),
+            Err(EntityError::TooDeep { max: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,327 @@
+// this_file: svgn/src/export.rs
+
+//! Component export
+//!
+//! Besides SVG, the optimized document can be written as the source of a UI
+//! framework component, as an Android VectorDrawable or as Jetpack Compose
+//! code. [`Config::format`] selects the backend and [`Config::component`] tunes the component it
+//! generates. Each backend is a stringifier of its own, next to the SVG
+//! [`Stringifier`].
+
+pub mod compose;
+pub mod jsx;
+pub mod svelte;
+mod template;
+pub mod vector_drawable;
+pub mod vue;
+
+use crate::ast::{Document, Element, Node};
+use crate::config::Config;
+use crate::stringifier::{Stringifier, StringifyError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use thiserror::Error;
+
+/// Export errors
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error(transparent)]
+    Stringify(#[from] StringifyError),
+
+    #[error("{format} does not support {}", .features.join(", "))]
+    Unsupported {
+        format: &'static str,
+        /// What can't be converted, and where in the document
+        features: Vec<String>,
+    },
+}
+
+/// Export result type
+pub type ExportResult<T> = Result<T, ExportError>;
+
+/// What the optimizer writes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// SVG markup
+    #[default]
+    Svg,
+    /// React component in JavaScript
+    Jsx,
+    /// React component in TypeScript
+    Tsx,
+    /// Vue single-file component
+    Vue,
+    /// Svelte component
+    Svelte,
+    /// Android VectorDrawable XML
+    VectorDrawable,
+    /// Jetpack Compose `ImageVector` in Kotlin
+    Compose,
+}
+
+impl OutputFormat {
+    pub fn is_svg(&self) -> bool {
+        *self == OutputFormat::Svg
+    }
+
+    /// Extension of output files, including the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Svg => ".svg",
+            OutputFormat::Jsx => ".jsx",
+            OutputFormat::Tsx => ".tsx",
+            OutputFormat::Vue => ".vue",
+            OutputFormat::Svelte => ".svelte",
+            OutputFormat::VectorDrawable => ".xml",
+            OutputFormat::Compose => ".kt",
+        }
+    }
+
+    /// Name of the module that re-exports a folder of components, if the
+    /// format has one
+    pub fn index_file_name(self) -> Option<&'static str> {
+        match self {
+            OutputFormat::Svg | OutputFormat::VectorDrawable | OutputFormat::Compose => None,
+            OutputFormat::Jsx | OutputFormat::Vue | OutputFormat::Svelte => Some("index.js"),
+            OutputFormat::Tsx => Some("index.ts"),
+        }
+    }
+}
+
+/// Options for component output formats
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentOptions {
+    /// Component name; derived from the input file name if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Accept a `title` prop that replaces the `<title>` of the root
+    /// (JSX and TSX)
+    #[serde(default)]
+    pub title_prop: bool,
+
+    /// Accept a `size` prop that sets the width and height of the root
+    /// (Vue and Svelte)
+    #[serde(default)]
+    pub size_prop: bool,
+
+    /// Kotlin package of the generated file (Compose)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+impl ComponentOptions {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Stringify an optimized document in the format selected by `config`
+///
+/// The component name is taken from [`ComponentOptions::name`] or derived
+/// from [`Config::path`] with [`component_name`], or [`icon_name`] for
+/// Compose.
+pub fn export(document: &Document, config: &Config) -> ExportResult<String> {
+    let name = || {
+        config
+            .component
+            .name
+            .clone()
+            .unwrap_or_else(|| component_name(config.path.as_deref().map(Path::new)))
+    };
+
+    let output = match config.format {
+        OutputFormat::Svg => Stringifier::from_options(&config.js2svg).stringify(document)?,
+        OutputFormat::Jsx | OutputFormat::Tsx => jsx::JsxStringifier::new(name())
+            .typescript(config.format == OutputFormat::Tsx)
+            .title_prop(config.component.title_prop)
+            .stringify(document)?,
+        OutputFormat::Vue => vue::VueStringifier::new()
+            .size_prop(config.component.size_prop)
+            .stringify(document)?,
+        OutputFormat::Svelte => svelte::SvelteStringifier::new()
+            .size_prop(config.component.size_prop)
+            .stringify(document)?,
+        OutputFormat::VectorDrawable => {
+            vector_drawable::VectorDrawableStringifier::new().stringify(document)?
+        }
+        OutputFormat::Compose => compose::ComposeStringifier::new(
+            config
+                .component
+                .name
+                .clone()
+                .unwrap_or_else(|| icon_name(config.path.as_deref().map(Path::new))),
+        )
+        .package(config.component.package.clone())
+        .stringify(document)?,
+    };
+    Ok(output)
+}
+
+/// Component name for an input file: `arrow-left.svg` becomes `SvgArrowLeft`
+///
+/// The `Svg` prefix keeps names valid identifiers when the file name starts
+/// with a digit. Without a path the name is `SvgComponent`.
+pub fn component_name(path: Option<&Path>) -> String {
+    let name = format!("Svg{}", pascal_case(path));
+    if name.len() == 3 {
+        return "SvgComponent".to_string();
+    }
+    name
+}
+
+/// Icon name for an input file: `arrow-left.svg` becomes `ArrowLeft`
+///
+/// Names that would start with a digit get an `Icon` prefix, and without a
+/// path the name is `Icon`.
+pub fn icon_name(path: Option<&Path>) -> String {
+    let name = pascal_case(path);
+    if name.starts_with(|c: char| !c.is_ascii_alphabetic()) || name.is_empty() {
+        return format!("Icon{}", name);
+    }
+    name
+}
+
+/// File name of `path` without extensions, in PascalCase
+fn pascal_case(path: Option<&Path>) -> String {
+    let stem = path
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    // Strip every extension, so `icon.svg.svgz` is named like `icon.svg`
+    let stem = stem.split('.').next().unwrap_or_default();
+
+    let mut name = String::new();
+    for word in stem.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    name
+}
+
+/// Source of a module that re-exports the components of a folder
+///
+/// `components` lists each component's name and its output path relative to
+/// the index file. Names that occur more than once get a numeric suffix.
+/// Returns `None` for formats without an index.
+pub fn index_module(format: OutputFormat, components: &[(String, String)]) -> Option<String> {
+    format.index_file_name()?;
+
+    let mut used = HashSet::new();
+    let mut module = String::new();
+    for (name, path) in components {
+        let mut unique = name.clone();
+        let mut suffix = 1;
+        while !used.insert(unique.clone()) {
+            suffix += 1;
+            unique = format!("{}{}", name, suffix);
+        }
+
+        let path = path.replace('\\', "/");
+        // Bundlers resolve script modules without their extension, but
+        // need it for single-file components
+        let specifier = match format {
+            OutputFormat::Jsx | OutputFormat::Tsx => {
+                path.strip_suffix(format.extension()).unwrap_or(&path)
+            }
+            _ => &path,
+        }
+        .trim_start_matches("./");
+        module.push_str(&format!(
+            "export {{ default as {} }} from \"./{}\";\n",
+            unique, specifier
+        ));
+    }
+    Some(module)
+}
+
+/// Double-quoted JavaScript string literal
+pub(crate) fn js_string(value: &str) -> String {
+    js_quoted(value, '"')
+}
+
+/// JavaScript string literal in `quote`s
+pub(crate) fn js_quoted(value: &str, quote: char) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push(quote);
+    for c in value.chars() {
+        match c {
+            c if c == quote => {
+                literal.push('\\');
+                literal.push(c);
+            }
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            '\u{2028}' | '\u{2029}' => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push(quote);
+    literal
+}
+
+/// Whether an element has non-whitespace text children
+pub(crate) fn has_text(element: &Element) -> bool {
+    element.children.iter().any(
+        |child| matches!(child, Node::Text(text) | Node::CData(text) if !text.trim().is_empty()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_name() {
+        let name = |path: &str| component_name(Some(Path::new(path)));
+
+        assert_eq!(name("icons/arrow-left.svg"), "SvgArrowLeft");
+        assert_eq!(name("2x_close icon.svg"), "Svg2xCloseIcon");
+        assert_eq!(name("logo.min.svg"), "SvgLogo");
+        assert_eq!(name(".svg"), "SvgComponent");
+        assert_eq!(component_name(None), "SvgComponent");
+    }
+
+    #[test]
+    fn test_icon_name() {
+        let name = |path: &str| icon_name(Some(Path::new(path)));
+
+        assert_eq!(name("icons/arrow-left.svg"), "ArrowLeft");
+        assert_eq!(name("ic_add_24.xml"), "IcAdd24");
+        assert_eq!(name("2x_close.svg"), "Icon2xClose");
+        assert_eq!(icon_name(None), "Icon");
+    }
+
+    #[test]
+    fn test_index_module() {
+        let components = [
+            ("SvgArrow".to_string(), "arrow.tsx".to_string()),
+            ("SvgIcon".to_string(), "a/icon.tsx".to_string()),
+            ("SvgIcon".to_string(), "b\\icon.tsx".to_string()),
+        ];
+
+        assert_eq!(
+            index_module(OutputFormat::Tsx, &components).unwrap(),
+            "export { default as SvgArrow } from \"./arrow\";\n\
+             export { default as SvgIcon } from \"./a/icon\";\n\
+             export { default as SvgIcon2 } from \"./b/icon\";\n"
+        );
+        assert_eq!(index_module(OutputFormat::Svg, &components), None);
+        assert_eq!(
+            index_module(
+                OutputFormat::Vue,
+                &[("SvgArrow".to_string(), "arrow.vue".to_string())]
+            )
+            .unwrap(),
+            "export { default as SvgArrow } from \"./arrow.vue\";\n"
+        );
+    }
+}
@@ -0,0 +1,495 @@
+// this_file: svgn/src/export/compose.rs
+
+//! Jetpack Compose `ImageVector` generator
+//!
+//! Writes the document as Kotlin that builds an `ImageVector` the way the
+//! Material Icons do: a property that builds the icon once with
+//! `ImageVector.Builder`, with a `path { ... }` block of `moveTo`,
+//! `curveTo` and other calls for each path. `ImageVector` shares the
+//! VectorDrawable model, so the document is converted like a VectorDrawable
+//! first and supports the same features.
+
+use super::vector_drawable::{convert, number, VdElement};
+use super::{js_string, ExportResult};
+use crate::ast::Document;
+use crate::plugins::convert_path_data::{parse_path_data, CommandType};
+use std::collections::BTreeSet;
+
+const INDENT: &str = "    ";
+
+/// Compose `ImageVector` stringifier
+#[derive(Debug, Clone)]
+pub struct ComposeStringifier {
+    /// Name of the property and the image vector
+    name: String,
+    /// Kotlin package
+    package: Option<String>,
+}
+
+impl ComposeStringifier {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            package: None,
+        }
+    }
+
+    /// Kotlin package of the generated file
+    pub fn package(mut self, package: Option<String>) -> Self {
+        self.package = package;
+        self
+    }
+
+    /// Stringify a document as a Kotlin file
+    ///
+    /// Fails with [`ExportError::Unsupported`](super::ExportError::Unsupported)
+    /// for what a VectorDrawable can't draw either.
+    pub fn stringify(&self, document: &Document) -> ExportResult<String> {
+        let vector = convert(document, "ImageVector")?;
+        let field = backing_field(&self.name);
+        let mut writer = Writer {
+            body: String::new(),
+            imports: BTreeSet::from([
+                "androidx.compose.ui.graphics.vector.ImageVector",
+                "androidx.compose.ui.unit.dp",
+            ]),
+            // Builders have no alpha of their own
+            alpha: vector
+                .attr("android:alpha")
+                .and_then(|alpha| alpha.parse().ok())
+                .unwrap_or(1.0),
+        };
+
+        let dimension = |name: &str| vector.attr(name).unwrap_or("0").trim_end_matches("dp");
+        writer.line(1, "get() {");
+        writer.line(2, &format!("if ({} != null) {{", field));
+        writer.line(3, &format!("return {}!!", field));
+        writer.line(2, "}");
+        writer.call(
+            2,
+            &format!("{} = ImageVector.Builder", field),
+            vec![
+                format!("name = {}", kotlin_string(&self.name)),
+                format!("defaultWidth = {}.dp", dimension("android:width")),
+                format!("defaultHeight = {}.dp", dimension("android:height")),
+                format!("viewportWidth = {}f", dimension("android:viewportWidth")),
+                format!("viewportHeight = {}f", dimension("android:viewportHeight")),
+            ],
+            ".apply {",
+        );
+        for child in &vector.children {
+            writer.element(child, 3);
+        }
+        writer.line(2, "}.build()");
+        writer.line(2, &format!("return {}!!", field));
+        writer.line(1, "}");
+
+        let mut output = String::new();
+        if let Some(package) = &self.package {
+            output.push_str(&format!("package {}\n\n", package));
+        }
+        for import in &writer.imports {
+            output.push_str(&format!("import {}\n", import));
+        }
+        output.push_str(&format!("\npublic val {}: ImageVector\n", self.name));
+        output.push_str(&writer.body);
+        output.push_str(&format!("\nprivate var {}: ImageVector? = null\n", field));
+        Ok(output)
+    }
+}
+
+/// Name of the property that caches the image vector: `_arrowLeft` for
+/// `ArrowLeft`
+fn backing_field(name: &str) -> String {
+    let mut chars = name.chars();
+    let first = chars.next().map(|c| c.to_ascii_lowercase());
+    format!(
+        "_{}{}",
+        first.map(String::from).unwrap_or_default(),
+        chars.as_str()
+    )
+}
+
+struct Writer {
+    body: String,
+    imports: BTreeSet<&'static str>,
+    /// Alpha of the vector, multiplied into the paths
+    alpha: f64,
+}
+
+impl Writer {
+    fn line(&mut self, depth: usize, text: &str) {
+        self.body.push_str(&INDENT.repeat(depth));
+        self.body.push_str(text);
+        self.body.push('\n');
+    }
+
+    /// Write a call, with its arguments on one line if there is a single
+    /// short one, followed by `suffix`
+    fn call(&mut self, depth: usize, function: &str, arguments: Vec<String>, suffix: &str) {
+        if arguments.is_empty() {
+            self.line(depth, &format!("{}{}", function, suffix));
+        } else if arguments.len() == 1 && !arguments[0].contains('\n') {
+            self.line(depth, &format!("{}({}){}", function, arguments[0], suffix));
+        } else {
+            self.line(depth, &format!("{}(", function));
+            for argument in arguments {
+                self.line(depth + 1, &format!("{},", argument));
+            }
+            self.line(depth, &format!("){}", suffix));
+        }
+    }
+
+    fn element(&mut self, element: &VdElement, depth: usize) {
+        match element.name {
+            "group" => self.group(element, depth),
+            "path" => self.path(element, depth),
+            _ => {}
+        }
+    }
+
+    fn group(&mut self, group: &VdElement, depth: usize) {
+        self.imports
+            .insert("androidx.compose.ui.graphics.vector.group");
+        let mut arguments = Vec::new();
+        if let Some(name) = group.attr("android:name") {
+            arguments.push(format!("name = {}", kotlin_string(name)));
+        }
+        for (attribute, argument) in [
+            ("android:rotation", "rotate"),
+            ("android:scaleX", "scaleX"),
+            ("android:scaleY", "scaleY"),
+            ("android:translateX", "translationX"),
+            ("android:translateY", "translationY"),
+        ] {
+            if let Some(value) = group.attr(attribute) {
+                arguments.push(format!("{} = {}f", argument, value));
+            }
+        }
+        if let Some(clip) = group
+            .children
+            .iter()
+            .find(|child| child.name == "clip-path")
+        {
+            self.imports
+                .insert("androidx.compose.ui.graphics.vector.PathData");
+            let mut data = String::from("clipPathData = PathData {\n");
+            for command in commands(clip.attr("android:pathData").unwrap_or_default()) {
+                data.push_str(&format!("{}{}\n", INDENT.repeat(depth + 2), command));
+            }
+            data.push_str(&format!("{}}}", INDENT.repeat(depth + 1)));
+            arguments.push(data);
+        }
+
+        self.call(depth, "group", arguments, " {");
+        for child in &group.children {
+            self.element(child, depth + 1);
+        }
+        self.line(depth, "}");
+    }
+
+    fn path(&mut self, path: &VdElement, depth: usize) {
+        self.imports
+            .insert("androidx.compose.ui.graphics.vector.path");
+        let mut arguments = Vec::new();
+        if let Some(name) = path.attr("android:name") {
+            arguments.push(format!("name = {}", kotlin_string(name)));
+        }
+        for paint in ["fill", "stroke"] {
+            let color = format!("android:{}Color", paint);
+            let Some(brush) = self.brush(path, &color, depth + 1) else {
+                continue;
+            };
+            arguments.push(format!("{} = {}", paint, brush));
+            let alpha = path
+                .attr(&format!("android:{}Alpha", paint))
+                .and_then(|alpha| alpha.parse::<f64>().ok())
+                .unwrap_or(1.0)
+                * self.alpha;
+            if alpha < 1.0 {
+                arguments.push(format!("{}Alpha = {}f", paint, number(alpha)));
+            }
+        }
+        if let Some(width) = path.attr("android:strokeWidth") {
+            arguments.push(format!("strokeLineWidth = {}f", width));
+        }
+        if let Some(cap) = path.attr("android:strokeLineCap") {
+            self.imports
+                .insert("androidx.compose.ui.graphics.StrokeCap");
+            arguments.push(format!("strokeLineCap = StrokeCap.{}", capitalize(cap)));
+        }
+        if let Some(join) = path.attr("android:strokeLineJoin") {
+            self.imports
+                .insert("androidx.compose.ui.graphics.StrokeJoin");
+            arguments.push(format!("strokeLineJoin = StrokeJoin.{}", capitalize(join)));
+        }
+        if let Some(limit) = path.attr("android:strokeMiterLimit") {
+            arguments.push(format!("strokeLineMiter = {}f", limit));
+        }
+        if path.attr("android:fillType") == Some("evenOdd") {
+            self.imports
+                .insert("androidx.compose.ui.graphics.PathFillType");
+            arguments.push("pathFillType = PathFillType.EvenOdd".to_string());
+        }
+
+        self.call(depth, "path", arguments, " {");
+        for command in commands(path.attr("android:pathData").unwrap_or_default()) {
+            self.line(depth + 1, &command);
+        }
+        self.line(depth, "}");
+    }
+
+    /// A `SolidColor` or gradient `Brush` for a color attribute, written as
+    /// an argument at `depth`
+    fn brush(&mut self, path: &VdElement, attribute: &str, depth: usize) -> Option<String> {
+        if let Some(color) = path.attr(attribute) {
+            self.imports.insert("androidx.compose.ui.graphics.Color");
+            self.imports
+                .insert("androidx.compose.ui.graphics.SolidColor");
+            return Some(format!("SolidColor({})", color_literal(color)));
+        }
+
+        let gradient = path
+            .children
+            .iter()
+            .find(|child| child.name == "aapt:attr" && child.attr("name") == Some(attribute))?
+            .children
+            .first()?;
+        self.imports.insert("androidx.compose.ui.graphics.Brush");
+        self.imports.insert("androidx.compose.ui.graphics.Color");
+        self.imports.insert("androidx.compose.ui.geometry.Offset");
+
+        let float = |name: &str| format!("{}f", gradient.attr(name).unwrap_or("0"));
+        let mut arguments: Vec<String> = gradient
+            .children
+            .iter()
+            .map(|item| {
+                format!(
+                    "{}f to {}",
+                    item.attr("android:offset").unwrap_or("0"),
+                    color_literal(item.attr("android:color").unwrap_or_default())
+                )
+            })
+            .collect();
+        let function = match gradient.attr("android:type") {
+            Some("radial") => {
+                arguments.push(format!(
+                    "center = Offset({}, {})",
+                    float("android:centerX"),
+                    float("android:centerY")
+                ));
+                arguments.push(format!("radius = {}", float("android:gradientRadius")));
+                "Brush.radialGradient"
+            }
+            _ => {
+                arguments.push(format!(
+                    "start = Offset({}, {})",
+                    float("android:startX"),
+                    float("android:startY")
+                ));
+                arguments.push(format!(
+                    "end = Offset({}, {})",
+                    float("android:endX"),
+                    float("android:endY")
+                ));
+                "Brush.linearGradient"
+            }
+        };
+        let tile_mode = match gradient.attr("android:tileMode") {
+            Some("mirror") => Some("Mirror"),
+            Some("repeat") => Some("Repeated"),
+            _ => None,
+        };
+        if let Some(tile_mode) = tile_mode {
+            self.imports.insert("androidx.compose.ui.graphics.TileMode");
+            arguments.push(format!("tileMode = TileMode.{}", tile_mode));
+        }
+
+        let indent = INDENT.repeat(depth);
+        let mut brush = format!("{}(\n", function);
+        for argument in arguments {
+            brush.push_str(&format!("{}{}{},\n", indent, INDENT, argument));
+        }
+        brush.push_str(&format!("{})", indent));
+        Some(brush)
+    }
+}
+
+/// `PathBuilder` calls for path data
+fn commands(d: &str) -> Vec<String> {
+    let commands = parse_path_data(d).unwrap_or_default();
+    commands
+        .iter()
+        .map(|command| {
+            let function = match command.cmd_type {
+                CommandType::MoveTo => "moveTo",
+                CommandType::LineTo => "lineTo",
+                CommandType::HorizontalLineTo => "horizontalLineTo",
+                CommandType::VerticalLineTo => "verticalLineTo",
+                CommandType::CurveTo => "curveTo",
+                CommandType::SmoothCurveTo => "reflectiveCurveTo",
+                CommandType::QuadraticBezier => "quadTo",
+                CommandType::SmoothQuadraticBezier => "reflectiveQuadTo",
+                CommandType::Arc => "arcTo",
+                CommandType::ClosePath => return "close()".to_string(),
+            };
+            let arguments: Vec<String> = command
+                .params
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| match command.cmd_type {
+                    // Large arc and sweep flags
+                    CommandType::Arc if i == 3 || i == 4 => (value != 0.0).to_string(),
+                    _ => format!("{}f", number(value)),
+                })
+                .collect();
+            let relative = if command.is_absolute { "" } else { "Relative" };
+            format!("{}{}({})", function, relative, arguments.join(", "))
+        })
+        .collect()
+}
+
+/// Kotlin string literal, which escapes like JavaScript except for `$`
+/// templates
+fn kotlin_string(value: &str) -> String {
+    js_string(value).replace('$', "\\$")
+}
+
+/// `Color(0xAARRGGBB)` for a `#AARRGGBB` color
+fn color_literal(color: &str) -> String {
+    format!("Color(0x{})", color.trim_start_matches('#'))
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn compose(svg: &str, stringifier: ComposeStringifier) -> String {
+        stringifier
+            .stringify(&Parser::new().parse(svg).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_image_vector() {
+        let output = compose(
+            r##"<svg width="24" height="24" viewBox="0 0 24 24"><path d="M12 2l-1.5.5a1 1 0 01 1 1z" fill="#f00" fill-rule="evenodd"/><g transform="rotate(45)" opacity=".5"><path d="M0 0C1 1 2 2 3 3s1 1 2 2H6V7" fill="none" stroke="blue" stroke-width="2" stroke-linecap="round"/></g></svg>"##,
+            ComposeStringifier::new("ArrowLeft").package(Some("com.example.icons".to_string())),
+        );
+
+        assert_eq!(
+            output,
+            r#"package com.example.icons
+
+import androidx.compose.ui.graphics.Color
+import androidx.compose.ui.graphics.PathFillType
+import androidx.compose.ui.graphics.SolidColor
+import androidx.compose.ui.graphics.StrokeCap
+import androidx.compose.ui.graphics.vector.ImageVector
+import androidx.compose.ui.graphics.vector.group
+import androidx.compose.ui.graphics.vector.path
+import androidx.compose.ui.unit.dp
+
+public val ArrowLeft: ImageVector
+    get() {
+        if (_arrowLeft != null) {
+            return _arrowLeft!!
+        }
+        _arrowLeft = ImageVector.Builder(
+            name = "ArrowLeft",
+            defaultWidth = 24.dp,
+            defaultHeight = 24.dp,
+            viewportWidth = 24f,
+            viewportHeight = 24f,
+        ).apply {
+            path(
+                fill = SolidColor(Color(0xFFFF0000)),
+                pathFillType = PathFillType.EvenOdd,
+            ) {
+                moveTo(12f, 2f)
+                lineToRelative(-1.5f, 0.5f)
+                arcToRelative(1f, 1f, 0f, false, true, 1f, 1f)
+                close()
+            }
+            group(rotate = 45f) {
+                path(
+                    stroke = SolidColor(Color(0xFF0000FF)),
+                    strokeAlpha = 0.5f,
+                    strokeLineWidth = 2f,
+                    strokeLineCap = StrokeCap.Round,
+                ) {
+                    moveTo(0f, 0f)
+                    curveTo(1f, 1f, 2f, 2f, 3f, 3f)
+                    reflectiveCurveToRelative(1f, 1f, 2f, 2f)
+                    horizontalLineTo(6f)
+                    verticalLineTo(7f)
+                }
+            }
+        }.build()
+        return _arrowLeft!!
+    }
+
+private var _arrowLeft: ImageVector? = null
+"#
+        );
+    }
+
+    #[test]
+    fn test_gradient_and_clip_path() {
+        let output = compose(
+            r##"<svg viewBox="0 0 24 24"><linearGradient id="a" x1="0" x2="24" gradientUnits="userSpaceOnUse" spreadMethod="reflect"><stop offset="0" stop-color="red"/><stop offset="1" stop-color="#00f" stop-opacity=".5"/></linearGradient><clipPath id="c"><rect width="12" height="24"/></clipPath><g clip-path="url(#c)"><path d="M0 0h24v24H0z" fill="url(#a)"/></g></svg>"##,
+            ComposeStringifier::new("Clipped"),
+        );
+
+        assert!(output.starts_with("import androidx.compose.ui.geometry.Offset\n"));
+        assert!(output.contains(
+            r#"            group(
+                clipPathData = PathData {
+                    moveTo(0f, 0f)
+                    horizontalLineTo(12f)
+                    verticalLineTo(24f)
+                    horizontalLineTo(0f)
+                    close()
+                },
+            ) {
+                path(
+                    fill = Brush.linearGradient(
+                        0f to Color(0xFFFF0000),
+                        1f to Color(0x800000FF),
+                        start = Offset(0f, 0f),
+                        end = Offset(24f, 0f),
+                        tileMode = TileMode.Mirror,
+                    ),
+                ) {"#
+        ));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let error = ComposeStringifier::new("Text")
+            .stringify(
+                &Parser::new()
+                    .parse("<svg viewBox=\"0 0 1 1\"><text>A</text></svg>")
+                    .unwrap(),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "ImageVector does not support <text> at /svg/text[1]"
+        );
+    }
+
+    #[test]
+    fn test_backing_field() {
+        assert_eq!(backing_field("ArrowLeft"), "_arrowLeft");
+    }
+}
@@ -0,0 +1,395 @@
+// this_file: svgn/src/export/jsx.rs
+
+//! React component stringifier
+//!
+//! Writes the document as a function component that returns the SVG markup
+//! in JSX. Attribute names are converted to their React spelling
+//! (`stroke-width` to `strokeWidth`, `xlink:href` to `xlinkHref`, `class` to
+//! `className`), `style` attributes become style objects, and the component's
+//! props are spread onto the root element.
+
+use super::{has_text, js_string};
+use crate::ast::{Document, Element, Node};
+use crate::stringifier::StringifyResult;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+const INDENT: &str = "  ";
+
+/// JSX and TSX stringifier
+#[derive(Debug, Clone)]
+pub struct JsxStringifier {
+    /// Component name
+    name: String,
+    /// Emit TypeScript with typed props
+    typescript: bool,
+    /// Accept a `title` prop
+    title_prop: bool,
+}
+
+impl JsxStringifier {
+    /// Create a stringifier for a component called `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            typescript: false,
+            title_prop: false,
+        }
+    }
+
+    /// Emit TypeScript (TSX) with typed props
+    pub fn typescript(mut self, typescript: bool) -> Self {
+        self.typescript = typescript;
+        self
+    }
+
+    /// Accept a `title` prop rendered as the root's `<title>`
+    ///
+    /// An existing `<title>` child of the root is replaced and its text
+    /// becomes the default value of the prop.
+    pub fn title_prop(mut self, title_prop: bool) -> Self {
+        self.title_prop = title_prop;
+        self
+    }
+
+    /// Stringify a document as a component module
+    pub fn stringify(&self, document: &Document) -> StringifyResult<String> {
+        let root = &document.root;
+        let title = root.child_elements().find(|child| child.name == "title");
+        let mut output = String::new();
+
+        writeln!(output, "import * as React from \"react\";")?;
+        if self.typescript {
+            writeln!(output, "import type {{ SVGProps }} from \"react\";")?;
+            if self.title_prop {
+                writeln!(
+                    output,
+                    "interface TitleProps {{\n{}title?: string;\n}}",
+                    INDENT
+                )?;
+            }
+        }
+        output.push('\n');
+
+        let props = match (self.title_prop, title.and_then(text_content)) {
+            (false, _) => Cow::Borrowed("props"),
+            (true, None) => Cow::Borrowed("{ title, ...props }"),
+            (true, Some(text)) => {
+                Cow::Owned(format!("{{ title = {}, ...props }}", js_string(&text)))
+            }
+        };
+        let props_type = match (self.typescript, self.title_prop) {
+            (false, _) => "",
+            (true, false) => ": SVGProps<SVGSVGElement>",
+            (true, true) => ": SVGProps<SVGSVGElement> & TitleProps",
+        };
+        writeln!(
+            output,
+            "const {} = ({}{}) => (",
+            self.name, props, props_type
+        )?;
+        self.write_root(root, title, &mut output)?;
+        writeln!(output, ");")?;
+        writeln!(output, "export default {};", self.name)?;
+
+        Ok(output)
+    }
+
+    fn write_root(
+        &self,
+        root: &Element,
+        title: Option<&Element>,
+        output: &mut String,
+    ) -> StringifyResult<()> {
+        if !self.title_prop {
+            write_element(root, 1, false, output)?;
+            output.push('\n');
+            return Ok(());
+        }
+
+        // The title expression takes the place of the existing <title>, or
+        // comes first. A root with text content is written inline, as
+        // `write_element` does.
+        let inline = has_text(root);
+        write!(output, "{}<{}", INDENT, root.name)?;
+        write_attributes(root, true, output)?;
+        output.push('>');
+        if !inline {
+            output.push('\n');
+        }
+        if title.is_none() {
+            write_title(None, inline, output)?;
+        }
+        for child in &root.children {
+            match child {
+                Node::Element(child) if title.is_some_and(|title| std::ptr::eq(title, child)) => {
+                    write_title(title, inline, output)?
+                }
+                Node::Element(child) => {
+                    write_element(child, 2, inline, output)?;
+                    if !inline {
+                        output.push('\n');
+                    }
+                }
+                Node::Text(text) | Node::CData(text) if inline => write_text(text, output),
+                _ => {}
+            }
+        }
+        if !inline {
+            output.push_str(INDENT);
+        }
+        writeln!(output, "</{}>", root.name)?;
+        Ok(())
+    }
+}
+
+/// Write `element` at `depth`, or on a single line without indentation
+/// if `inline`
+///
+/// Depth 1 is the root, which gets the props spread onto it. Elements with
+/// text content are written inline, so JSX keeps their whitespace.
+fn write_element(
+    element: &Element,
+    depth: usize,
+    inline: bool,
+    output: &mut String,
+) -> StringifyResult<()> {
+    if !inline {
+        output.push_str(&INDENT.repeat(depth));
+    }
+    write!(output, "<{}", element.name)?;
+    write_attributes(element, depth == 1 && !inline, output)?;
+
+    if has_text(element) {
+        output.push('>');
+        for child in &element.children {
+            match child {
+                Node::Element(child) => write_element(child, 0, true, output)?,
+                Node::Text(text) | Node::CData(text) => write_text(text, output),
+                _ => {}
+            }
+        }
+    } else if element.child_elements().next().is_none() {
+        output.push_str(" />");
+        return Ok(());
+    } else if inline {
+        output.push('>');
+        for child in element.child_elements() {
+            write_element(child, 0, true, output)?;
+        }
+    } else {
+        output.push_str(">\n");
+        for child in element.child_elements() {
+            write_element(child, depth + 1, false, output)?;
+            output.push('\n');
+        }
+        output.push_str(&INDENT.repeat(depth));
+    }
+    write!(output, "</{}>", element.name)?;
+    Ok(())
+}
+
+fn write_attributes(element: &Element, root: bool, output: &mut String) -> StringifyResult<()> {
+    for (name, value) in &element.attributes {
+        if name == "style" {
+            let style = style_object(value);
+            if !style.is_empty() {
+                write!(output, " style={{{{ {} }}}}", style)?;
+            }
+            continue;
+        }
+        write!(output, " {}=", attribute_name(name))?;
+        // JSX attribute strings can't escape quotes and decode entities
+        if value.contains(['"', '&', '\n', '\r']) {
+            write!(output, "{{{}}}", js_string(value))?;
+        } else {
+            write!(output, "\"{}\"", value)?;
+        }
+    }
+    if root {
+        output.push_str(" {...props}");
+    }
+    Ok(())
+}
+
+fn write_title(title: Option<&Element>, inline: bool, output: &mut String) -> StringifyResult<()> {
+    if !inline {
+        output.push_str(&INDENT.repeat(2));
+    }
+    output.push_str("{title ? <title");
+    if let Some(title) = title {
+        write_attributes(title, false, output)?;
+    }
+    output.push_str(">{title}</title> : null}");
+    if !inline {
+        output.push('\n');
+    }
+    Ok(())
+}
+
+/// Write text as JSX text if that keeps it verbatim, or as a string
+/// expression
+fn write_text(text: &str, output: &mut String) {
+    if text.contains(['{', '}', '<', '>', '&', '\n', '\r']) {
+        output.push('{');
+        output.push_str(&js_string(text));
+        output.push('}');
+    } else {
+        output.push_str(text);
+    }
+}
+
+/// React name of an SVG attribute
+fn attribute_name(name: &str) -> Cow<'_, str> {
+    match name {
+        "class" => Cow::Borrowed("className"),
+        _ if name.starts_with("aria-") || name.starts_with("data-") => Cow::Borrowed(name),
+        _ if name.contains(['-', ':']) => Cow::Owned(camel_case(name)),
+        _ => Cow::Borrowed(name),
+    }
+}
+
+/// Body of a style object for a `style` attribute, e.g.
+/// `fill: "red", strokeWidth: "2"`
+fn style_object(style: &str) -> String {
+    let mut properties = Vec::new();
+    for declaration in style.split(';') {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let (property, value) = (property.trim(), value.trim());
+        if property.is_empty() || value.is_empty() {
+            continue;
+        }
+
+        let key = if property.starts_with("--") {
+            // Custom properties keep their name
+            js_string(property)
+        } else if let Some(rest) = property.strip_prefix("-ms-") {
+            camel_case(&format!("ms-{}", rest))
+        } else if let Some(rest) = property.strip_prefix('-') {
+            // Vendor prefixes are capitalized: -webkit-mask becomes WebkitMask
+            let mut key = camel_case(rest);
+            if let Some(first) = key.get_mut(..1) {
+                first.make_ascii_uppercase();
+            }
+            key
+        } else {
+            camel_case(property)
+        };
+        properties.push(format!("{}: {}", key, js_string(value)));
+    }
+    properties.join(", ")
+}
+
+/// `stroke-width` to `strokeWidth`, `xlink:href` to `xlinkHref`
+fn camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '-' || c == ':' {
+            upper = !result.is_empty();
+        } else if upper {
+            result.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Whitespace-collapsed text of an element, if it has any
+fn text_content(element: &Element) -> Option<String> {
+    let text: Vec<&str> = element
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            Node::Text(text) | Node::CData(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .flat_map(str::split_whitespace)
+        .collect();
+    (!text.is_empty()).then(|| text.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn jsx(svg: &str, stringifier: JsxStringifier) -> String {
+        stringifier
+            .stringify(&Parser::new().parse(svg).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_component() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 24 24"><!-- icon --><g class="a" stroke-width="2" style="fill: red; -webkit-mask: none;--c:1"><use xlink:href="#p" aria-label="A &quot;b&quot;"/></g></svg>"##;
+
+        assert_eq!(
+            jsx(svg, JsxStringifier::new("SvgIcon")),
+            r##"import * as React from "react";
+
+const SvgIcon = (props) => (
+  <svg xmlns="http://www.w3.org/2000/svg" xmlnsXlink="http://www.w3.org/1999/xlink" viewBox="0 0 24 24" {...props}>
+    <g className="a" strokeWidth="2" style={{ fill: "red", WebkitMask: "none", "--c": "1" }}>
+      <use xlinkHref="#p" aria-label={"A \"b\""} />
+    </g>
+  </svg>
+);
+export default SvgIcon;
+"##
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        let output = jsx(
+            "<svg><text>a <tspan>{b}</tspan>\n c</text><style>.a{fill:red}</style></svg>",
+            JsxStringifier::new("SvgText"),
+        );
+
+        assert!(output.contains(r#"<text>a <tspan>{"{b}"}</tspan>{"\n c"}</text>"#));
+        assert!(output.contains(r#"<style>{".a{fill:red}"}</style>"#));
+    }
+
+    #[test]
+    fn test_title_prop() {
+        let stringifier = || JsxStringifier::new("SvgIcon").title_prop(true);
+
+        assert_eq!(
+            jsx(
+                r#"<svg aria-labelledby="t"><title id="t">An  icon</title><path d="M0 0"/></svg>"#,
+                stringifier().typescript(true)
+            ),
+            r#"import * as React from "react";
+import type { SVGProps } from "react";
+interface TitleProps {
+  title?: string;
+}
+
+const SvgIcon = ({ title = "An icon", ...props }: SVGProps<SVGSVGElement> & TitleProps) => (
+  <svg aria-labelledby="t" {...props}>
+    {title ? <title id="t">{title}</title> : null}
+    <path d="M0 0" />
+  </svg>
+);
+export default SvgIcon;
+"#
+        );
+
+        let output = jsx("<svg/>", stringifier());
+        assert!(output.contains("const SvgIcon = ({ title, ...props }) => (\n"));
+        assert!(output.contains(
+            "  <svg {...props}>\n    {title ? <title>{title}</title> : null}\n  </svg>\n"
+        ));
+
+        // Roots with text content are written inline, title expression
+        // included
+        let output = jsx("<svg><title>A</title>B<g/></svg>", stringifier());
+        assert!(output
+            .contains("  <svg {...props}>{title ? <title>{title}</title> : null}B<g /></svg>\n"));
+    }
+}
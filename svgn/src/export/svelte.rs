@@ -0,0 +1,159 @@
+// this_file: svgn/src/export/svelte.rs
+
+//! Svelte component stringifier
+//!
+//! Writes the document as the markup of a Svelte 5 component. The props
+//! that the component doesn't declare are spread onto the root `<svg>`,
+//! which forwards attributes and event handlers alike.
+
+use super::js_string;
+use super::template::{
+    escape, escape_attribute, escape_text, write_element, RootBindings, TemplateSyntax, INDENT,
+};
+use crate::ast::{Document, Element};
+use crate::stringifier::StringifyResult;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Svelte component stringifier
+#[derive(Debug, Clone, Default)]
+pub struct SvelteStringifier {
+    /// Accept a `size` prop
+    size_prop: bool,
+}
+
+impl SvelteStringifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a `size` prop that replaces the width and height of the root
+    pub fn size_prop(mut self, size_prop: bool) -> Self {
+        self.size_prop = size_prop;
+        self
+    }
+
+    /// Stringify a document as a component
+    pub fn stringify(&self, document: &Document) -> StringifyResult<String> {
+        let root = &document.root;
+        let mut output = String::new();
+        let mut bindings = RootBindings {
+            replaced: &[],
+            bindings: String::new(),
+        };
+
+        writeln!(output, "<script>")?;
+        if self.size_prop {
+            writeln!(output, "{}let {{ size, ...props }} = $props();", INDENT)?;
+
+            bindings.replaced = &["width", "height"];
+            for name in ["width", "height"] {
+                // Without a size, keep the original dimension
+                match root.attr(name) {
+                    Some(value) => write!(
+                        bindings.bindings,
+                        " {}={{size ?? {}}}",
+                        name,
+                        js_string(value)
+                    )?,
+                    None => write!(bindings.bindings, " {}={{size}}", name)?,
+                }
+            }
+        } else {
+            writeln!(output, "{}let props = $props();", INDENT)?;
+        }
+        writeln!(output, "</script>\n")?;
+        bindings.bindings.push_str(" {...props}");
+
+        write_element(&SvelteSyntax, root, 0, false, Some(&bindings), &mut output)?;
+        output.push('\n');
+        Ok(output)
+    }
+}
+
+struct SvelteSyntax;
+
+impl TemplateSyntax for SvelteSyntax {
+    fn text<'a>(&self, parent: &Element, text: &'a str) -> Cow<'a, str> {
+        // The content of <style> and <script> is read as raw text
+        if matches!(parent.name.as_str(), "style" | "script") {
+            return Cow::Borrowed(text);
+        }
+        escape_braces(escape_text(text))
+    }
+
+    fn attribute<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        escape_braces(escape_attribute(value))
+    }
+}
+
+/// Braces start expressions in text and attribute values
+fn escape_braces(text: Cow<'_, str>) -> Cow<'_, str> {
+    if !text.contains(['{', '}']) {
+        return text;
+    }
+    let escaped = escape(&text, |c| match c {
+        '{' => Some("&#123;"),
+        '}' => Some("&#125;"),
+        _ => None,
+    });
+    Cow::Owned(escaped.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn svelte(svg: &str, stringifier: SvelteStringifier) -> String {
+        stringifier
+            .stringify(&Parser::new().parse(svg).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_component() {
+        assert_eq!(
+            svelte(
+                r#"<svg viewBox="0 0 24 24"><g class="a"><path d="M0 0"/></g></svg>"#,
+                SvelteStringifier::new()
+            ),
+            r#"<script>
+  let props = $props();
+</script>
+
+<svg viewBox="0 0 24 24" {...props}>
+  <g class="a">
+    <path d="M0 0" />
+  </g>
+</svg>
+"#
+        );
+    }
+
+    #[test]
+    fn test_size_prop() {
+        let output = svelte(
+            r#"<svg width="24" height="24" viewBox="0 0 24 24"/>"#,
+            SvelteStringifier::new().size_prop(true),
+        );
+
+        assert!(output.contains("  let { size, ...props } = $props();\n"));
+        assert!(output.contains(
+            r#"<svg viewBox="0 0 24 24" width={size ?? "24"} height={size ?? "24"} {...props} />"#
+        ));
+    }
+
+    #[test]
+    fn test_escaping() {
+        let output = svelte(
+            r#"<svg><style>.a{fill:red}</style><text title="{x} &quot;">{x} &lt; y</text></svg>"#,
+            SvelteStringifier::new(),
+        );
+
+        assert!(output.contains("<style>.a{fill:red}</style>"));
+        assert!(
+            output.contains(r#"<text title="&#123;x&#125; &quot;">&#123;x&#125; &lt; y</text>"#)
+        );
+    }
+}
@@ -0,0 +1,141 @@
+// this_file: svgn/src/export/template.rs
+
+//! Template markup shared by the Vue and Svelte stringifiers
+//!
+//! Both frameworks embed the SVG as HTML-like markup, but differ in what
+//! their template compilers interpret. [`TemplateSyntax`] escapes text and
+//! attribute values for one framework, and [`write_element`] writes the
+//! indented markup.
+
+use super::has_text;
+use crate::ast::{Element, Node};
+use crate::stringifier::StringifyResult;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+pub(crate) const INDENT: &str = "  ";
+
+/// How a framework's template compiler reads markup
+pub(crate) trait TemplateSyntax {
+    /// Escape a text child of `parent`
+    fn text<'a>(&self, parent: &Element, text: &'a str) -> Cow<'a, str>;
+
+    /// Escape a value for a double-quoted attribute
+    fn attribute<'a>(&self, value: &'a str) -> Cow<'a, str>;
+
+    /// Tag name an element is written with
+    fn tag_name<'a>(&self, element: &'a Element) -> &'a str {
+        &element.name
+    }
+
+    /// Directives written before the element's attributes
+    fn directives(&self, _element: &Element) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Root attributes that a component replaces or adds
+pub(crate) struct RootBindings<'a> {
+    /// Attributes of the root that are left out
+    pub replaced: &'a [&'a str],
+    /// Markup written after the remaining attributes, with a leading space
+    pub bindings: String,
+}
+
+/// Write `element` at `depth`, or on a single line without indentation if
+/// `inline`, with `root` bindings if it is the root
+///
+/// Elements with text content are written inline, so their whitespace is
+/// kept. Comments are left out.
+pub(crate) fn write_element(
+    syntax: &dyn TemplateSyntax,
+    element: &Element,
+    depth: usize,
+    inline: bool,
+    root: Option<&RootBindings>,
+    output: &mut String,
+) -> StringifyResult<()> {
+    if !inline {
+        output.push_str(&INDENT.repeat(depth));
+    }
+    let tag_name = syntax.tag_name(element);
+    write!(output, "<{}", tag_name)?;
+    for directive in syntax.directives(element) {
+        write!(output, " {}", directive)?;
+    }
+    for (name, value) in &element.attributes {
+        if root.is_some_and(|root| root.replaced.contains(&name.as_str())) {
+            continue;
+        }
+        write!(output, " {}=\"{}\"", name, syntax.attribute(value))?;
+    }
+    if let Some(root) = root {
+        output.push_str(&root.bindings);
+    }
+
+    if has_text(element) {
+        output.push('>');
+        for child in &element.children {
+            match child {
+                Node::Element(child) => write_element(syntax, child, 0, true, None, output)?,
+                Node::Text(text) | Node::CData(text) => {
+                    output.push_str(&syntax.text(element, text))
+                }
+                _ => {}
+            }
+        }
+    } else if element.child_elements().next().is_none() {
+        output.push_str(" />");
+        return Ok(());
+    } else if inline {
+        output.push('>');
+        for child in element.child_elements() {
+            write_element(syntax, child, 0, true, None, output)?;
+        }
+    } else {
+        output.push_str(">\n");
+        for child in element.child_elements() {
+            write_element(syntax, child, depth + 1, false, None, output)?;
+            output.push('\n');
+        }
+        output.push_str(&INDENT.repeat(depth));
+    }
+    write!(output, "</{}>", tag_name)?;
+    Ok(())
+}
+
+/// Escape the characters that start markup in text
+pub(crate) fn escape_text(text: &str) -> Cow<'_, str> {
+    escape(text, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// Escape the characters that end or start markup in a double-quoted
+/// attribute value
+pub(crate) fn escape_attribute(value: &str) -> Cow<'_, str> {
+    escape(value, |c| match c {
+        '&' => Some("&amp;"),
+        '"' => Some("&quot;"),
+        '<' => Some("&lt;"),
+        _ => None,
+    })
+}
+
+/// Replace the characters for which `entity` returns a replacement
+pub(crate) fn escape(text: &str, entity: impl Fn(char) -> Option<&'static str>) -> Cow<'_, str> {
+    if !text.chars().any(|c| entity(c).is_some()) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match entity(c) {
+            Some(replacement) => escaped.push_str(replacement),
+            None => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
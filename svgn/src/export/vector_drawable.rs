@@ -0,0 +1,1186 @@
+// this_file: svgn/src/export/vector_drawable.rs
+
+//! Android VectorDrawable stringifier
+//!
+//! Converts the document to a `<vector>` drawable: the viewBox becomes the
+//! viewport, shapes become paths, group transforms are split into
+//! translation, rotation and scale, and gradients become `<aapt:attr>`
+//! gradients. Clip paths made of shapes and paths are supported as well.
+//! Everything else a VectorDrawable can't draw, such as text, filters and
+//! masks, is collected and reported as [`ExportError::Unsupported`].
+
+use super::template::escape_attribute;
+use super::{ExportError, ExportResult};
+use crate::ast::{Document, Element};
+use crate::collections::COLORS_NAMES;
+use crate::plugins::convert_path_data::{parse_path_data, CommandType};
+use crate::plugins::convert_shape_to_path::convert_shape_element;
+use crate::plugins::convert_transform::{
+    decompose_matrix, transforms_to_matrix, ConvertTransformPlugin,
+};
+use nalgebra::{Matrix3, Vector3};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+const ANDROID_NS: &str = "http://schemas.android.com/apk/res/android";
+const AAPT_NS: &str = "http://schemas.android.com/aapt";
+const INDENT: &str = "    ";
+
+/// VectorDrawable stringifier
+#[derive(Debug, Clone, Default)]
+pub struct VectorDrawableStringifier;
+
+impl VectorDrawableStringifier {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Convert a document to VectorDrawable XML
+    ///
+    /// Fails with [`ExportError::Unsupported`] listing every element and
+    /// property that can't be converted.
+    pub fn stringify(&self, document: &Document) -> ExportResult<String> {
+        let vector = convert(document, "VectorDrawable")?;
+        let mut output = String::new();
+        vector.write(0, &mut output);
+        Ok(output)
+    }
+}
+
+/// Convert a document to the element tree of a drawable
+///
+/// Jetpack Compose's `ImageVector` shares the VectorDrawable model, so the
+/// Compose stringifier writes the same tree as Kotlin. `format` names the
+/// output format in errors.
+pub(crate) fn convert(document: &Document, format: &'static str) -> ExportResult<VdElement> {
+    let root = &document.root;
+    let mut converter = Converter {
+        format,
+        ids: HashMap::new(),
+        viewport: (0.0, 0.0),
+        unsupported: Vec::new(),
+        uses_aapt: false,
+    };
+    collect_ids(root, &mut converter.ids);
+
+    let (min_x, min_y, width, height) = match viewport(root) {
+        Some(viewport) => viewport,
+        None => return Err(converter.fail(vec!["a root without viewBox or size".to_string()])),
+    };
+    converter.viewport = (width, height);
+
+    let mut paint = Paint::default();
+    let root_path = format!("/{}", root.name);
+    if !paint.apply(root, &root_path, &mut converter.unsupported) {
+        paint = Paint::default();
+    }
+    let alpha = std::mem::replace(&mut paint.opacity, 1.0);
+
+    let mut children = converter.convert_children(root, &paint, &root_path);
+    if let Some(transform) = root.attr("transform") {
+        let mut group = VdElement::new("group");
+        group.attributes = converter.transform(transform, &root_path);
+        group.children = children;
+        children = vec![group];
+    }
+    if min_x != 0.0 || min_y != 0.0 {
+        let mut group = VdElement::new("group");
+        group.set("android:translateX", number(-min_x));
+        group.set("android:translateY", number(-min_y));
+        group.children = children;
+        children = vec![group];
+    }
+
+    if !converter.unsupported.is_empty() {
+        let unsupported = std::mem::take(&mut converter.unsupported);
+        return Err(converter.fail(unsupported));
+    }
+
+    let mut vector = VdElement::new("vector");
+    vector.set("xmlns:android", ANDROID_NS.to_string());
+    if converter.uses_aapt {
+        vector.set("xmlns:aapt", AAPT_NS.to_string());
+    }
+    let dimension = |name: &str, fallback: f64| {
+        root.attr(name)
+            .and_then(|value| length(value))
+            .unwrap_or(fallback)
+    };
+    vector.set(
+        "android:width",
+        format!("{}dp", number(dimension("width", width))),
+    );
+    vector.set(
+        "android:height",
+        format!("{}dp", number(dimension("height", height))),
+    );
+    vector.set("android:viewportWidth", number(width));
+    vector.set("android:viewportHeight", number(height));
+    if alpha < 1.0 {
+        vector.set("android:alpha", number(alpha));
+    }
+    vector.children = children;
+    Ok(vector)
+}
+
+/// An element of the drawable, with `android:` attributes
+pub(crate) struct VdElement {
+    pub(crate) name: &'static str,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children: Vec<VdElement>,
+}
+
+impl VdElement {
+    pub(crate) fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, name: &str, value: String) {
+        self.attributes.push((name.to_string(), value));
+    }
+
+    /// Write the element the way Android Studio does, one attribute per
+    /// line, with namespace declarations on the line of the root tag
+    fn write(&self, depth: usize, output: &mut String) {
+        let indent = INDENT.repeat(depth);
+        output.push_str(&indent);
+        output.push('<');
+        output.push_str(self.name);
+        for (name, value) in &self.attributes {
+            if depth == 0 && name.starts_with("xmlns:") && output.ends_with(self.name) {
+                output.push(' ');
+            } else {
+                output.push('\n');
+                output.push_str(&indent);
+                output.push_str(INDENT);
+            }
+            output.push_str(&format!("{}=\"{}\"", name, escape_attribute(value)));
+        }
+
+        if self.children.is_empty() {
+            output.push_str("/>\n");
+            return;
+        }
+        output.push_str(">\n");
+        for child in &self.children {
+            child.write(depth + 1, output);
+        }
+        output.push_str(&format!("{}</{}>\n", indent, self.name));
+    }
+}
+
+/// Presentation properties inherited down the tree
+#[derive(Debug, Clone)]
+struct Paint {
+    fill: String,
+    fill_opacity: f64,
+    fill_rule: String,
+    stroke: String,
+    stroke_width: f64,
+    stroke_opacity: f64,
+    stroke_linecap: String,
+    stroke_linejoin: String,
+    stroke_miterlimit: f64,
+    visible: bool,
+    /// Product of the `opacity` of the element and its ancestors
+    opacity: f64,
+}
+
+impl Default for Paint {
+    fn default() -> Self {
+        Self {
+            fill: "black".to_string(),
+            fill_opacity: 1.0,
+            fill_rule: "nonzero".to_string(),
+            stroke: "none".to_string(),
+            stroke_width: 1.0,
+            stroke_opacity: 1.0,
+            stroke_linecap: "butt".to_string(),
+            stroke_linejoin: "miter".to_string(),
+            stroke_miterlimit: 4.0,
+            visible: true,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl Paint {
+    /// Apply the presentation attributes and `style` of an element
+    ///
+    /// Returns `false` if the element is not displayed.
+    fn apply(&mut self, element: &Element, path: &str, unsupported: &mut Vec<String>) -> bool {
+        let style = element
+            .attr("style")
+            .map(|style| declarations(style))
+            .unwrap_or_default();
+        let properties = element
+            .attributes
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .chain(style.iter().map(|(name, value)| (*name, *value)));
+
+        let mut displayed = true;
+        for (name, value) in properties {
+            let value = value.trim();
+            if value == "inherit" {
+                continue;
+            }
+            match name {
+                "fill" => self.fill = value.to_string(),
+                "fill-opacity" => self.fill_opacity = opacity(value).unwrap_or(self.fill_opacity),
+                "fill-rule" => self.fill_rule = value.to_string(),
+                "stroke" => self.stroke = value.to_string(),
+                "stroke-width" => self.stroke_width = length(value).unwrap_or(self.stroke_width),
+                "stroke-opacity" => {
+                    self.stroke_opacity = opacity(value).unwrap_or(self.stroke_opacity)
+                }
+                "stroke-linecap" => self.stroke_linecap = value.to_string(),
+                "stroke-linejoin" => self.stroke_linejoin = value.to_string(),
+                "stroke-miterlimit" => {
+                    self.stroke_miterlimit = length(value).unwrap_or(self.stroke_miterlimit)
+                }
+                "opacity" => self.opacity *= opacity(value).unwrap_or(1.0),
+                "visibility" => self.visible = value == "visible",
+                "display" if value == "none" => displayed = false,
+                "filter" | "mask" | "stroke-dasharray" | "marker" | "marker-start"
+                | "marker-mid" | "marker-end"
+                    if value != "none" =>
+                {
+                    unsupported.push(format!("{} at {}", name, path))
+                }
+                _ => {}
+            }
+        }
+        displayed
+    }
+}
+
+struct Converter<'a> {
+    /// Name of the output format, for errors
+    format: &'static str,
+    /// Elements by ID, for gradients and clip paths
+    ids: HashMap<&'a str, &'a Element>,
+    /// Viewport width and height, for percentages
+    viewport: (f64, f64),
+    unsupported: Vec<String>,
+    uses_aapt: bool,
+}
+
+impl<'a> Converter<'a> {
+    fn fail(&self, features: Vec<String>) -> ExportError {
+        ExportError::Unsupported {
+            format: self.format,
+            features,
+        }
+    }
+
+    fn convert_children(&mut self, parent: &Element, paint: &Paint, path: &str) -> Vec<VdElement> {
+        let mut converted = Vec::new();
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for child in parent.child_elements() {
+            let n = seen.entry(child.name.as_str()).or_default();
+            *n += 1;
+            let path = format!("{}/{}[{}]", path, child.name, n);
+            converted.extend(self.convert_element(child, paint, &path));
+        }
+        converted
+    }
+
+    fn convert_element(
+        &mut self,
+        element: &Element,
+        paint: &Paint,
+        path: &str,
+    ) -> Option<VdElement> {
+        match element.name.as_str() {
+            // Not rendered, or only rendered where referenced
+            "title" | "desc" | "metadata" | "defs" | "clipPath" | "linearGradient"
+            | "radialGradient" | "mask" | "filter" | "pattern" | "marker" | "symbol" | "script" => {
+                return None
+            }
+            "style" if !super::has_text(element) => return None,
+            // Editor data in foreign namespaces
+            name if name.contains(':') => return None,
+            _ => {}
+        }
+
+        let mut paint = paint.clone();
+        if !paint.apply(element, path, &mut self.unsupported) {
+            return None;
+        }
+
+        let mut converted = match element.name.as_str() {
+            "g" | "a" => {
+                let mut group = VdElement::new("group");
+                group.children = self.convert_children(element, &paint, path);
+                if group.children.is_empty() {
+                    return None;
+                }
+                if let Some(id) = element.attr("id") {
+                    group.set("android:name", id.clone());
+                }
+                group
+            }
+            "path" | "rect" | "circle" | "ellipse" | "line" | "polyline" | "polygon" => {
+                self.convert_path(element, &paint, path)?
+            }
+            name => {
+                self.unsupported.push(format!("<{}> at {}", name, path));
+                return None;
+            }
+        };
+
+        // Transforms and clip paths need a group of their own
+        let transform = element
+            .attr("transform")
+            .map(|transform| self.transform(transform, path))
+            .unwrap_or_default();
+        let clip = element
+            .attr("clip-path")
+            .and_then(|value| self.clip_path(value, path));
+        if transform.is_empty() && clip.is_none() {
+            return Some(converted);
+        }
+        if converted.name != "group" {
+            let mut group = VdElement::new("group");
+            group.children.push(converted);
+            converted = group;
+        }
+        converted.attributes.extend(transform);
+        if let Some(clip) = clip {
+            converted.children.insert(0, clip);
+        }
+        Some(converted)
+    }
+
+    fn convert_path(&mut self, element: &Element, paint: &Paint, path: &str) -> Option<VdElement> {
+        let d = self.path_data(element, path)?;
+        if !paint.visible {
+            return None;
+        }
+
+        let mut converted = VdElement::new("path");
+        if let Some(id) = element.attr("id") {
+            converted.set("android:name", id.clone());
+        }
+        converted.set("android:pathData", d.clone());
+
+        let fill_alpha = paint.fill_opacity * paint.opacity;
+        if self.paint(&paint.fill, "android:fillColor", &d, &mut converted, path) {
+            if fill_alpha < 1.0 {
+                converted.set("android:fillAlpha", number(fill_alpha));
+            }
+            if paint.fill_rule == "evenodd" {
+                converted.set("android:fillType", "evenOdd".to_string());
+            }
+        }
+
+        let stroke_alpha = paint.stroke_opacity * paint.opacity;
+        if self.paint(
+            &paint.stroke,
+            "android:strokeColor",
+            &d,
+            &mut converted,
+            path,
+        ) {
+            if stroke_alpha < 1.0 {
+                converted.set("android:strokeAlpha", number(stroke_alpha));
+            }
+            converted.set("android:strokeWidth", number(paint.stroke_width));
+            if paint.stroke_linecap != "butt" {
+                converted.set("android:strokeLineCap", paint.stroke_linecap.clone());
+            }
+            match paint.stroke_linejoin.as_str() {
+                "round" | "bevel" => {
+                    converted.set("android:strokeLineJoin", paint.stroke_linejoin.clone())
+                }
+                _ => {}
+            }
+            if paint.stroke_miterlimit != 4.0 {
+                converted.set("android:strokeMiterLimit", number(paint.stroke_miterlimit));
+            }
+        }
+
+        Some(converted)
+    }
+
+    /// Path data of a path or shape, with shapes converted as by
+    /// `convertShapeToPath`
+    fn path_data(&mut self, element: &Element, path: &str) -> Option<String> {
+        let mut shape = element.clone();
+        convert_shape_element(&mut shape, true, None);
+        if shape.name == "rect" {
+            shape.set_attr("d".to_string(), rounded_rect(&shape)?);
+        } else if shape.name != "path" {
+            self.unsupported.push(format!(
+                "<{}> with non-numeric geometry at {}",
+                element.name, path
+            ));
+            return None;
+        }
+
+        let d = shape.attr("d")?.trim();
+        if d.is_empty() {
+            return None;
+        }
+        // A relative moveto is only relative to the origin at the start of
+        // a path, not when path data is joined
+        Some(match d.starts_with('m') {
+            true => format!("M0 0{}", d),
+            false => d.to_string(),
+        })
+    }
+
+    /// Set a fill or stroke color, or a gradient, on `target`
+    ///
+    /// Returns whether the path is painted with it.
+    fn paint(
+        &mut self,
+        value: &str,
+        attribute: &str,
+        d: &str,
+        target: &mut VdElement,
+        path: &str,
+    ) -> bool {
+        if value == "none" || value == "transparent" {
+            return false;
+        }
+        if let Some(id) = reference(value) {
+            return match self.gradient(id, d, path) {
+                Some(gradient) => {
+                    let mut attr = VdElement::new("aapt:attr");
+                    attr.set("name", attribute.to_string());
+                    attr.children.push(gradient);
+                    target.children.push(attr);
+                    self.uses_aapt = true;
+                    true
+                }
+                None => false,
+            };
+        }
+        match parse_color(value) {
+            Some(color) => {
+                target.set(attribute, argb(color, 1.0));
+                true
+            }
+            None => {
+                let name = attribute
+                    .trim_start_matches("android:")
+                    .trim_end_matches("Color");
+                self.unsupported
+                    .push(format!("{} color \"{}\" at {}", name, value, path));
+                false
+            }
+        }
+    }
+
+    fn gradient(&mut self, id: &str, d: &str, path: &str) -> Option<VdElement> {
+        let Some(&element) = self.ids.get(id) else {
+            self.unsupported
+                .push(format!("missing paint server #{} at {}", id, path));
+            return None;
+        };
+        let linear = match element.name.as_str() {
+            "linearGradient" => true,
+            "radialGradient" => false,
+            name => {
+                self.unsupported
+                    .push(format!("<{}> paint at {}", name, path));
+                return None;
+            }
+        };
+        let attr = |name: &str| self.gradient_attr(element, name);
+
+        let bounding_box = attr("gradientUnits") != Some("userSpaceOnUse");
+        let bounds = match bounding_box {
+            true => path_bounds(d)?,
+            false => (0.0, 0.0, self.viewport.0, self.viewport.1),
+        };
+        let matrix = attr("gradientTransform")
+            .map(|transform| {
+                transforms_to_matrix(&ConvertTransformPlugin.parse_transform_string(transform))
+            })
+            .unwrap_or_else(Matrix3::identity);
+        // Gradients only keep their shape under rotation, uniform scaling
+        // and translation
+        let scale = match decompose_matrix(&matrix) {
+            Some(parts) if (parts.scale_x.abs() - parts.scale_y.abs()).abs() < 1e-9 => {
+                parts.scale_x.abs()
+            }
+            _ => {
+                self.unsupported
+                    .push(format!("gradientTransform of #{} at {}", id, path));
+                return None;
+            }
+        };
+
+        // Coordinates are fractions of the bounding box, or user units with
+        // percentages of the viewport
+        let (width, height) = (bounds.2 - bounds.0, bounds.3 - bounds.1);
+        let coordinate = |name: &str, default: &str, size: f64| {
+            let value = attr(name).unwrap_or(default);
+            match value.trim().strip_suffix('%') {
+                Some(percent) => {
+                    let fraction = percent.trim().parse::<f64>().unwrap_or(0.0) / 100.0;
+                    if bounding_box {
+                        fraction
+                    } else {
+                        fraction * size
+                    }
+                }
+                None => length(value).unwrap_or(0.0),
+            }
+        };
+        let point = |x: f64, y: f64| {
+            let p = matrix * Vector3::new(x, y, 1.0);
+            match bounding_box {
+                true => (bounds.0 + p.x * width, bounds.1 + p.y * height),
+                false => (p.x, p.y),
+            }
+        };
+
+        let mut gradient = VdElement::new("gradient");
+        if linear {
+            let start = point(
+                coordinate("x1", "0%", self.viewport.0),
+                coordinate("y1", "0%", self.viewport.1),
+            );
+            let end = point(
+                coordinate("x2", "100%", self.viewport.0),
+                coordinate("y2", "0%", self.viewport.1),
+            );
+            gradient.set("android:type", "linear".to_string());
+            gradient.set("android:startX", number(start.0));
+            gradient.set("android:startY", number(start.1));
+            gradient.set("android:endX", number(end.0));
+            gradient.set("android:endY", number(end.1));
+        } else {
+            let (cx, cy) = (
+                coordinate("cx", "50%", self.viewport.0),
+                coordinate("cy", "50%", self.viewport.1),
+            );
+            let focus = (
+                attr("fx").map(|_| coordinate("fx", "", self.viewport.0)),
+                attr("fy").map(|_| coordinate("fy", "", self.viewport.1)),
+            );
+            if focus.0.is_some_and(|fx| fx != cx) || focus.1.is_some_and(|fy| fy != cy) {
+                self.unsupported
+                    .push(format!("focal point of #{} at {}", id, path));
+                return None;
+            }
+            // Radial gradients over a non-square bounding box are elliptical
+            if bounding_box && (width - height).abs() > 1e-6 * width.max(height) {
+                self.unsupported
+                    .push(format!("elliptical gradient #{} at {}", id, path));
+                return None;
+            }
+            let diagonal = ((self.viewport.0.powi(2) + self.viewport.1.powi(2)) / 2.0).sqrt();
+            let radius = coordinate("r", "50%", diagonal) * scale;
+            let center = point(cx, cy);
+            gradient.set("android:type", "radial".to_string());
+            gradient.set("android:centerX", number(center.0));
+            gradient.set("android:centerY", number(center.1));
+            gradient.set(
+                "android:gradientRadius",
+                number(if bounding_box { radius * width } else { radius }),
+            );
+        }
+        match attr("spreadMethod") {
+            Some("reflect") => gradient.set("android:tileMode", "mirror".to_string()),
+            Some("repeat") => gradient.set("android:tileMode", "repeat".to_string()),
+            _ => {}
+        }
+
+        let mut offset: f64 = 0.0;
+        for stop in self.gradient_stops(element) {
+            let style = stop
+                .attr("style")
+                .map(|s| declarations(s))
+                .unwrap_or_default();
+            let property = |name: &str| {
+                style
+                    .iter()
+                    .rev()
+                    .find(|(property, _)| *property == name)
+                    .map(|(_, value)| *value)
+                    .or_else(|| stop.attr(name).map(String::as_str))
+            };
+            // Offsets never decrease
+            offset = offset.max(opacity(stop.attr("offset").map_or("0", |o| o)).unwrap_or(0.0));
+            let value = property("stop-color").unwrap_or("black");
+            let Some(color) = parse_color(value) else {
+                self.unsupported
+                    .push(format!("stop-color \"{}\" of #{} at {}", value, id, path));
+                return None;
+            };
+            let alpha = property("stop-opacity").and_then(opacity).unwrap_or(1.0);
+
+            let mut item = VdElement::new("item");
+            item.set("android:offset", number(offset));
+            item.set("android:color", argb(color, alpha));
+            gradient.children.push(item);
+        }
+        Some(gradient)
+    }
+
+    /// Follow `href` links to the gradient that defines `name`
+    fn gradient_attr(&self, element: &'a Element, name: &str) -> Option<&'a str> {
+        let mut current = element;
+        for _ in 0..8 {
+            if let Some(value) = current.attr(name) {
+                return Some(value);
+            }
+            current = self.href(current)?;
+        }
+        None
+    }
+
+    /// Stops of a gradient, or of the first gradient it links to that has any
+    fn gradient_stops(&self, element: &'a Element) -> Vec<&'a Element> {
+        let mut current = element;
+        for _ in 0..8 {
+            let stops: Vec<&Element> = current
+                .child_elements()
+                .filter(|child| child.name == "stop")
+                .collect();
+            if !stops.is_empty() {
+                return stops;
+            }
+            match self.href(current) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        Vec::new()
+    }
+
+    fn href(&self, element: &Element) -> Option<&'a Element> {
+        let href = element
+            .attr("href")
+            .or_else(|| element.attr("xlink:href"))?;
+        self.ids.get(href.strip_prefix('#')?).copied()
+    }
+
+    /// Group attributes for a transform
+    fn transform(&mut self, transform: &str, path: &str) -> Vec<(String, String)> {
+        let matrix =
+            transforms_to_matrix(&ConvertTransformPlugin.parse_transform_string(transform));
+        let Some(parts) = decompose_matrix(&matrix) else {
+            self.unsupported
+                .push(format!("skewing transform at {}", path));
+            return Vec::new();
+        };
+
+        // Matrices rounded by convertTransform decompose to scales such as
+        // 0.9998, so rotation and scale are snapped to a few decimals
+        let snap = |value: f64, decimals: i32| {
+            let factor = 10f64.powi(decimals);
+            (value * factor).round() / factor
+        };
+        let mut attributes = Vec::new();
+        let mut set = |name: &str, value: f64, default: f64| {
+            if (value - default).abs() > 1e-9 {
+                attributes.push((name.to_string(), number(value)));
+            }
+        };
+        set("android:rotation", snap(parts.rotate, 2), 0.0);
+        set("android:scaleX", snap(parts.scale_x, 3), 1.0);
+        set("android:scaleY", snap(parts.scale_y, 3), 1.0);
+        set("android:translateX", parts.translate_x, 0.0);
+        set("android:translateY", parts.translate_y, 0.0);
+        attributes
+    }
+
+    /// A `<clip-path>` for a `clip-path` property
+    fn clip_path(&mut self, value: &str, path: &str) -> Option<VdElement> {
+        let id = reference(value)?;
+        let clip = match self.ids.get(id) {
+            Some(&clip) if clip.name == "clipPath" => clip,
+            _ => {
+                self.unsupported
+                    .push(format!("clip-path #{} at {}", id, path));
+                return None;
+            }
+        };
+        if clip.attr("clipPathUnits").map(String::as_str) == Some("objectBoundingBox")
+            || clip.has_attr("transform")
+            || clip
+                .child_elements()
+                .any(|child| child.has_attr("transform"))
+        {
+            self.unsupported
+                .push(format!("transformed clip-path #{} at {}", id, path));
+            return None;
+        }
+
+        let data: Vec<String> = clip
+            .child_elements()
+            .filter_map(|child| self.path_data(child, path))
+            .collect();
+        let mut converted = VdElement::new("clip-path");
+        converted.set("android:pathData", data.join(""));
+        Some(converted)
+    }
+}
+
+/// The viewBox, or the size of the root if it has none
+fn viewport(root: &Element) -> Option<(f64, f64, f64, f64)> {
+    if let Some(view_box) = root.attr("viewBox") {
+        let values: Vec<f64> = view_box
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|part| !part.is_empty())
+            .filter_map(|part| part.parse().ok())
+            .collect();
+        if let [x, y, width, height] = values[..] {
+            if width > 0.0 && height > 0.0 {
+                return Some((x, y, width, height));
+            }
+        }
+    }
+    let width = length(root.attr("width")?)?;
+    let height = length(root.attr("height")?)?;
+    Some((0.0, 0.0, width, height))
+}
+
+fn collect_ids<'a>(element: &'a Element, ids: &mut HashMap<&'a str, &'a Element>) {
+    if let Some(id) = element.attr("id") {
+        ids.insert(id.as_str(), element);
+    }
+    for child in element.child_elements() {
+        collect_ids(child, ids);
+    }
+}
+
+/// Declarations of a `style` attribute
+fn declarations(style: &str) -> Vec<(&str, &str)> {
+    style
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect()
+}
+
+/// The ID in `url(#id)`
+fn reference(value: &str) -> Option<&str> {
+    let url = value.trim().strip_prefix("url(")?.split(')').next()?;
+    url.trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .strip_prefix('#')
+}
+
+/// A unitless or pixel length
+fn length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// An opacity or offset, as a number or percentage, clamped to 0..1
+fn opacity(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+        None => value.parse::<f64>().ok()?,
+    };
+    Some(opacity.clamp(0.0, 1.0))
+}
+
+/// Parse a CSS color to red, green, blue and alpha
+///
+/// `currentColor` is black; tint the drawable to color it.
+fn parse_color(value: &str) -> Option<(u8, u8, u8, f64)> {
+    let value = value.trim();
+    let lower = value.to_ascii_lowercase();
+    if lower == "currentcolor" {
+        return Some((0, 0, 0, 1.0));
+    }
+    let hex = match lower.strip_prefix('#') {
+        Some(hex) => hex,
+        None => match COLORS_NAMES.get(lower.as_str()) {
+            Some(hex) => &hex[1..],
+            None => return parse_rgb(&lower),
+        },
+    };
+
+    let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+    let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    match hex.len() {
+        3 | 4 => Some((
+            digit(0)? * 17,
+            digit(1)? * 17,
+            digit(2)? * 17,
+            match hex.len() {
+                4 => f64::from(digit(3)? * 17) / 255.0,
+                _ => 1.0,
+            },
+        )),
+        6 | 8 => Some((
+            byte(0)?,
+            byte(2)?,
+            byte(4)?,
+            match hex.len() {
+                8 => f64::from(byte(6)?) / 255.0,
+                _ => 1.0,
+            },
+        )),
+        _ => None,
+    }
+}
+
+/// `rgb()` and `rgba()` with numbers or percentages
+fn parse_rgb(value: &str) -> Option<(u8, u8, u8, f64)> {
+    let arguments = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+    let parts: Vec<&str> = arguments
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let channel = |part: &str| -> Option<u8> {
+        let value = match part.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok()? * 2.55,
+            None => part.parse::<f64>().ok()?,
+        };
+        Some(value.round().clamp(0.0, 255.0) as u8)
+    };
+    match parts[..] {
+        [r, g, b] => Some((channel(r)?, channel(g)?, channel(b)?, 1.0)),
+        [r, g, b, a] => Some((channel(r)?, channel(g)?, channel(b)?, opacity(a)?)),
+        _ => None,
+    }
+}
+
+/// `#AARRGGBB` with the color's alpha times `alpha`
+fn argb((r, g, b, a): (u8, u8, u8, f64), alpha: f64) -> String {
+    let alpha = (a * alpha * 255.0).round().clamp(0.0, 255.0) as u8;
+    format!("#{:02X}{:02X}{:02X}{:02X}", alpha, r, g, b)
+}
+
+/// Format a number with at most four decimals
+pub(crate) fn number(value: f64) -> String {
+    let formatted = format!("{:.4}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" | "" => "0".to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+/// Path data for a rectangle with rounded corners
+fn rounded_rect(rect: &Element) -> Option<String> {
+    let get = |name: &str| rect.attr(name).map_or(Some(0.0), |value| length(value));
+    let (x, y, width, height) = (get("x")?, get("y")?, get("width")?, get("height")?);
+    let rx = rect.attr("rx").and_then(|value| length(value));
+    let ry = rect.attr("ry").and_then(|value| length(value));
+    let rx = rx.or(ry).unwrap_or(0.0).min(width / 2.0);
+    let ry = ry.or(Some(rx)).unwrap_or(0.0).min(height / 2.0);
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+
+    let arc = |x: f64, y: f64| {
+        format!(
+            "A{} {} 0 0 1 {} {}",
+            number(rx),
+            number(ry),
+            number(x),
+            number(y)
+        )
+    };
+    Some(format!(
+        "M{} {}H{}{}V{}{}H{}{}V{}{}z",
+        number(x + rx),
+        number(y),
+        number(x + width - rx),
+        arc(x + width, y + ry),
+        number(y + height - ry),
+        arc(x + width - rx, y + height),
+        number(x + rx),
+        arc(x, y + height - ry),
+        number(y + ry),
+        arc(x + rx, y),
+    ))
+}
+
+/// Bounding box of path data as `(min_x, min_y, max_x, max_y)`
+///
+/// Curves are bounded by their control points and arcs are sampled, which
+/// is close enough to place gradients.
+fn path_bounds(d: &str) -> Option<(f64, f64, f64, f64)> {
+    let commands = parse_path_data(d).ok()?;
+    let mut points = Vec::new();
+    let (mut x, mut y) = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    // Last control point of a cubic or quadratic curve, for reflection
+    let mut cubic: Option<(f64, f64)> = None;
+    let mut quadratic: Option<(f64, f64)> = None;
+
+    for command in &commands {
+        let (dx, dy) = if command.is_absolute {
+            (0.0, 0.0)
+        } else {
+            (x, y)
+        };
+        let p: Vec<(f64, f64)> = command
+            .params
+            .chunks(2)
+            .filter(|pair| pair.len() == 2)
+            .map(|pair| (pair[0] + dx, pair[1] + dy))
+            .collect();
+        let reflect = |control: Option<(f64, f64)>| {
+            control.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy))
+        };
+        let (mut next_cubic, mut next_quadratic) = (None, None);
+
+        match command.cmd_type {
+            CommandType::MoveTo | CommandType::LineTo => {
+                (x, y) = p[0];
+                if command.cmd_type == CommandType::MoveTo {
+                    start = (x, y);
+                }
+            }
+            CommandType::HorizontalLineTo => x = command.params[0] + dx,
+            CommandType::VerticalLineTo => y = command.params[0] + dy,
+            CommandType::CurveTo => {
+                points.extend(&p[..2]);
+                next_cubic = Some(p[1]);
+                (x, y) = p[2];
+            }
+            CommandType::SmoothCurveTo => {
+                points.push(reflect(cubic));
+                points.push(p[0]);
+                next_cubic = Some(p[0]);
+                (x, y) = p[1];
+            }
+            CommandType::QuadraticBezier => {
+                points.push(p[0]);
+                next_quadratic = Some(p[0]);
+                (x, y) = p[1];
+            }
+            CommandType::SmoothQuadraticBezier => {
+                let control = reflect(quadratic);
+                points.push(control);
+                next_quadratic = Some(control);
+                (x, y) = p[0];
+            }
+            CommandType::Arc => {
+                let params = &command.params;
+                let end = (params[5] + dx, params[6] + dy);
+                arc_points(
+                    (x, y),
+                    (params[0], params[1]),
+                    params[2],
+                    params[3] != 0.0,
+                    params[4] != 0.0,
+                    end,
+                    &mut points,
+                );
+                (x, y) = end;
+            }
+            CommandType::ClosePath => (x, y) = start,
+        }
+        points.push((x, y));
+        (cubic, quadratic) = (next_cubic, next_quadratic);
+    }
+
+    points.iter().fold(None, |bounds, &(px, py)| {
+        let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((px, py, px, py));
+        Some((min_x.min(px), min_y.min(py), max_x.max(px), max_y.max(py)))
+    })
+}
+
+/// Sample points along an elliptical arc
+fn arc_points(
+    from: (f64, f64),
+    (rx, ry): (f64, f64),
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+    to: (f64, f64),
+    points: &mut Vec<(f64, f64)>,
+) {
+    const SAMPLES: usize = 16;
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+    if rx == 0.0 || ry == 0.0 || from == to {
+        return;
+    }
+
+    // Center parameterization, as in SVG 1.1 appendix F.6.5
+    let (sin, cos) = (rotation * PI / 180.0).sin_cos();
+    let (hx, hy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let (x1, y1) = (cos * hx + sin * hy, -sin * hx + cos * hy);
+    let lambda = (x1 / rx).powi(2) + (y1 / ry).powi(2);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = (rx * ry).powi(2) - (rx * y1).powi(2) - (ry * x1).powi(2);
+    let denominator = (rx * y1).powi(2) + (ry * x1).powi(2);
+    let mut coefficient = match denominator {
+        0.0 => 0.0,
+        _ => (numerator / denominator).max(0.0).sqrt(),
+    };
+    if large_arc == sweep {
+        coefficient = -coefficient;
+    }
+    let (cx1, cy1) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+    let center = (
+        cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+        sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+    );
+
+    let angle = |ux: f64, uy: f64, vx: f64, vy: f64| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+    let (ux, uy) = ((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let start = angle(1.0, 0.0, ux, uy);
+    let mut delta = angle(ux, uy, (-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    if !sweep && delta > 0.0 {
+        delta -= 2.0 * PI;
+    } else if sweep && delta < 0.0 {
+        delta += 2.0 * PI;
+    }
+
+    for i in 1..SAMPLES {
+        let (sin_t, cos_t) = (start + delta * i as f64 / SAMPLES as f64).sin_cos();
+        points.push((
+            center.0 + rx * cos_t * cos - ry * sin_t * sin,
+            center.1 + rx * cos_t * sin + ry * sin_t * cos,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn convert(svg: &str) -> ExportResult<String> {
+        VectorDrawableStringifier::new().stringify(&Parser::new().parse(svg).unwrap())
+    }
+
+    #[test]
+    fn test_vector() {
+        let output = convert(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="48" height="48" viewBox="0 0 24 24"><g id="icon" transform="translate(2 3) rotate(90)" fill="#f00" opacity=".5"><rect width="4" height="2"/><path d="M0 0h4" stroke="rgb(0,0,255)" stroke-linecap="round" fill="none" fill-rule="evenodd"/></g><circle cx="12" cy="12" r="2" style="fill-rule:evenodd"/></svg>"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            r##"<vector xmlns:android="http://schemas.android.com/apk/res/android"
+    android:width="48dp"
+    android:height="48dp"
+    android:viewportWidth="24"
+    android:viewportHeight="24">
+    <group
+        android:name="icon"
+        android:rotation="90"
+        android:translateX="2"
+        android:translateY="3">
+        <path
+            android:pathData="M0 0H4V2H0z"
+            android:fillColor="#FFFF0000"
+            android:fillAlpha="0.5"/>
+        <path
+            android:pathData="M0 0h4"
+            android:strokeColor="#FF0000FF"
+            android:strokeAlpha="0.5"
+            android:strokeWidth="1"
+            android:strokeLineCap="round"/>
+    </group>
+    <path
+        android:pathData="M12 10A2 2 0 1 0 12 14A2 2 0 1 0 12 10z"
+        android:fillColor="#FF000000"
+        android:fillType="evenOdd"/>
+</vector>
+"##
+        );
+    }
+
+    #[test]
+    fn test_gradient() {
+        let output = convert(
+            r##"<svg viewBox="0 0 24 24"><linearGradient id="a"><stop offset="0" stop-color="red"/><stop offset="100%" style="stop-color:#00f;stop-opacity:.5"/></linearGradient><radialGradient id="b" href="#a" gradientUnits="userSpaceOnUse" cx="12" cy="12" r="10" spreadMethod="reflect"/><path d="M2 4h10v10H2z" fill="url(#a)"/><path d="M0 0h24v24H0z" fill="url(#b)"/></svg>"##,
+        )
+        .unwrap();
+
+        assert!(output.contains(r#"xmlns:aapt="http://schemas.android.com/aapt""#));
+        assert!(output.contains(
+            r##"        <aapt:attr
+            name="android:fillColor">
+            <gradient
+                android:type="linear"
+                android:startX="2"
+                android:startY="4"
+                android:endX="12"
+                android:endY="4">
+                <item
+                    android:offset="0"
+                    android:color="#FFFF0000"/>
+                <item
+                    android:offset="1"
+                    android:color="#800000FF"/>
+            </gradient>
+        </aapt:attr>"##
+        ));
+        assert!(output.contains(
+            "android:type=\"radial\"\n                android:centerX=\"12\"\n                android:centerY=\"12\"\n                android:gradientRadius=\"10\"\n                android:tileMode=\"mirror\">"
+        ));
+    }
+
+    #[test]
+    fn test_clip_path() {
+        let output = convert(
+            r#"<svg viewBox="0 0 24 24"><clipPath id="c"><rect width="12" height="24" rx="2"/></clipPath><path d="m0 0h24v24H0z" clip-path="url(#c)"/></svg>"#,
+        )
+        .unwrap();
+
+        assert!(output.contains(
+            r##"    <group>
+        <clip-path
+            android:pathData="M2 0H10A2 2 0 0 1 12 2V22A2 2 0 0 1 10 24H2A2 2 0 0 1 0 22V2A2 2 0 0 1 2 0z"/>
+        <path
+            android:pathData="M0 0m0 0h24v24H0z"
+            android:fillColor="#FF000000"/>
+    </group>"##
+        ));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let error = convert(
+            r#"<svg viewBox="0 0 24 24"><text>A</text><g filter="url(#f)" transform="skewX(10)"><path d="M0 0h1" stroke="red" stroke-dasharray="1"/></g></svg>"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "VectorDrawable does not support <text> at /svg/text[1], \
+             filter at /svg/g[1], stroke-dasharray at /svg/g[1]/path[1], \
+             skewing transform at /svg/g[1]"
+        );
+    }
+
+    #[test]
+    fn test_path_bounds() {
+        assert_eq!(path_bounds("M1 2h3v4z"), Some((1.0, 2.0, 4.0, 6.0)));
+        let (min_x, min_y, max_x, max_y) = path_bounds("M0 0a5 5 0 0 0 10 0").unwrap();
+        assert_eq!((min_x, min_y, max_x), (0.0, 0.0, 10.0));
+        assert!((max_y - 5.0).abs() < 0.1);
+    }
+}
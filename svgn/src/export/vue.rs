@@ -0,0 +1,178 @@
+// this_file: svgn/src/export/vue.rs
+
+//! Vue single-file component stringifier
+//!
+//! Writes the document as the `<template>` of a Vue 3 single-file
+//! component. The `<svg>` is the only root node, so attributes and listeners
+//! passed to the component fall through to it.
+
+use super::js_quoted;
+use super::template::{
+    escape_attribute, escape_text, write_element, RootBindings, TemplateSyntax, INDENT,
+};
+use crate::ast::{Document, Element};
+use crate::stringifier::StringifyResult;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Vue SFC stringifier
+#[derive(Debug, Clone, Default)]
+pub struct VueStringifier {
+    /// Accept a `size` prop
+    size_prop: bool,
+}
+
+impl VueStringifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a `size` prop that replaces the width and height of the root
+    pub fn size_prop(mut self, size_prop: bool) -> Self {
+        self.size_prop = size_prop;
+        self
+    }
+
+    /// Stringify a document as a component
+    pub fn stringify(&self, document: &Document) -> StringifyResult<String> {
+        let root = &document.root;
+        let mut output = String::new();
+        let mut bindings = RootBindings {
+            replaced: &[],
+            bindings: String::new(),
+        };
+
+        if self.size_prop {
+            writeln!(output, "<script setup>")?;
+            writeln!(
+                output,
+                "defineProps({{\n{}size: [Number, String],\n}});",
+                INDENT
+            )?;
+            writeln!(output, "</script>\n")?;
+
+            bindings.replaced = &["width", "height"];
+            for name in ["width", "height"] {
+                // Without a size, keep the original dimension
+                let value = match root.attr(name) {
+                    Some(value) => format!("size ?? {}", js_quoted(value, '\'')),
+                    None => "size".to_string(),
+                };
+                write!(
+                    bindings.bindings,
+                    " :{}=\"{}\"",
+                    name,
+                    escape_attribute(&value)
+                )?;
+            }
+        }
+
+        writeln!(output, "<template>")?;
+        write_element(&VueSyntax, root, 1, false, Some(&bindings), &mut output)?;
+        writeln!(output, "\n</template>")?;
+        Ok(output)
+    }
+}
+
+struct VueSyntax;
+
+impl VueSyntax {
+    /// The compiler drops `<style>` and `<script>` from templates, but not
+    /// when they are rendered as dynamic components
+    fn is_side_effect_tag(element: &Element) -> bool {
+        matches!(element.name.as_str(), "style" | "script")
+    }
+}
+
+impl TemplateSyntax for VueSyntax {
+    fn text<'a>(&self, _parent: &Element, text: &'a str) -> Cow<'a, str> {
+        let escaped = escape_text(text);
+        if escaped.contains("{{") {
+            // An interpolation of the delimiter itself
+            Cow::Owned(escaped.replace("{{", "{{ '{{' }}"))
+        } else {
+            escaped
+        }
+    }
+
+    fn attribute<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        escape_attribute(value)
+    }
+
+    fn tag_name<'a>(&self, element: &'a Element) -> &'a str {
+        if Self::is_side_effect_tag(element) {
+            "component"
+        } else {
+            &element.name
+        }
+    }
+
+    fn directives(&self, element: &Element) -> Vec<String> {
+        if Self::is_side_effect_tag(element) {
+            vec![format!("is=\"{}\"", element.name)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn vue(svg: &str, stringifier: VueStringifier) -> String {
+        stringifier
+            .stringify(&Parser::new().parse(svg).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_component() {
+        assert_eq!(
+            vue(
+                r#"<svg viewBox="0 0 24 24" width="24" height="24"><!-- icon --><g class="a"><path d="M0 0"/></g></svg>"#,
+                VueStringifier::new()
+            ),
+            r#"<template>
+  <svg viewBox="0 0 24 24" width="24" height="24">
+    <g class="a">
+      <path d="M0 0" />
+    </g>
+  </svg>
+</template>
+"#
+        );
+    }
+
+    #[test]
+    fn test_size_prop() {
+        assert_eq!(
+            vue(
+                r#"<svg width="1em" viewBox="0 0 24 24"/>"#,
+                VueStringifier::new().size_prop(true)
+            ),
+            r#"<script setup>
+defineProps({
+  size: [Number, String],
+});
+</script>
+
+<template>
+  <svg viewBox="0 0 24 24" :width="size ?? '1em'" :height="size" />
+</template>
+"#
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        let output = vue(
+            r#"<svg><style>.a{fill:red}</style><text title="&quot;{{x}}&quot;">{{ x }} &lt; y</text></svg>"#,
+            VueStringifier::new(),
+        );
+
+        assert!(output.contains(r#"<component is="style">.a{fill:red}</component>"#));
+        assert!(output.contains(r#"<text title="&quot;{{x}}&quot;">{{ '{{' }} x }} &lt; y</text>"#));
+    }
+}
@@ -0,0 +1,94 @@
+// this_file: svgn/src/import.rs
+
+//! Input formats
+//!
+//! Besides SVG, the optimizer reads Android VectorDrawables. The input is
+//! parsed as XML like any SVG, then converted to an SVG document before the
+//! plugins run. [`Config::input_format`] selects the format; without it, the
+//! format is detected from the extension of [`Config::path`].
+//!
+//! [`Config::input_format`]: crate::config::Config::input_format
+//! [`Config::path`]: crate::config::Config::path
+
+pub mod vector_drawable;
+
+use crate::ast::Document;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// Import errors
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("{format} input must have a <{expected}> root, not <{found}>")]
+    UnexpectedRoot {
+        format: &'static str,
+        expected: &'static str,
+        found: String,
+    },
+
+    #[error("{format} input has what SVG can't express: {}", .features.join(", "))]
+    Unsupported {
+        format: &'static str,
+        /// What can't be converted, and where in the input
+        features: Vec<String>,
+    },
+}
+
+/// Import result type
+pub type ImportResult<T> = Result<T, ImportError>;
+
+/// What the optimizer reads
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputFormat {
+    /// SVG markup
+    #[default]
+    Svg,
+    /// Android VectorDrawable XML
+    VectorDrawable,
+}
+
+impl InputFormat {
+    /// Input format for a file name: `.xml` files are VectorDrawables, and
+    /// everything else is SVG
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("xml") => InputFormat::VectorDrawable,
+            _ => InputFormat::Svg,
+        }
+    }
+}
+
+/// Convert a parsed input document to SVG
+pub fn import(document: Document, format: InputFormat) -> ImportResult<Document> {
+    match format {
+        InputFormat::Svg => Ok(document),
+        InputFormat::VectorDrawable => vector_drawable::convert(&document),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            InputFormat::from_path(Path::new("res/drawable/ic_add.xml")),
+            InputFormat::VectorDrawable
+        );
+        assert_eq!(
+            InputFormat::from_path(Path::new("ic_add.XML")),
+            InputFormat::VectorDrawable
+        );
+        assert_eq!(
+            InputFormat::from_path(Path::new("ic_add.svg")),
+            InputFormat::Svg
+        );
+        assert_eq!(
+            InputFormat::from_path(Path::new("ic_add")),
+            InputFormat::Svg
+        );
+    }
+}
@@ -0,0 +1,669 @@
+// this_file: svgn/src/import/vector_drawable.rs
+
+//! Android VectorDrawable import
+//!
+//! Converts a `<vector>` drawable to SVG: the viewport becomes the viewBox,
+//! `<group>` transforms become `transform` attributes, `<clip-path>`s clip
+//! the siblings that follow them, and `<aapt:attr>` gradients become SVG
+//! gradients. Colors in the `#AARRGGBB` forms become an RGB color and an
+//! opacity. Resource references such as `@color/accent` can't be resolved
+//! outside the app and are reported as [`ImportError::Unsupported`], like
+//! `android:tint` and everything else SVG can't express.
+
+use super::{ImportError, ImportResult};
+use crate::ast::{Document, Element, Node};
+use std::collections::{HashMap, HashSet};
+
+const ANDROID_NS: &str = "http://schemas.android.com/apk/res/android";
+const AAPT_NS: &str = "http://schemas.android.com/aapt";
+const SVG_NS: &str = "http://www.w3.org/2000/svg";
+
+/// Convert a parsed VectorDrawable to an SVG document
+pub fn convert(document: &Document) -> ImportResult<Document> {
+    let root = &document.root;
+    if root.name != "vector" {
+        return Err(ImportError::UnexpectedRoot {
+            format: "VectorDrawable",
+            expected: "vector",
+            found: root.name.clone(),
+        });
+    }
+
+    let mut converter = Converter::new(root);
+    let svg = converter.convert_root(root);
+    if !converter.unsupported.is_empty() {
+        return Err(ImportError::Unsupported {
+            format: "VectorDrawable",
+            features: converter.unsupported,
+        });
+    }
+
+    Ok(Document {
+        prologue: document.prologue.clone(),
+        root: svg,
+        epilogue: document.epilogue.clone(),
+        metadata: document.metadata.clone(),
+    })
+}
+
+/// How a path is painted
+enum Paint {
+    /// `#rrggbb` color and its alpha
+    Color(String, f64),
+    /// ID of a gradient
+    Gradient(String),
+}
+
+struct Converter {
+    /// Prefixes bound to the android and aapt namespaces, with the colon
+    android: String,
+    aapt: String,
+    /// Clip paths and gradients, written to `<defs>`
+    defs: Vec<Node>,
+    /// IDs taken by `android:name`s and generated IDs
+    ids: HashSet<String>,
+    unsupported: Vec<String>,
+}
+
+impl Converter {
+    fn new(root: &Element) -> Self {
+        let prefix = |namespace: &str, default: &str| {
+            root.attributes
+                .iter()
+                .find(|(_, value)| value.as_str() == namespace)
+                .and_then(|(name, _)| name.strip_prefix("xmlns:"))
+                .unwrap_or(default)
+                .to_string()
+                + ":"
+        };
+        let mut converter = Self {
+            android: prefix(ANDROID_NS, "android"),
+            aapt: prefix(AAPT_NS, "aapt"),
+            defs: Vec::new(),
+            ids: HashSet::new(),
+            unsupported: Vec::new(),
+        };
+        converter.collect_names(root);
+        converter
+    }
+
+    fn collect_names(&mut self, element: &Element) {
+        if let Some(name) = self.attr(element, "name") {
+            self.ids.insert(name.to_string());
+        }
+        for child in element.child_elements() {
+            self.collect_names(child);
+        }
+    }
+
+    /// Value of an `android:` attribute
+    fn attr<'a>(&self, element: &'a Element, name: &str) -> Option<&'a str> {
+        element
+            .attr(&format!("{}{}", self.android, name))
+            .map(String::as_str)
+    }
+
+    /// Numeric `android:` attribute, reporting values that aren't numbers
+    fn number(&mut self, element: &Element, name: &str, path: &str) -> Option<f64> {
+        let value = self.attr(element, name)?;
+        match value.trim().parse() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                self.unsupport(name, value, path);
+                None
+            }
+        }
+    }
+
+    fn unsupport(&mut self, name: &str, value: &str, path: &str) {
+        self.unsupported.push(format!(
+            "{}{}=\"{}\" at {}",
+            self.android, name, value, path
+        ));
+    }
+
+    /// An unused ID starting with `prefix`
+    fn generate_id(&mut self, prefix: &str) -> String {
+        let mut n = 1;
+        while self.ids.contains(&format!("{}{}", prefix, n)) {
+            n += 1;
+        }
+        let id = format!("{}{}", prefix, n);
+        self.ids.insert(id.clone());
+        id
+    }
+
+    fn convert_root(&mut self, root: &Element) -> Element {
+        let path = "/vector";
+        let mut svg = Element::new("svg");
+        svg.set_attr("xmlns".to_string(), SVG_NS.to_string());
+
+        let width = self.dimension(root, "width", path);
+        let height = self.dimension(root, "height", path);
+        let viewport_width = self.number(root, "viewportWidth", path).or(width);
+        let viewport_height = self.number(root, "viewportHeight", path).or(height);
+        if let Some(width) = width {
+            svg.set_attr("width".to_string(), number(width));
+        }
+        if let Some(height) = height {
+            svg.set_attr("height".to_string(), number(height));
+        }
+        match (viewport_width, viewport_height) {
+            (Some(width), Some(height)) => svg.set_attr(
+                "viewBox".to_string(),
+                format!("0 0 {} {}", number(width), number(height)),
+            ),
+            _ => self
+                .unsupported
+                .push(format!("a viewport without a size at {}", path)),
+        }
+        if let Some(alpha) = self.number(root, "alpha", path) {
+            if alpha < 1.0 {
+                svg.set_attr("opacity".to_string(), number(alpha));
+            }
+        }
+        // A tint recolors everything drawn, usually from a theme attribute
+        if let Some(tint) = self.attr(root, "tint") {
+            self.unsupport("tint", tint, path);
+        }
+
+        let children = self.convert_children(root, path);
+        if !self.defs.is_empty() {
+            let mut defs = Element::new("defs");
+            defs.children = std::mem::take(&mut self.defs);
+            svg.children.push(Node::Element(defs));
+        }
+        svg.children.extend(children);
+        svg
+    }
+
+    /// `android:width` or `height` in dp, which map to CSS pixels
+    fn dimension(&mut self, element: &Element, name: &str, path: &str) -> Option<f64> {
+        let value = self.attr(element, name)?;
+        let trimmed = value.trim();
+        let number = ["dip", "dp", "px"]
+            .iter()
+            .find_map(|unit| trimmed.strip_suffix(unit))
+            .unwrap_or(trimmed);
+        match number.trim().parse() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                self.unsupport(name, value, path);
+                None
+            }
+        }
+    }
+
+    fn convert_children(&mut self, parent: &Element, path: &str) -> Vec<Node> {
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        let children: Vec<(&Element, String)> = parent
+            .child_elements()
+            .map(|child| {
+                let n = seen.entry(child.name.as_str()).or_default();
+                *n += 1;
+                (child, format!("{}/{}[{}]", path, child.name, n))
+            })
+            .collect();
+        self.convert_siblings(&children)
+    }
+
+    fn convert_siblings(&mut self, children: &[(&Element, String)]) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        for (i, (child, path)) in children.iter().enumerate() {
+            if child.name == "clip-path" {
+                // A clip path clips everything drawn after it in its group
+                let rest = self.convert_siblings(&children[i + 1..]);
+                match self.clip_path(child, path) {
+                    Some(id) => {
+                        let mut group = Element::new("g");
+                        group.set_attr("clip-path".to_string(), format!("url(#{})", id));
+                        group.children = rest;
+                        nodes.push(Node::Element(group));
+                    }
+                    None => nodes.extend(rest),
+                }
+                break;
+            }
+            nodes.extend(self.convert_element(child, path).map(Node::Element));
+        }
+        nodes
+    }
+
+    fn convert_element(&mut self, element: &Element, path: &str) -> Option<Element> {
+        match element.name.as_str() {
+            "group" => {
+                let mut group = Element::new("g");
+                if let Some(name) = self.attr(element, "name") {
+                    group.set_attr("id".to_string(), name.to_string());
+                }
+                if let Some(transform) = self.transform(element, path) {
+                    group.set_attr("transform".to_string(), transform);
+                }
+                group.children = self.convert_children(element, path);
+                Some(group)
+            }
+            "path" => self.convert_path(element, path),
+            name => {
+                self.unsupported.push(format!("<{}> at {}", name, path));
+                None
+            }
+        }
+    }
+
+    /// The transform of a group: scaled and rotated around the pivot, then
+    /// translated
+    fn transform(&mut self, group: &Element, path: &str) -> Option<String> {
+        let mut value =
+            |name: &str, default: f64| self.number(group, name, path).unwrap_or(default);
+        let (pivot_x, pivot_y) = (value("pivotX", 0.0), value("pivotY", 0.0));
+        let rotation = value("rotation", 0.0);
+        let (scale_x, scale_y) = (value("scaleX", 1.0), value("scaleY", 1.0));
+        let (translate_x, translate_y) = (value("translateX", 0.0), value("translateY", 0.0));
+
+        let linear = rotation != 0.0 || scale_x != 1.0 || scale_y != 1.0;
+        let pivot = linear && (pivot_x != 0.0 || pivot_y != 0.0);
+        let mut transforms = Vec::new();
+        let (x, y) = match pivot {
+            true => (translate_x + pivot_x, translate_y + pivot_y),
+            false => (translate_x, translate_y),
+        };
+        if x != 0.0 || y != 0.0 {
+            transforms.push(format!("translate({} {})", number(x), number(y)));
+        }
+        if rotation != 0.0 {
+            transforms.push(format!("rotate({})", number(rotation)));
+        }
+        if scale_x != 1.0 || scale_y != 1.0 {
+            transforms.push(match scale_x == scale_y {
+                true => format!("scale({})", number(scale_x)),
+                false => format!("scale({} {})", number(scale_x), number(scale_y)),
+            });
+        }
+        if pivot {
+            transforms.push(format!(
+                "translate({} {})",
+                number(-pivot_x),
+                number(-pivot_y)
+            ));
+        }
+        (!transforms.is_empty()).then(|| transforms.join(""))
+    }
+
+    fn convert_path(&mut self, element: &Element, path: &str) -> Option<Element> {
+        let d = self.path_data(element, path)?;
+        let mut svg_path = Element::new("path");
+        if let Some(name) = self.attr(element, "name") {
+            svg_path.set_attr("id".to_string(), name.to_string());
+        }
+        svg_path.set_attr("d".to_string(), d);
+
+        // VectorDrawable paths are neither filled nor stroked by default
+        let fill_alpha = self.number(element, "fillAlpha", path).unwrap_or(1.0);
+        let filled = self.set_paint(&mut svg_path, element, "fill", fill_alpha, path);
+        if filled && self.attr(element, "fillType") == Some("evenOdd") {
+            svg_path.set_attr("fill-rule".to_string(), "evenodd".to_string());
+        }
+
+        let stroke_width = self.number(element, "strokeWidth", path).unwrap_or(0.0);
+        let stroke_alpha = self.number(element, "strokeAlpha", path).unwrap_or(1.0);
+        let stroked = stroke_width > 0.0
+            && self.set_paint(&mut svg_path, element, "stroke", stroke_alpha, path);
+        if stroked {
+            svg_path.set_attr("stroke-width".to_string(), number(stroke_width));
+            if let Some(cap @ ("round" | "square")) = self.attr(element, "strokeLineCap") {
+                svg_path.set_attr("stroke-linecap".to_string(), cap.to_string());
+            }
+            if let Some(join @ ("round" | "bevel")) = self.attr(element, "strokeLineJoin") {
+                svg_path.set_attr("stroke-linejoin".to_string(), join.to_string());
+            }
+            match self.number(element, "strokeMiterLimit", path) {
+                Some(limit) if limit != 4.0 => {
+                    svg_path.set_attr("stroke-miterlimit".to_string(), number(limit))
+                }
+                _ => {}
+            }
+        }
+
+        // Trimming is drawn as a single dash, repeated so that it wraps
+        // around the end of the path like a trimmed path does
+        let start = self.number(element, "trimPathStart", path).unwrap_or(0.0);
+        let end = self.number(element, "trimPathEnd", path).unwrap_or(1.0);
+        let offset = self.number(element, "trimPathOffset", path).unwrap_or(0.0);
+        let length = end - start;
+        if start != 0.0 || end != 1.0 || offset != 0.0 {
+            if filled {
+                self.unsupported
+                    .push(format!("trimming a filled path at {}", path));
+            } else if length <= 0.0 {
+                return None;
+            } else if stroked && length < 1.0 {
+                svg_path.set_attr("pathLength".to_string(), "1".to_string());
+                svg_path.set_attr(
+                    "stroke-dasharray".to_string(),
+                    format!("{} {}", number(length), number(1.0 - length)),
+                );
+                svg_path.set_attr("stroke-dashoffset".to_string(), number(-(start + offset)));
+            }
+        }
+
+        if !filled {
+            svg_path.set_attr("fill".to_string(), "none".to_string());
+        }
+        Some(svg_path)
+    }
+
+    fn path_data(&mut self, element: &Element, path: &str) -> Option<String> {
+        let d = self.attr(element, "pathData")?.trim();
+        if d.starts_with(['@', '?']) {
+            self.unsupport("pathData", d, path);
+            return None;
+        }
+        (!d.is_empty()).then(|| d.to_string())
+    }
+
+    /// Set `fill` or `stroke` and its opacity from the color or gradient of
+    /// the path
+    ///
+    /// Returns whether the path is painted with it.
+    fn set_paint(
+        &mut self,
+        svg_path: &mut Element,
+        element: &Element,
+        property: &str,
+        alpha: f64,
+        path: &str,
+    ) -> bool {
+        let (value, opacity) = match self.paint(element, &format!("{}Color", property), path) {
+            Some(Paint::Color(_, color_alpha)) if color_alpha * alpha <= 0.0 => return false,
+            Some(Paint::Color(color, color_alpha)) => (color, color_alpha * alpha),
+            Some(Paint::Gradient(id)) => (format!("url(#{})", id), alpha),
+            None => return false,
+        };
+        svg_path.set_attr(property.to_string(), value);
+        if opacity < 1.0 {
+            svg_path.set_attr(format!("{}-opacity", property), number(opacity));
+        }
+        true
+    }
+
+    fn paint(&mut self, element: &Element, name: &str, path: &str) -> Option<Paint> {
+        let attribute = format!("{}{}", self.android, name);
+        let aapt_attr = format!("{}attr", self.aapt);
+        if let Some(inline) = element
+            .child_elements()
+            .find(|child| child.name == aapt_attr && child.attr("name") == Some(&attribute))
+        {
+            return self.gradient(inline, path).map(Paint::Gradient);
+        }
+
+        let value = self.attr(element, name)?;
+        match parse_color(value) {
+            Some((color, alpha)) => Some(Paint::Color(color, alpha)),
+            None => {
+                self.unsupport(name, value, path);
+                None
+            }
+        }
+    }
+
+    /// Add the gradient of an `<aapt:attr>` to the defs and return its ID
+    fn gradient(&mut self, inline: &Element, path: &str) -> Option<String> {
+        let Some(gradient) = inline
+            .child_elements()
+            .find(|child| child.name == "gradient")
+        else {
+            self.unsupported.push(format!(
+                "<{}attr> without a gradient at {}",
+                self.aapt, path
+            ));
+            return None;
+        };
+
+        let (name, coordinates) = match self.attr(gradient, "type").unwrap_or("linear") {
+            "linear" => (
+                "linearGradient",
+                [
+                    ("x1", "startX"),
+                    ("y1", "startY"),
+                    ("x2", "endX"),
+                    ("y2", "endY"),
+                ]
+                .as_slice(),
+            ),
+            "radial" => (
+                "radialGradient",
+                [
+                    ("cx", "centerX"),
+                    ("cy", "centerY"),
+                    ("r", "gradientRadius"),
+                ]
+                .as_slice(),
+            ),
+            kind => {
+                self.unsupported
+                    .push(format!("{} gradient at {}", kind, path));
+                return None;
+            }
+        };
+        let mut svg_gradient = Element::new(name);
+        for (svg_name, name) in coordinates {
+            let value = self.number(gradient, name, path).unwrap_or(0.0);
+            svg_gradient.set_attr(svg_name.to_string(), number(value));
+        }
+        svg_gradient.set_attr("gradientUnits".to_string(), "userSpaceOnUse".to_string());
+        match self.attr(gradient, "tileMode") {
+            Some("repeat") => {
+                svg_gradient.set_attr("spreadMethod".to_string(), "repeat".to_string())
+            }
+            Some("mirror") => {
+                svg_gradient.set_attr("spreadMethod".to_string(), "reflect".to_string())
+            }
+            _ => {}
+        }
+
+        // Items, or the start, center and end colors
+        let items: Vec<&Element> = gradient
+            .child_elements()
+            .filter(|child| child.name == "item")
+            .collect();
+        let stops: Vec<(f64, Option<&str>)> = match items.is_empty() {
+            false => items
+                .iter()
+                .map(|item| {
+                    let offset = self.number(item, "offset", path).unwrap_or(0.0);
+                    (offset, self.attr(item, "color"))
+                })
+                .collect(),
+            true => [(0.0, "startColor"), (0.5, "centerColor"), (1.0, "endColor")]
+                .into_iter()
+                .filter_map(|(offset, name)| {
+                    let color = self.attr(gradient, name);
+                    (name != "centerColor" || color.is_some()).then_some((offset, color))
+                })
+                .collect(),
+        };
+        for (offset, color) in stops {
+            let (color, alpha) = match color {
+                Some(value) => match parse_color(value) {
+                    Some(color) => color,
+                    None => {
+                        self.unsupport("color", value, path);
+                        continue;
+                    }
+                },
+                None => ("#000000".to_string(), 0.0),
+            };
+            let mut stop = Element::new("stop");
+            stop.set_attr("offset".to_string(), number(offset));
+            stop.set_attr("stop-color".to_string(), color);
+            if alpha < 1.0 {
+                stop.set_attr("stop-opacity".to_string(), number(alpha));
+            }
+            svg_gradient.children.push(Node::Element(stop));
+        }
+
+        let id = self.generate_id("gradient");
+        svg_gradient.set_attr("id".to_string(), id.clone());
+        self.defs.push(Node::Element(svg_gradient));
+        Some(id)
+    }
+
+    /// Add a `<clipPath>` for a `<clip-path>` to the defs and return its ID
+    fn clip_path(&mut self, clip: &Element, path: &str) -> Option<String> {
+        let d = self.path_data(clip, path)?;
+        let mut clip_path = Element::new("clipPath");
+        let id = match self.attr(clip, "name") {
+            Some(name) => name.to_string(),
+            None => self.generate_id("clip"),
+        };
+        clip_path.set_attr("id".to_string(), id.clone());
+        let mut svg_path = Element::new("path");
+        svg_path.set_attr("d".to_string(), d);
+        clip_path.children.push(Node::Element(svg_path));
+        self.defs.push(Node::Element(clip_path));
+        Some(id)
+    }
+}
+
+/// Parse an Android color, `#RGB`, `#ARGB`, `#RRGGBB` or `#AARRGGBB`, to an
+/// SVG color and its alpha
+fn parse_color(value: &str) -> Option<(String, f64)> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let (alpha, rgb) = match hex.len() {
+        3 => ("f".repeat(2), hex.chars().flat_map(|c| [c, c]).collect()),
+        4 => (
+            hex[..1].repeat(2),
+            hex[1..].chars().flat_map(|c| [c, c]).collect(),
+        ),
+        6 => ("ff".to_string(), hex.to_string()),
+        8 => (hex[..2].to_string(), hex[2..].to_string()),
+        _ => return None,
+    };
+    let alpha = u8::from_str_radix(&alpha, 16).ok()?;
+    // Alpha is stored in 8 bits, so three decimals keep it exact
+    let opacity = (f64::from(alpha) / 255.0 * 1000.0).round() / 1000.0;
+    Some((format!("#{}", rgb.to_ascii_lowercase()), opacity))
+}
+
+fn number(value: f64) -> String {
+    if value == 0.0 {
+        // Also formats -0 as 0
+        return "0".to_string();
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::stringifier::Stringifier;
+
+    fn convert_xml(xml: &str) -> ImportResult<String> {
+        let document = convert(&Parser::new().parse(xml).unwrap())?;
+        Ok(Stringifier::new().stringify(&document).unwrap())
+    }
+
+    #[test]
+    fn test_vector() {
+        let svg = convert_xml(
+            r##"<vector xmlns:android="http://schemas.android.com/apk/res/android" android:width="24dp" android:height="24dp" android:viewportWidth="48" android:viewportHeight="48" android:alpha="0.5">
+    <group android:name="arrow" android:pivotX="24" android:pivotY="24" android:rotation="90" android:translateX="2">
+        <path android:pathData="M0 0h10" android:strokeColor="#8000FF00" android:strokeWidth="2" android:strokeLineCap="round" android:strokeAlpha="0.5"/>
+    </group>
+    <group android:translateY="4"><path android:fillColor="#F00" android:fillType="evenOdd" android:pathData="M0 0h4v4z"/></group>
+</vector>"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 48 48" opacity="0.5"><g id="arrow" transform="translate(26 24)rotate(90)translate(-24 -24)"><path stroke="#00ff00" fill="none" d="M0 0h10" stroke-opacity="0.251" stroke-width="2" stroke-linecap="round"/></g><g transform="translate(0 4)"><path fill="#ff0000" d="M0 0h4v4z" fill-rule="evenodd"/></g></svg>"##
+        );
+    }
+
+    #[test]
+    fn test_gradient_and_clip_path() {
+        let svg = convert_xml(
+            r##"<vector xmlns:android="http://schemas.android.com/apk/res/android" xmlns:aapt="http://schemas.android.com/aapt" android:width="24dp" android:height="24dp" android:viewportWidth="24" android:viewportHeight="24">
+    <path android:pathData="M0 0h1"/>
+    <clip-path android:pathData="M0 0h12v24H0z"/>
+    <path android:pathData="M0 0h24v24H0z">
+        <aapt:attr name="android:fillColor">
+            <gradient android:type="radial" android:centerX="12" android:centerY="12" android:gradientRadius="12" android:tileMode="mirror">
+                <item android:offset="0" android:color="#FFFF0000"/>
+                <item android:offset="1" android:color="#000000FF"/>
+            </gradient>
+        </aapt:attr>
+    </path>
+</vector>"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24"><defs><radialGradient id="gradient1" cx="12" cy="12" r="12" gradientUnits="userSpaceOnUse" spreadMethod="reflect"><stop offset="0" stop-color="#ff0000"/><stop offset="1" stop-color="#0000ff" stop-opacity="0"/></radialGradient><clipPath id="clip1"><path d="M0 0h12v24H0z"/></clipPath></defs><path fill="none" d="M0 0h1"/><g clip-path="url(#clip1)"><path fill="url(#gradient1)" d="M0 0h24v24H0z"/></g></svg>"##
+        );
+    }
+
+    #[test]
+    fn test_trim_path() {
+        let svg = convert_xml(
+            r##"<vector xmlns:android="http://schemas.android.com/apk/res/android" android:viewportWidth="24" android:viewportHeight="24"><path android:pathData="M0 0h24" android:strokeColor="#000" android:strokeWidth="1" android:trimPathStart="0.25" android:trimPathEnd="0.75" android:trimPathOffset="0.5"/></vector>"##,
+        )
+        .unwrap();
+
+        assert!(
+            svg.contains(r#"pathLength="1" stroke-dasharray="0.5 0.5" stroke-dashoffset="-0.75""#)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = convert_xml(
+            r##"<vector xmlns:android="http://schemas.android.com/apk/res/android" android:viewportWidth="24" android:viewportHeight="24" android:tint="?attr/colorControlNormal"><path android:pathData="M0 0h1" android:fillColor="@color/accent"/><group><text/></group></vector>"##,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "VectorDrawable input has what SVG can't express: \
+             android:tint=\"?attr/colorControlNormal\" at /vector, \
+             android:fillColor=\"@color/accent\" at /vector/path[1], \
+             <text> at /vector/group[1]/text[1]"
+        );
+
+        let error = convert_xml("<svg/>").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "VectorDrawable input must have a <vector> root, not <svg>"
+        );
+    }
+
+    #[test]
+    fn test_optimize_keeps_fill_type() {
+        let mut config = crate::config::Config::with_default_preset();
+        config.path = Some("ic_frame.xml".to_string());
+        let result = crate::optimizer::optimize_with_config(
+            r##"<vector xmlns:android="http://schemas.android.com/apk/res/android" android:viewportWidth="24" android:viewportHeight="24"><path android:fillColor="#F00" android:fillType="evenOdd" android:pathData="M2 2h20v20H2zm4 4v12h12V6z"/></vector>"##,
+            config,
+        )
+        .unwrap();
+
+        assert!(result.data.contains(r#"fill-rule="evenodd""#));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#F00"), Some(("#ff0000".to_string(), 1.0)));
+        assert_eq!(parse_color("#8F00"), Some(("#ff0000".to_string(), 0.533)));
+        assert_eq!(
+            parse_color("#80FF0000"),
+            Some(("#ff0000".to_string(), 0.502))
+        );
+        assert_eq!(parse_color("@color/red"), None);
+        assert_eq!(parse_color("#GG0000"), None);
+    }
+}
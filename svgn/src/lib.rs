@@ -6,20 +6,34 @@
 //! with the original SVGO JavaScript library while offering significant
 //! performance improvements.
 
+pub mod a11y;
+pub mod analyze;
 pub mod ast;
+pub mod cache;
 pub mod collections;
 pub mod config;
+pub mod diff;
+pub mod encoding;
+pub mod entities;
+pub mod export;
+pub mod import;
+pub mod lint;
 pub mod optimizer;
 pub mod parser;
 pub mod plugin;
 pub mod plugins;
+pub mod report;
+pub mod streaming;
 pub mod stringifier;
+pub mod svgz;
+pub mod tuning;
 
 // Re-export main types
 pub use ast::{Document, Element, Node};
 pub use config::Config;
 pub use optimizer::{
-    optimize, optimize_default, optimize_with_config, OptimizationResult, OptimizeOptions,
+    optimize, optimize_bytes, optimize_bytes_with_registry, optimize_default, optimize_with_config,
+    optimize_with_registry, OptimizationResult, OptimizeOptions,
 };
 pub use plugin::{Plugin, PluginConfig, PluginRegistry};
 
@@ -0,0 +1,515 @@
+// this_file: svgn/src/lint.rs
+
+//! Asset linting
+//!
+//! A [`LintRule`] inspects a parsed document and reports [`Violation`]s,
+//! much like a [`Plugin`](crate::plugin::Plugin) rewrites one. Rules are
+//! kept in a [`RuleRegistry`]; [`create_default_rule_registry`] holds the
+//! built-in ones from [`rules`]. Each rule's severity, and its options, can
+//! be set in the `lint.rules` section of the configuration:
+//!
+//! ```toml
+//! [lint.rules]
+//! no-inline-styles = "error"
+//! no-hardcoded-colors = "warning"
+//! max-paths = { severity = "warning", max = 20 }
+//! ```
+//!
+//! [`output`] renders the results as text, JSON or SARIF.
+//!
+//! Rules whose violations a plugin can repair name it in
+//! [`LintRule::fix`]; [`fix`] runs just those plugins, for the rules that
+//! were violated, and keeps the formatting of everything else.
+
+pub mod output;
+pub mod rules;
+
+use crate::ast::Document;
+use crate::config::{Config, Js2SvgOptions, SizeMetric};
+use crate::export::OutputFormat;
+use crate::import::InputFormat;
+use crate::optimizer::{decode_input, optimize_bytes, OptimizeError, OptimizeOptions};
+use crate::parser::{ParseError, Parser};
+use crate::plugin::PluginConfig;
+use crate::svgz::{self, SvgzOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use thiserror::Error;
+
+/// Lint error types
+#[derive(Error, Debug)]
+pub enum LintError {
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("Unknown lint rule: {0}")]
+    UnknownRule(String),
+    #[error("Invalid options for lint rule {rule}: {message}")]
+    InvalidOptions { rule: String, message: String },
+    #[error("{0}")]
+    OptimizeError(#[from] OptimizeError),
+}
+
+/// Lint result type
+pub type LintResult<T> = Result<T, LintError>;
+
+/// How seriously a rule's violations are taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The rule doesn't run
+    Off,
+    /// Reported, for information only
+    Info,
+    /// Reported, but doesn't fail the lint
+    #[serde(alias = "warn")]
+    Warning,
+    /// Reported and fails the lint
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// Configuration of a single rule: a severity, or a table with an optional
+/// `severity` and rule-specific options
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleConfig {
+    /// Just the severity, e.g. `"error"`
+    Severity(Severity),
+    /// Severity and options, e.g. `{ "severity": "warning", "max": 20 }`
+    Options {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        severity: Option<Severity>,
+        #[serde(flatten)]
+        options: Map<String, Value>,
+    },
+}
+
+impl RuleConfig {
+    /// The configured severity, if any
+    pub fn severity(&self) -> Option<Severity> {
+        match self {
+            RuleConfig::Severity(severity) => Some(*severity),
+            RuleConfig::Options { severity, .. } => *severity,
+        }
+    }
+
+    /// Rule options, if any were given
+    pub fn options(&self) -> Option<Value> {
+        match self {
+            RuleConfig::Options { options, .. } if !options.is_empty() => {
+                Some(Value::Object(options.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The `lint` section of the configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Rule ID to configuration; rules not listed use their default severity
+    #[serde(default)]
+    pub rules: BTreeMap<String, RuleConfig>,
+}
+
+impl LintConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// A problem found by a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// What is wrong
+    pub message: String,
+    /// Path of the offending element, e.g. `/svg/g[1]/image[2]`
+    pub path: Option<String>,
+}
+
+impl Violation {
+    pub fn new(message: impl Into<String>, path: Option<String>) -> Self {
+        Self {
+            message: message.into(),
+            path,
+        }
+    }
+}
+
+/// A violation, with the rule and severity it was reported under
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Rule ID
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Path of the offending element
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}] {}", self.severity, self.rule, self.message)?;
+        if let Some(path) = &self.path {
+            write!(f, " at {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lint rule trait that all rules must implement
+pub trait LintRule: Send + Sync {
+    /// Rule ID, in kebab-case
+    fn id(&self) -> &'static str;
+
+    /// What the rule checks
+    fn description(&self) -> &'static str;
+
+    /// Severity used when the configuration doesn't set one
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Find violations in the document
+    fn check(&self, document: &Document, options: Option<&Value>) -> LintResult<Vec<Violation>>;
+
+    /// The plugin that fixes this rule's violations, if there is one
+    ///
+    /// `options` are the rule's options, for plugins that take the same.
+    fn fix(&self, _options: Option<&Value>) -> Option<PluginConfig> {
+        None
+    }
+}
+
+/// Registry of lint rules
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn LintRule>>,
+    index: HashMap<&'static str, usize>,
+}
+
+impl RuleRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Register a rule, replacing any rule with the same ID
+    pub fn register<R: LintRule + 'static>(&mut self, rule: R) {
+        match self.index.get(rule.id()) {
+            Some(&i) => self.rules[i] = Box::new(rule),
+            None => {
+                self.index.insert(rule.id(), self.rules.len());
+                self.rules.push(Box::new(rule));
+            }
+        }
+    }
+
+    /// Get a rule by ID
+    pub fn get(&self, id: &str) -> Option<&dyn LintRule> {
+        self.index.get(id).map(|&i| self.rules[i].as_ref())
+    }
+
+    /// All rules, in registration order
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    /// The severity `config` gives to a rule
+    pub fn severity(&self, rule: &dyn LintRule, config: &LintConfig) -> Severity {
+        config
+            .rules
+            .get(rule.id())
+            .and_then(RuleConfig::severity)
+            .unwrap_or_else(|| rule.default_severity())
+    }
+
+    /// Run every rule that isn't turned off on the document
+    pub fn check(&self, document: &Document, config: &LintConfig) -> LintResult<Vec<Diagnostic>> {
+        if let Some(unknown) = config.rules.keys().find(|id| self.get(id).is_none()) {
+            return Err(LintError::UnknownRule(unknown.clone()));
+        }
+
+        let mut diagnostics = Vec::new();
+        for rule in self.rules() {
+            let severity = self.severity(rule, config);
+            if severity == Severity::Off {
+                continue;
+            }
+            let options = config.rules.get(rule.id()).and_then(RuleConfig::options);
+            diagnostics.extend(rule.check(document, options.as_ref())?.into_iter().map(
+                |violation| Diagnostic {
+                    rule: rule.id().to_string(),
+                    severity,
+                    message: violation.message,
+                    path: violation.path,
+                },
+            ));
+        }
+        Ok(diagnostics)
+    }
+
+    /// Plugins that fix `diagnostics`, in rule order and without duplicates
+    pub fn fix_plugins(
+        &self,
+        diagnostics: &[Diagnostic],
+        config: &LintConfig,
+    ) -> Vec<PluginConfig> {
+        let mut plugins: Vec<PluginConfig> = Vec::new();
+        for rule in self.rules() {
+            if !diagnostics.iter().any(|d| d.rule == rule.id()) {
+                continue;
+            }
+            let options = config.rules.get(rule.id()).and_then(RuleConfig::options);
+            if let Some(plugin) = rule.fix(options.as_ref()) {
+                if !plugins.iter().any(|p| p.name == plugin.name) {
+                    plugins.push(plugin);
+                }
+            }
+        }
+        plugins
+    }
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create a registry with all built-in rules
+pub fn create_default_rule_registry() -> RuleRegistry {
+    let mut registry = RuleRegistry::new();
+    registry.register(rules::RequireViewBox);
+    registry.register(rules::NoRasterImages);
+    registry.register(rules::NoInlineStyles);
+    registry.register(rules::NoHardcodedColors);
+    registry.register(rules::MaxPaths);
+    registry.register(rules::NoRootTransform);
+    registry.register(rules::NoEditorNamespaces);
+    registry.register(rules::AccessibleName);
+    registry.register(rules::AriaReferences);
+    registry
+}
+
+/// Lint an SVG string with the built-in rules
+pub fn lint(input: &str, config: &Config) -> LintResult<Vec<Diagnostic>> {
+    lint_with_registry(input, config, &create_default_rule_registry())
+}
+
+/// Lint an SVG string with the rules in `registry`
+pub fn lint_with_registry(
+    input: &str,
+    config: &Config,
+    registry: &RuleRegistry,
+) -> LintResult<Vec<Diagnostic>> {
+    let document = Parser::new()
+        .preserve_whitespace(config.parser.preserve_whitespace)
+        .preserve_comments(config.parser.preserve_comments)
+        .expand_entities(config.parser.expand_entities)
+        .entity_limits(config.parser.entity_limits.clone())
+        .parse(input)?;
+    registry.check(&document, &config.lint)
+}
+
+/// Result of [`fix`]
+#[derive(Debug, Clone)]
+pub struct LintFix {
+    /// The fixed file, gzip-compressed if the input was
+    pub data: Vec<u8>,
+    /// Names of the plugins that were applied
+    pub plugins: Vec<String>,
+    /// Violations left after fixing
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Fix violations of the built-in rules
+pub fn fix(input: &[u8], config: &Config) -> LintResult<LintFix> {
+    fix_with_registry(input, config, &create_default_rule_registry())
+}
+
+/// Fix violations of the rules in `registry`
+///
+/// Only the plugins named by the violated rules run, once each, with the
+/// formatting of unmodified elements preserved, so that the file changes as
+/// little as possible. Input is decoded like [`optimize_bytes`] does.
+pub fn fix_with_registry(
+    input: &[u8],
+    config: &Config,
+    registry: &RuleRegistry,
+) -> LintResult<LintFix> {
+    let diagnostics = lint_with_registry(&decode_input(input)?, config, registry)?;
+    let plugins = registry.fix_plugins(&diagnostics, &config.lint);
+    if plugins.is_empty() {
+        return Ok(LintFix {
+            data: input.to_vec(),
+            plugins: Vec::new(),
+            diagnostics,
+        });
+    }
+
+    let fix_config = Config {
+        plugins,
+        multipass: false,
+        a11y: false,
+        js2svg: Js2SvgOptions {
+            preserve_formatting: true,
+            ..config.js2svg.clone()
+        },
+        // Fixes rewrite the SVG in place, whatever the project converts from
+        // or exports to
+        input_format: Some(InputFormat::Svg),
+        format: OutputFormat::Svg,
+        datauri: None,
+        svgz: svgz::is_gzip(input).then(SvgzOptions::default),
+        size_metric: SizeMetric::Raw,
+        ..config.clone()
+    };
+    let plugins = fix_config.plugins.iter().map(|p| p.name.clone()).collect();
+    let result = optimize_bytes(input, OptimizeOptions::new(fix_config))?;
+    let diagnostics = lint_with_registry(&result.data, config, registry)?;
+
+    Ok(LintFix {
+        data: result
+            .compressed
+            .unwrap_or_else(|| result.data.into_bytes()),
+        plugins,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rule_config() {
+        let config: LintConfig = toml::from_str(
+            r#"
+            [rules]
+            no-inline-styles = "error"
+            no-raster-images = "off"
+            max-paths = { severity = "warn", max = 2 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.rules["no-inline-styles"],
+            RuleConfig::Severity(Severity::Error)
+        );
+        assert_eq!(
+            config.rules["max-paths"].severity(),
+            Some(Severity::Warning)
+        );
+        assert_eq!(config.rules["max-paths"].options(), Some(json!({"max": 2})));
+        assert_eq!(config.rules["no-raster-images"].options(), None);
+    }
+
+    #[test]
+    fn test_severities() {
+        let svg = r#"<svg width="10" height="10" style="fill:red"><path/><path/><path/></svg>"#;
+        let mut config = Config::default();
+        let diagnostics = lint(svg, &config).unwrap();
+        let rules: Vec<_> = diagnostics.iter().map(|d| d.rule.as_str()).collect();
+        assert_eq!(rules, ["require-viewbox", "no-inline-styles"]);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error [require-viewbox] missing viewBox; width and height give viewBox=\"0 0 10 10\" at /svg"
+        );
+
+        config.lint = serde_json::from_value(json!({"rules": {
+            "require-viewbox": "off",
+            "no-inline-styles": "error",
+            "max-paths": {"severity": "info", "max": 2},
+        }}))
+        .unwrap();
+        let diagnostics = lint(svg, &config).unwrap();
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.severity))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("no-inline-styles", Severity::Error),
+                ("max-paths", Severity::Info)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_errors() {
+        let mut config = Config::default();
+        config.lint.rules.insert(
+            "no-such-rule".to_string(),
+            RuleConfig::Severity(Severity::Error),
+        );
+        assert!(matches!(
+            lint("<svg/>", &config),
+            Err(LintError::UnknownRule(rule)) if rule == "no-such-rule"
+        ));
+
+        config.lint = serde_json::from_value(json!({"rules": {
+            "max-paths": {"severity": "error", "max": "many"},
+        }}))
+        .unwrap();
+        assert!(matches!(
+            lint("<svg/>", &config),
+            Err(LintError::InvalidOptions { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fix() {
+        let input = concat!(
+            "<!-- icon -->\n",
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" width="24" height="24" inkscape:version="1" transform="scale(2)">"#,
+            "\n  <path   d=\"M0 0h1\"   fill=\"red\"/>",
+            "\n  <g>\n    <path style=\"fill: blue\" d=\"M1 1h1\"/>\n  </g>\n</svg>\n"
+        );
+        let fixed = fix(input.as_bytes(), &Config::default()).unwrap();
+
+        assert_eq!(
+            fixed.plugins,
+            [
+                "removeDimensions",
+                "convertStyleToAttrs",
+                "removeEditorsNSData"
+            ]
+        );
+        assert_eq!(
+            String::from_utf8(fixed.data).unwrap(),
+            concat!(
+                "<!-- icon -->\n",
+                r#"<svg xmlns="http://www.w3.org/2000/svg" transform="scale(2)" viewBox="0 0 24 24">"#,
+                "\n  <path   d=\"M0 0h1\"   fill=\"red\"/>",
+                "\n  <g>\n    <path d=\"M1 1h1\" fill=\"blue\"/>\n  </g>\n</svg>\n"
+            )
+        );
+        let left: Vec<_> = fixed.diagnostics.iter().map(|d| d.rule.as_str()).collect();
+        assert_eq!(left, ["no-root-transform"]);
+
+        let clean = r#"<svg viewBox="0 0 1 1"/>"#;
+        let fixed = fix(clean.as_bytes(), &Config::default()).unwrap();
+        assert!(fixed.plugins.is_empty());
+        assert_eq!(fixed.data, clean.as_bytes());
+    }
+}
@@ -0,0 +1,295 @@
+// this_file: svgn/src/lint/output.rs
+
+//! Lint output
+//!
+//! A [`LintReport`] collects the diagnostics for each linted file, and what
+//! `--fix` changed, and renders them as text for terminals, JSON, or SARIF
+//! 2.1.0 for code scanning tools.
+
+use super::{Diagnostic, RuleRegistry, Severity};
+use crate::config::Config;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// Supported output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintFormat {
+    /// One line per diagnostic, followed by a summary
+    Text,
+    /// A single JSON document with a summary and per-file entries
+    Json,
+    /// SARIF 2.1.0
+    Sarif,
+}
+
+/// Lint results for a single file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileLint {
+    /// Input path
+    pub path: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Plugins applied by `--fix`, if the file was changed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixed: Vec<String>,
+    /// Error message if the file could not be read or parsed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FileLint {
+    /// Results for a file that was linted
+    pub fn new(path: &str, diagnostics: Vec<Diagnostic>) -> Self {
+        Self {
+            path: path.to_string(),
+            diagnostics,
+            fixed: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Entry for a file that could not be linted
+    pub fn failed(path: &str, error: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            diagnostics: Vec::new(),
+            fixed: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// Diagnostic counts over all files in a report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LintSummary {
+    pub files: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    pub fixed: usize,
+    pub failed: usize,
+}
+
+/// A complete lint run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LintReport {
+    pub summary: LintSummary,
+    pub files: Vec<FileLint>,
+}
+
+impl LintReport {
+    /// Build a report and its summary from per-file results
+    pub fn new(files: Vec<FileLint>) -> Self {
+        let mut summary = LintSummary {
+            files: files.len(),
+            ..Default::default()
+        };
+        for file in &files {
+            if file.error.is_some() {
+                summary.failed += 1;
+            }
+            if !file.fixed.is_empty() {
+                summary.fixed += 1;
+            }
+            for diagnostic in &file.diagnostics {
+                match diagnostic.severity {
+                    Severity::Error => summary.errors += 1,
+                    Severity::Warning => summary.warnings += 1,
+                    Severity::Info => summary.infos += 1,
+                    Severity::Off => {}
+                }
+            }
+        }
+        Self { summary, files }
+    }
+
+    /// Whether the lint failed: an error-level diagnostic or an unreadable file
+    pub fn has_errors(&self) -> bool {
+        self.summary.errors > 0 || self.summary.failed > 0
+    }
+
+    /// Render the report in the given format
+    ///
+    /// SARIF output lists the rules of `registry` with their severities
+    /// under `config`.
+    pub fn render(
+        &self,
+        format: LintFormat,
+        registry: &RuleRegistry,
+        config: &Config,
+    ) -> Result<String, serde_json::Error> {
+        match format {
+            LintFormat::Text => Ok(self.to_text()),
+            LintFormat::Json => serde_json::to_string_pretty(self),
+            LintFormat::Sarif => serde_json::to_string_pretty(&self.to_sarif(registry, config)),
+        }
+    }
+
+    /// Plain text, one `path: severity [rule] message` line per diagnostic
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            if let Some(error) = &file.error {
+                let _ = writeln!(out, "{}: {}", file.path, error);
+            }
+            if !file.fixed.is_empty() {
+                let _ = writeln!(out, "{}: fixed with {}", file.path, file.fixed.join(", "));
+            }
+            for diagnostic in &file.diagnostics {
+                let _ = writeln!(out, "{}: {}", file.path, diagnostic);
+            }
+        }
+        let summary = &self.summary;
+        let _ = write!(
+            out,
+            "Checked: {} files, {} errors, {} warnings",
+            summary.files, summary.errors, summary.warnings
+        );
+        if summary.fixed > 0 {
+            let _ = write!(out, ", {} fixed", summary.fixed);
+        }
+        if summary.failed > 0 {
+            let _ = write!(out, ", {} failed", summary.failed);
+        }
+        out.push('\n');
+        out
+    }
+
+    /// SARIF 2.1.0 log with a single run
+    pub fn to_sarif(&self, registry: &RuleRegistry, config: &Config) -> Value {
+        let rules: Vec<Value> = registry
+            .rules()
+            .map(|rule| {
+                json!({
+                    "id": rule.id(),
+                    "shortDescription": {"text": rule.description()},
+                    "defaultConfiguration": {
+                        "level": sarif_level(registry.severity(rule, &config.lint)),
+                    },
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        let mut notifications = Vec::new();
+        for file in &self.files {
+            let artifact = json!({"uri": file.path.replace('\\', "/")});
+            if let Some(error) = &file.error {
+                notifications.push(json!({
+                    "level": "error",
+                    "message": {"text": error},
+                    "locations": [{"physicalLocation": {"artifactLocation": artifact}}],
+                }));
+            }
+            for diagnostic in &file.diagnostics {
+                let mut location = json!({"physicalLocation": {"artifactLocation": artifact}});
+                if let Some(path) = &diagnostic.path {
+                    location["logicalLocations"] =
+                        json!([{"fullyQualifiedName": path, "kind": "element"}]);
+                }
+                results.push(json!({
+                    "ruleId": diagnostic.rule,
+                    "level": sarif_level(diagnostic.severity),
+                    "message": {"text": diagnostic.message},
+                    "locations": [location],
+                }));
+            }
+        }
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {"driver": {
+                    "name": "svgn",
+                    "version": crate::VERSION,
+                    "informationUri": "https://github.com/twardoch/svgn",
+                    "rules": rules,
+                }},
+                "invocations": [{
+                    "executionSuccessful": notifications.is_empty(),
+                    "toolExecutionNotifications": notifications,
+                }],
+                "results": results,
+            }],
+        })
+    }
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Off => "none",
+        Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::{create_default_rule_registry, lint};
+
+    fn sample_report() -> LintReport {
+        let config = Config::default();
+        LintReport::new(vec![
+            FileLint::new(
+                "icons/a.svg",
+                lint(r#"<svg><path style="fill:red"/></svg>"#, &config).unwrap(),
+            ),
+            FileLint {
+                fixed: vec!["removeEditorsNSData".to_string()],
+                ..FileLint::new("icons/b.svg", Vec::new())
+            },
+            FileLint::failed("icons/c.svg", "Parse error: unexpected end"),
+        ])
+    }
+
+    #[test]
+    fn test_summary() {
+        let report = sample_report();
+        assert_eq!(report.summary.files, 3);
+        assert_eq!(report.summary.errors, 1);
+        assert_eq!(report.summary.warnings, 1);
+        assert_eq!(report.summary.fixed, 1);
+        assert_eq!(report.summary.failed, 1);
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            sample_report().to_text(),
+            "icons/a.svg: error [require-viewbox] missing viewBox at /svg\n\
+             icons/a.svg: warning [no-inline-styles] inline style=\"fill:red\" at /svg/path[1]\n\
+             icons/b.svg: fixed with removeEditorsNSData\n\
+             icons/c.svg: Parse error: unexpected end\n\
+             Checked: 3 files, 1 errors, 1 warnings, 1 fixed, 1 failed\n"
+        );
+    }
+
+    #[test]
+    fn test_sarif() {
+        let sarif = sample_report().to_sarif(&create_default_rule_registry(), &Config::default());
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "require-viewbox");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["defaultConfiguration"]["level"],
+            "error"
+        );
+        assert_eq!(run["results"].as_array().unwrap().len(), 2);
+        assert_eq!(run["results"][1]["ruleId"], "no-inline-styles");
+        assert_eq!(run["results"][1]["level"], "warning");
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "icons/a.svg"
+        );
+        assert_eq!(
+            run["results"][1]["locations"][0]["logicalLocations"][0]["fullyQualifiedName"],
+            "/svg/path[1]"
+        );
+        assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+    }
+}
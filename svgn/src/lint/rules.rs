@@ -0,0 +1,550 @@
+// this_file: svgn/src/lint/rules.rs
+
+//! Built-in lint rules
+//!
+//! Where a plugin already detects the content a rule is about, the rule
+//! reuses its logic, so `svgn lint` flags exactly what the plugin would
+//! remove or rewrite.
+
+use super::{LintError, LintResult, LintRule, Severity, Violation};
+use crate::a11y::{self, A11yIssue};
+use crate::ast::{ns, Document, Element, NamespaceScope};
+use crate::collections::EDITOR_NAMESPACES;
+use crate::plugin::PluginConfig;
+use crate::plugins::remove_dimensions::viewbox_from_dimensions;
+use crate::plugins::remove_raster_images::is_raster_image;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Presentation attributes and properties that take a color
+const COLOR_PROPERTIES: &[&str] = &[
+    "color",
+    "fill",
+    "flood-color",
+    "lighting-color",
+    "stop-color",
+    "stroke",
+];
+
+/// Color values that don't fix a color
+const NEUTRAL_COLORS: &[&str] = &[
+    "none",
+    "currentcolor",
+    "inherit",
+    "initial",
+    "unset",
+    "transparent",
+    "context-fill",
+    "context-stroke",
+];
+
+/// Call `f` with every element below and including `root`, the namespaces in
+/// scope inside it and its path
+fn visit(root: &Element, f: &mut dyn FnMut(&Element, &NamespaceScope, &str)) {
+    fn walk(
+        element: &Element,
+        scope: &NamespaceScope,
+        path: &str,
+        f: &mut dyn FnMut(&Element, &NamespaceScope, &str),
+    ) {
+        let scope = scope.enter(element);
+        f(element, &scope, path);
+
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for child in element.child_elements() {
+            let n = seen.entry(child.name.as_str()).or_default();
+            *n += 1;
+            walk(child, &scope, &format!("{}/{}[{}]", path, child.name, n), f);
+        }
+    }
+
+    walk(root, &NamespaceScope::new(), &format!("/{}", root.name), f);
+}
+
+fn root_path(document: &Document) -> Option<String> {
+    Some(format!("/{}", document.root.name))
+}
+
+fn invalid_options(rule: &dyn LintRule, message: impl Into<String>) -> LintError {
+    LintError::InvalidOptions {
+        rule: rule.id().to_string(),
+        message: message.into(),
+    }
+}
+
+/// Values of a string array option
+fn string_list(rule: &dyn LintRule, options: Option<&Value>, key: &str) -> LintResult<Vec<String>> {
+    match options.and_then(|options| options.get(key)) {
+        None => Ok(Vec::new()),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| invalid_options(rule, format!("{} must list strings", key)))
+            })
+            .collect(),
+        Some(_) => Err(invalid_options(rule, format!("{} must be an array", key))),
+    }
+}
+
+/// The root `<svg>` must have a `viewBox`
+pub struct RequireViewBox;
+
+impl LintRule for RequireViewBox {
+    fn id(&self) -> &'static str {
+        "require-viewbox"
+    }
+
+    fn description(&self) -> &'static str {
+        "requires a viewBox on the root <svg> element"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, document: &Document, _options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        let root = &document.root;
+        if root.has_attr("viewBox") {
+            return Ok(Vec::new());
+        }
+
+        let message = match viewbox_from_dimensions(root) {
+            Some(viewbox) => format!(
+                "missing viewBox; width and height give viewBox=\"{}\"",
+                viewbox
+            ),
+            None => "missing viewBox".to_string(),
+        };
+        Ok(vec![Violation::new(message, root_path(document))])
+    }
+
+    fn fix(&self, _options: Option<&Value>) -> Option<PluginConfig> {
+        Some(PluginConfig::new("removeDimensions".to_string()))
+    }
+}
+
+/// No `<image>` referencing JPEG, PNG or GIF data
+pub struct NoRasterImages;
+
+impl LintRule for NoRasterImages {
+    fn id(&self) -> &'static str {
+        "no-raster-images"
+    }
+
+    fn description(&self) -> &'static str {
+        "disallows <image> elements referencing raster images"
+    }
+
+    fn check(&self, document: &Document, _options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        let mut violations = Vec::new();
+        visit(&document.root, &mut |element, scope, path| {
+            if !is_raster_image(element, scope) {
+                return;
+            }
+            let href = element
+                .attr_ns(scope, ns::XLINK, "href")
+                .map(|(_, value)| value)
+                .or_else(|| element.attr("href"))
+                .map(String::as_str)
+                .unwrap_or_default();
+            let message = if href.starts_with("data:") {
+                "embedded raster image".to_string()
+            } else {
+                format!("raster image \"{}\"", href)
+            };
+            violations.push(Violation::new(message, Some(path.to_string())));
+        });
+        Ok(violations)
+    }
+
+    fn fix(&self, _options: Option<&Value>) -> Option<PluginConfig> {
+        Some(PluginConfig::new("removeRasterImages".to_string()))
+    }
+}
+
+/// No `style` attributes
+pub struct NoInlineStyles;
+
+impl LintRule for NoInlineStyles {
+    fn id(&self) -> &'static str {
+        "no-inline-styles"
+    }
+
+    fn description(&self) -> &'static str {
+        "disallows style attributes"
+    }
+
+    fn check(&self, document: &Document, _options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        let mut violations = Vec::new();
+        visit(&document.root, &mut |element, _, path| {
+            if let Some(style) = element.attr("style") {
+                violations.push(Violation::new(
+                    format!("inline style=\"{}\"", style.trim()),
+                    Some(path.to_string()),
+                ));
+            }
+        });
+        Ok(violations)
+    }
+
+    fn fix(&self, _options: Option<&Value>) -> Option<PluginConfig> {
+        Some(PluginConfig::new("convertStyleToAttrs".to_string()))
+    }
+}
+
+/// Colors must come from `currentColor` (or a paint server), so that icons
+/// can be recolored
+///
+/// Options: `allow`, a list of color values that are accepted anyway.
+pub struct NoHardcodedColors;
+
+impl LintRule for NoHardcodedColors {
+    fn id(&self) -> &'static str {
+        "no-hardcoded-colors"
+    }
+
+    fn description(&self) -> &'static str {
+        "disallows colors other than currentColor, for icon sets"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Off
+    }
+
+    fn check(&self, document: &Document, options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        let allowed: HashSet<String> = string_list(self, options, "allow")?
+            .iter()
+            .map(|value| value.trim().to_ascii_lowercase())
+            .collect();
+        let is_hardcoded = |value: &str| {
+            let value = value.trim().to_ascii_lowercase();
+            !value.is_empty()
+                && !NEUTRAL_COLORS.contains(&value.as_str())
+                && !value.starts_with("url(")
+                && !value.starts_with("var(")
+                && !allowed.contains(&value)
+        };
+
+        let mut violations = Vec::new();
+        visit(&document.root, &mut |element, _, path| {
+            let style = element.attr("style").map(String::as_str).unwrap_or("");
+            let declarations = style
+                .split(';')
+                .filter_map(|declaration| declaration.split_once(':'))
+                .map(|(property, value)| (property.trim(), value));
+            for (property, value) in element
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .chain(declarations)
+            {
+                if COLOR_PROPERTIES.contains(&property) && is_hardcoded(value) {
+                    violations.push(Violation::new(
+                        format!("hard-coded color {}=\"{}\"", property, value.trim()),
+                        Some(path.to_string()),
+                    ));
+                }
+            }
+        });
+        Ok(violations)
+    }
+}
+
+/// Limit on the number of `<path>` elements
+///
+/// Options: `max`, the number of paths allowed (default 100).
+pub struct MaxPaths;
+
+impl MaxPaths {
+    const DEFAULT_MAX: u64 = 100;
+}
+
+impl LintRule for MaxPaths {
+    fn id(&self) -> &'static str {
+        "max-paths"
+    }
+
+    fn description(&self) -> &'static str {
+        "limits the number of <path> elements"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Off
+    }
+
+    fn check(&self, document: &Document, options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        let max = match options.and_then(|options| options.get("max")) {
+            None => Self::DEFAULT_MAX,
+            Some(value) => value
+                .as_u64()
+                .ok_or_else(|| invalid_options(self, "max must be a non-negative integer"))?,
+        };
+
+        let mut count = 0;
+        visit(&document.root, &mut |element, _, _| {
+            if element.name == "path" {
+                count += 1;
+            }
+        });
+
+        if count <= max {
+            return Ok(Vec::new());
+        }
+        Ok(vec![Violation::new(
+            format!("{} paths, more than the maximum of {}", count, max),
+            root_path(document),
+        )])
+    }
+}
+
+/// No `transform` on the root `<svg>`
+pub struct NoRootTransform;
+
+impl LintRule for NoRootTransform {
+    fn id(&self) -> &'static str {
+        "no-root-transform"
+    }
+
+    fn description(&self) -> &'static str {
+        "disallows a transform on the root <svg> element"
+    }
+
+    fn check(&self, document: &Document, _options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        Ok(document
+            .root
+            .attr("transform")
+            .map(|transform| {
+                Violation::new(
+                    format!("transform=\"{}\" on the root element", transform),
+                    root_path(document),
+                )
+            })
+            .into_iter()
+            .collect())
+    }
+}
+
+/// No namespaces, elements or attributes of editors such as Inkscape
+///
+/// Options: `additionalNamespaces`, like `removeEditorsNSData`.
+pub struct NoEditorNamespaces;
+
+impl LintRule for NoEditorNamespaces {
+    fn id(&self) -> &'static str {
+        "no-editor-namespaces"
+    }
+
+    fn description(&self) -> &'static str {
+        "disallows editor namespaces, elements and attributes"
+    }
+
+    fn check(&self, document: &Document, options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        let additional = string_list(self, options, "additionalNamespaces")?;
+        let namespaces: HashSet<&str> = EDITOR_NAMESPACES
+            .iter()
+            .copied()
+            .chain(additional.iter().map(String::as_str))
+            .collect();
+        let is_editor =
+            |uri: Option<String>| uri.is_some_and(|uri| namespaces.contains(uri.as_str()));
+
+        let mut violations = Vec::new();
+        let mut editor_paths: Vec<String> = Vec::new();
+        visit(&document.root, &mut |element, scope, path| {
+            // Report editor elements once, not their content
+            if editor_paths.iter().any(|p| path.starts_with(p.as_str())) {
+                return;
+            }
+            if is_editor(element.qname(scope).uri) {
+                violations.push(Violation::new(
+                    format!("editor element <{}>", element.name),
+                    Some(path.to_string()),
+                ));
+                editor_paths.push(format!("{}/", path));
+                return;
+            }
+
+            // Namespace declarations and attributes in editor namespaces
+            let names: Vec<&str> = element
+                .attributes
+                .iter()
+                .filter(|(name, value)| {
+                    (name.starts_with("xmlns:") && namespaces.contains(value.as_str()))
+                        || is_editor(scope.attribute_name(name).uri)
+                })
+                .map(|(name, _)| name.as_str())
+                .collect();
+            if !names.is_empty() {
+                violations.push(Violation::new(
+                    format!("editor data {}", names.join(", ")),
+                    Some(path.to_string()),
+                ));
+            }
+        });
+        Ok(violations)
+    }
+
+    fn fix(&self, options: Option<&Value>) -> Option<PluginConfig> {
+        Some(match options {
+            Some(options) => {
+                PluginConfig::with_params("removeEditorsNSData".to_string(), options.clone())
+            }
+            None => PluginConfig::new("removeEditorsNSData".to_string()),
+        })
+    }
+}
+
+/// The root must have an accessible name or be hidden (see [`crate::a11y`])
+pub struct AccessibleName;
+
+impl LintRule for AccessibleName {
+    fn id(&self) -> &'static str {
+        "accessible-name"
+    }
+
+    fn description(&self) -> &'static str {
+        "requires an accessible name or aria-hidden=\"true\""
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Off
+    }
+
+    fn check(&self, document: &Document, _options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        Ok(a11y::check(document)
+            .into_iter()
+            .filter(|issue| *issue == A11yIssue::MissingName)
+            .map(|issue| Violation::new(issue.to_string(), root_path(document)))
+            .collect())
+    }
+
+    fn fix(&self, _options: Option<&Value>) -> Option<PluginConfig> {
+        Some(PluginConfig::new("addAccessibilityAttrs".to_string()))
+    }
+}
+
+/// `aria-labelledby` and `aria-describedby` must reference existing IDs
+pub struct AriaReferences;
+
+impl LintRule for AriaReferences {
+    fn id(&self) -> &'static str {
+        "aria-references"
+    }
+
+    fn description(&self) -> &'static str {
+        "requires aria-labelledby and aria-describedby to reference existing elements"
+    }
+
+    fn check(&self, document: &Document, _options: Option<&Value>) -> LintResult<Vec<Violation>> {
+        Ok(a11y::check(document)
+            .into_iter()
+            .filter(|issue| matches!(issue, A11yIssue::MissingReference { .. }))
+            .map(|issue| Violation::new(issue.to_string(), None))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use serde_json::json;
+
+    fn check(rule: &dyn LintRule, svg: &str, options: Option<Value>) -> Vec<String> {
+        let document = Parser::new().parse(svg).unwrap();
+        rule.check(&document, options.as_ref())
+            .unwrap()
+            .into_iter()
+            .map(|v| match v.path {
+                Some(path) => format!("{}: {}", path, v.message),
+                None => v.message,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_require_viewbox() {
+        assert!(check(&RequireViewBox, r#"<svg viewBox="0 0 1 1"/>"#, None).is_empty());
+        assert_eq!(
+            check(&RequireViewBox, r#"<svg width="100%"/>"#, None),
+            ["/svg: missing viewBox"]
+        );
+    }
+
+    #[test]
+    fn test_no_raster_images() {
+        let svg = r#"<svg xmlns:x="http://www.w3.org/1999/xlink"><g><image x:href="a.png"/><image href="b.svg"/></g><image href="data:image/jpeg;base64,AA"/></svg>"#;
+        assert_eq!(
+            check(&NoRasterImages, svg, None),
+            [
+                "/svg/g[1]/image[1]: raster image \"a.png\"",
+                "/svg/image[1]: embedded raster image"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_hardcoded_colors() {
+        let svg = r##"<svg fill="currentColor"><path fill="#000" style="stroke: red; opacity: .5"/><path stroke="none" fill="url(#g)"/><path fill="WHITE"/></svg>"##;
+        assert_eq!(
+            check(&NoHardcodedColors, svg, None),
+            [
+                "/svg/path[1]: hard-coded color fill=\"#000\"",
+                "/svg/path[1]: hard-coded color stroke=\"red\"",
+                "/svg/path[3]: hard-coded color fill=\"WHITE\""
+            ]
+        );
+        assert_eq!(
+            check(
+                &NoHardcodedColors,
+                svg,
+                Some(json!({"allow": ["white", "red"]}))
+            ),
+            ["/svg/path[1]: hard-coded color fill=\"#000\""]
+        );
+    }
+
+    #[test]
+    fn test_max_paths() {
+        let svg = "<svg><path/><g><path/></g></svg>";
+        assert!(check(&MaxPaths, svg, None).is_empty());
+        assert_eq!(
+            check(&MaxPaths, svg, Some(json!({"max": 1}))),
+            ["/svg: 2 paths, more than the maximum of 1"]
+        );
+        let document = Parser::new().parse(svg).unwrap();
+        assert!(MaxPaths
+            .check(&document, Some(&json!({"max": -1})))
+            .is_err());
+    }
+
+    #[test]
+    fn test_no_editor_namespaces() {
+        let svg = r#"<svg xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" xmlns:s="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd" inkscape:version="1.0"><s:namedview><inkscape:grid/></s:namedview><path s:nodetypes="cc"/></svg>"#;
+        assert_eq!(
+            check(&NoEditorNamespaces, svg, None),
+            [
+                "/svg: editor data xmlns:inkscape, xmlns:s, inkscape:version",
+                "/svg/s:namedview[1]: editor element <s:namedview>",
+                "/svg/path[1]: editor data s:nodetypes"
+            ]
+        );
+        assert!(check(&NoEditorNamespaces, "<svg><path/></svg>", None).is_empty());
+    }
+
+    #[test]
+    fn test_root_and_style_rules() {
+        let svg = r#"<svg transform="scale(2)"><path style="fill: red"/></svg>"#;
+        assert_eq!(
+            check(&NoRootTransform, svg, None),
+            ["/svg: transform=\"scale(2)\" on the root element"]
+        );
+        assert_eq!(
+            check(&NoInlineStyles, svg, None),
+            ["/svg/path[1]: inline style=\"fill: red\""]
+        );
+    }
+}
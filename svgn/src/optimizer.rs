@@ -6,8 +6,11 @@
 //! parsing, plugin application, and output generation.
 
 use crate::config::Config;
+use crate::encoding::{DecodedInput, EncodingError};
+use crate::export::OutputFormat;
 use crate::parser::{ParseError, Parser};
-use crate::plugin::{PluginError, PluginRegistry};
+use crate::plugin::{PluginError, PluginObserver, PluginRegistry};
+use crate::plugins::sanitize::SanitizeReport;
 use crate::stringifier::{Stringifier, StringifyError};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -21,8 +24,16 @@ pub enum OptimizeError {
     PluginError(#[from] PluginError),
     #[error("Stringify error: {0}")]
     StringifyError(#[from] StringifyError),
+    #[error("Export error: {0}")]
+    ExportError(#[from] crate::export::ExportError),
+    #[error("Import error: {0}")]
+    ImportError(#[from] crate::import::ImportError),
     #[error("Configuration error: {0}")]
     ConfigError(String),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Encoding error: {0}")]
+    EncodingError(#[from] EncodingError),
 }
 
 /// Optimization result type
@@ -47,6 +58,12 @@ pub struct OptimizationResult {
     pub error: Option<String>,
     /// Whether modern parser was used
     pub modern: bool,
+    /// Gzip-compressed `data`, when SVGZ output is enabled
+    #[serde(skip)]
+    pub compressed: Option<Vec<u8>>,
+    /// What the `sanitize` plugin removed, if it ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sanitize_report: Option<SanitizeReport>,
 }
 
 /// Information about the optimization process
@@ -62,6 +79,16 @@ pub struct OptimizationInfo {
     pub plugins_applied: usize,
     /// Number of optimization passes
     pub passes: usize,
+    /// Size of the gzip-compressed input in bytes (SVGZ input only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_compressed_size: Option<usize>,
+    /// Size of the gzip-compressed output in bytes (SVGZ output or gzip
+    /// size metric only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<usize>,
+    /// Size of the brotli-compressed output in bytes (brotli size metric only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brotli_size: Option<usize>,
 }
 
 impl OptimizeOptions {
@@ -106,6 +133,9 @@ impl OptimizationInfo {
             compression_ratio,
             plugins_applied,
             passes,
+            original_compressed_size: None,
+            compressed_size: None,
+            brotli_size: None,
         }
     }
 
@@ -126,28 +156,101 @@ impl OptimizationInfo {
 /// `optimize` function. It takes an SVG string and configuration, then returns
 /// the optimized result.
 pub fn optimize(input: &str, options: OptimizeOptions) -> OptimizeResult<OptimizationResult> {
+    let mut registry = options
+        .registry
+        .unwrap_or_else(crate::plugin::create_default_registry);
+
+    optimize_with_registry(input, &options.config, &mut registry)
+}
+
+/// Optimize with a caller-owned plugin registry
+///
+/// Unlike [`optimize`], the registry is borrowed rather than consumed, so a
+/// single registry can be reused for many documents (e.g. one per worker
+/// thread when processing a folder).
+pub fn optimize_with_registry(
+    input: &str,
+    config: &Config,
+    registry: &mut PluginRegistry,
+) -> OptimizeResult<OptimizationResult> {
+    optimize_source(input, None, config, registry)
+}
+
+/// Optimize document text, recording the encoding it was transcoded from
+///
+/// With a compressed [`SizeMetric`](crate::config::SizeMetric), the document is
+/// optimized several times; see [`crate::tuning`].
+fn optimize_source(
+    input: &str,
+    source_encoding: Option<&str>,
+    config: &Config,
+    registry: &mut PluginRegistry,
+) -> OptimizeResult<OptimizationResult> {
+    if config.size_metric.is_raw() {
+        return optimize_once(input, source_encoding, config, registry, None);
+    }
+
+    crate::tuning::tune(input, config, |candidate| {
+        optimize_once(input, source_encoding, candidate, registry, None)
+    })
+}
+
+/// Optimize like [`optimize_with_registry`], reporting every plugin that is
+/// applied to `observer`
+///
+/// With a compressed [`SizeMetric`](crate::config::SizeMetric), only the run
+/// with the configuration that tuning picks is observed.
+pub fn optimize_observed(
+    input: &str,
+    config: &Config,
+    registry: &mut PluginRegistry,
+    observer: &mut dyn PluginObserver,
+) -> OptimizeResult<OptimizationResult> {
+    let tuned;
+    let config = if config.size_metric.is_raw() {
+        config
+    } else {
+        tuned = crate::tuning::tune_with_config(input, config, |candidate| {
+            optimize_once(input, None, candidate, registry, None)
+        })?
+        .1;
+        &tuned
+    };
+    optimize_once(input, None, config, registry, Some(observer))
+}
+
+/// A single optimization run with exactly the given configuration
+fn optimize_once(
+    input: &str,
+    source_encoding: Option<&str>,
+    config: &Config,
+    registry: &mut PluginRegistry,
+    mut observer: Option<&mut dyn PluginObserver>,
+) -> OptimizeResult<OptimizationResult> {
     let original_size = input.len();
-    let config = options.config;
 
     // Set up parser
     let parser = Parser::new()
         .preserve_whitespace(config.parser.preserve_whitespace)
-        .preserve_comments(config.parser.preserve_comments);
-
-    // Parse the SVG
-    let mut document = parser.parse(input)?;
+        .preserve_comments(config.parser.preserve_comments)
+        .expand_entities(config.parser.expand_entities)
+        .entity_limits(config.parser.entity_limits.clone())
+        .preserve_formatting(config.js2svg.preserve_formatting);
+
+    // Parse the SVG, or convert the input to SVG
+    let mut document =
+        crate::import::import(parser.parse(input)?, config.effective_input_format())?;
+    if let Some(encoding) = source_encoding {
+        document.metadata.encoding = Some(encoding.to_string());
+    }
 
     // Set document path from config
     if let Some(path) = &config.path {
         document.metadata.path = Some(path.clone());
     }
 
-    // Get or create plugin registry
-    let mut registry = options
-        .registry
-        .unwrap_or_else(crate::plugin::create_default_registry);
-
     // Apply optimization passes
+    let plugins = config.effective_plugins();
     let mut passes = 0;
     let mut plugins_applied = 0;
     let mut previous_output = String::new();
@@ -161,24 +264,30 @@ pub fn optimize(input: &str, options: OptimizeOptions) -> OptimizeResult<Optimiz
             path: document.metadata.path.clone(),
             multipass_count: passes - 1,
         };
-        registry.apply_plugins(&mut document, &config.plugins, &plugin_info)?;
+        registry.apply_plugins_observed(
+            &mut document,
+            &plugins,
+            &plugin_info,
+            observer.as_mut().map(|observer| &mut **observer as _),
+        )?;
 
         // For now, assume all enabled plugins were applied
         // In a real implementation, we'd track this more precisely
-        plugins_applied += config.plugins.iter().filter(|p| p.enabled).count();
+        plugins_applied += plugins.iter().filter(|p| p.enabled).count();
 
         // Generate output to check for changes
-        let stringifier = Stringifier::new()
-            .pretty(config.js2svg.pretty)
-            .indent(config.js2svg.indent)
-            .self_closing(config.js2svg.self_closing)
-            .eol(config.js2svg.eol)
-            .final_newline(config.js2svg.final_newline);
+        let stringifier = Stringifier::from_options(&config.js2svg);
 
         let current_output = stringifier.stringify(&document)?;
 
         // Check if we should continue with multi-pass optimization
         if !config.multipass || current_output == previous_output || passes >= 10 {
+            // Components wrap the final markup
+            let current_output = match config.format {
+                OutputFormat::Svg => current_output,
+                _ => crate::export::export(&document, config)?,
+            };
+
             // Apply data URI encoding if requested
             let final_output = match &config.datauri {
                 Some(format) => apply_datauri_encoding(&current_output, format),
@@ -186,14 +295,22 @@ pub fn optimize(input: &str, options: OptimizeOptions) -> OptimizeResult<Optimiz
             };
 
             let optimized_size = final_output.len();
-            let info =
+            let mut info =
                 OptimizationInfo::new(original_size, optimized_size, plugins_applied, passes);
 
+            let compressed = match &config.svgz {
+                Some(svgz) => Some(crate::svgz::compress(final_output.as_bytes(), svgz.level)?),
+                None => None,
+            };
+            info.compressed_size = compressed.as_ref().map(Vec::len);
+
             return Ok(OptimizationResult {
                 data: final_output,
                 info,
                 error: None,
                 modern: true, // We always use the modern parser
+                compressed,
+                sanitize_report: document.metadata.sanitize_report.take(),
             });
         }
 
@@ -201,6 +318,57 @@ pub fn optimize(input: &str, options: OptimizeOptions) -> OptimizeResult<Optimiz
     }
 }
 
+/// Optimize raw input bytes
+///
+/// Gzip-compressed (SVGZ) input is detected by its magic bytes and inflated
+/// before parsing. The compressed input size is recorded in
+/// [`OptimizationInfo::original_compressed_size`].
+///
+/// Input in UTF-16 or a legacy encoding declared in the XML declaration is
+/// transcoded to UTF-8 and the original encoding is recorded in
+/// [`DocumentMetadata::encoding`](crate::ast::DocumentMetadata::encoding).
+/// The output is always UTF-8.
+pub fn optimize_bytes(
+    input: &[u8],
+    options: OptimizeOptions,
+) -> OptimizeResult<OptimizationResult> {
+    let mut registry = options
+        .registry
+        .unwrap_or_else(crate::plugin::create_default_registry);
+
+    optimize_bytes_with_registry(input, &options.config, &mut registry)
+}
+
+/// Optimize raw input bytes with a caller-owned plugin registry
+pub fn optimize_bytes_with_registry(
+    input: &[u8],
+    config: &Config,
+    registry: &mut PluginRegistry,
+) -> OptimizeResult<OptimizationResult> {
+    let decoded = decode_bytes(input)?;
+    let source_encoding = (!decoded.is_utf8()).then_some(decoded.encoding.as_str());
+    let mut result = optimize_source(&decoded.text, source_encoding, config, registry)?;
+    if crate::svgz::is_gzip(input) {
+        result.info.original_compressed_size = Some(input.len());
+    }
+    Ok(result)
+}
+
+/// Decode raw input bytes into UTF-8 SVG source text
+///
+/// Gzip-compressed input is inflated first.
+pub fn decode_input(input: &[u8]) -> OptimizeResult<String> {
+    Ok(decode_bytes(input)?.text)
+}
+
+fn decode_bytes(input: &[u8]) -> OptimizeResult<DecodedInput> {
+    if crate::svgz::is_gzip(input) {
+        Ok(crate::encoding::decode(&crate::svgz::decompress(input)?)?)
+    } else {
+        Ok(crate::encoding::decode(input)?)
+    }
+}
+
 /// Apply data URI encoding to the SVG output
 fn apply_datauri_encoding(svg: &str, format: &crate::config::DataUriFormat) -> String {
     use crate::config::DataUriFormat;
@@ -229,7 +397,8 @@ fn base64_encode(input: &str) -> String {
 }
 
 fn url_encode(input: &str) -> String {
-    urlencoding::encode(input).into_owned()
+    urlencoding::encode(input)
+        .into_owned()
         .replace(' ', "%20")
         .replace('<', "%3C")
         .replace('>', "%3E")
@@ -287,6 +456,24 @@ mod tests {
         assert!(!result.data.is_empty());
     }
 
+    #[test]
+    fn test_a11y_mode() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" aria-labelledby="chartTitle"><title id="chartTitle">Chart</title><desc>Bars</desc><rect width="1" height="1" role="presentation"/></svg>"#;
+
+        let result = optimize_default(svg).unwrap();
+        assert!(!result.data.contains("<title"));
+
+        let mut config = Config::with_default_preset();
+        config.a11y = true;
+        let result = optimize_with_config(svg, config).unwrap();
+        assert!(result.data.contains(r#"aria-labelledby="a""#));
+        assert!(result
+            .data
+            .contains(r#"<title id="a">Chart</title><desc>Bars</desc>"#));
+        assert!(result.data.contains(r#"role="img""#));
+        assert!(result.data.contains(r#"role="presentation""#));
+    }
+
     #[test]
     fn test_optimization_info() {
         let info = OptimizationInfo::new(1000, 800, 5, 2);
@@ -299,6 +486,51 @@ mod tests {
         assert_eq!(info.passes, 2);
     }
 
+    #[test]
+    fn test_optimize_bytes_svgz() {
+        let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\"><!-- c --><rect/></svg>";
+        let input = crate::svgz::compress(svg.as_bytes(), 9).unwrap();
+        let mut config = Config::with_default_preset();
+        config.svgz = Some(crate::svgz::SvgzOptions::default());
+
+        let result = optimize_bytes(&input, OptimizeOptions::new(config)).unwrap();
+        assert!(!result.data.contains("<!--"));
+        assert_eq!(result.info.original_size, svg.len());
+        assert_eq!(result.info.original_compressed_size, Some(input.len()));
+
+        let compressed = result.compressed.unwrap();
+        assert_eq!(result.info.compressed_size, Some(compressed.len()));
+        assert_eq!(
+            crate::svgz::decompress(&compressed).unwrap(),
+            result.data.as_bytes()
+        );
+
+        let plain = optimize_bytes(svg.as_bytes(), OptimizeOptions::default()).unwrap();
+        assert_eq!(plain.data, result.data);
+        assert!(plain.compressed.is_none());
+        assert_eq!(plain.info.original_compressed_size, None);
+    }
+
+    #[test]
+    fn test_optimize_bytes_transcodes_to_utf8() {
+        let svg = "<?xml version=\"1.0\" encoding=\"UTF-16\"?><svg><text>é</text></svg>";
+        let input: Vec<u8> = [0xff, 0xfe]
+            .into_iter()
+            .chain(svg.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+
+        let result = optimize_bytes(&input, OptimizeOptions::new(Config::new())).unwrap();
+        assert_eq!(
+            result.data,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg><text>é</text></svg>"
+        );
+
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><svg><text>\xe9</text></svg>";
+        let result = optimize_bytes(latin1, OptimizeOptions::new(Config::new())).unwrap();
+        assert!(result.data.contains("encoding=\"UTF-8\""));
+        assert!(result.data.contains("<text>é</text>"));
+    }
+
     #[test]
     fn test_datauri_encoding() {
         use crate::config::DataUriFormat;
@@ -310,7 +542,10 @@ mod tests {
         let base64_result = apply_datauri_encoding(svg, &DataUriFormat::Base64);
         assert!(base64_result.starts_with("data:image/svg+xml;base64,"));
         let expected_base64 = STANDARD.encode(svg.as_bytes());
-        assert_eq!(base64_result, format!("data:image/svg+xml;base64,{}", expected_base64));
+        assert_eq!(
+            base64_result,
+            format!("data:image/svg+xml;base64,{}", expected_base64)
+        );
 
         // Test URL encoding
         let enc_result = apply_datauri_encoding(svg, &DataUriFormat::Enc);
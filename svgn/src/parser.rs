@@ -5,10 +5,11 @@
 //! This module provides functionality to parse SVG strings into our custom AST
 //! using the quick-xml crate for fast streaming XML parsing.
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{AttributeFormat, Document, Element, ElementFormat, Node};
+use crate::entities::{EntityError, EntityExpander, EntityLimits, Replacement};
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::fmt;
 use std::sync::LazyLock;
 use thiserror::Error;
@@ -30,6 +31,9 @@ pub enum ParseError {
     
     #[error("Unexpected end of document")]
     UnexpectedEnd,
+
+    #[error("Entity error: {0}")]
+    EntityError(#[from] EntityError),
     
     #[error("{0}")]
     DetailedError(DetailedParseError),
@@ -98,7 +102,7 @@ impl fmt::Display for DetailedParseError {
 pub type ParseResult<T> = Result<T, ParseError>;
 
 /// Elements where whitespace should be preserved
-static TEXT_ELEMENTS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+pub(crate) static TEXT_ELEMENTS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     HashSet::from([
         // Text content elements
         "text", "tspan", "textPath", "altGlyph", "tref", 
@@ -108,6 +112,17 @@ static TEXT_ELEMENTS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     ])
 });
 
+/// Text content collected across `Text` and `GeneralRef` events
+#[derive(Default)]
+struct PendingText {
+    /// Text with references expanded
+    text: String,
+    /// Source text of the events, for preserve-formatting mode
+    source: String,
+    /// Whether a declared entity was expanded, so the source can't be reused
+    expanded_entity: bool,
+}
+
 /// SVG parser
 pub struct Parser {
     /// Whether to preserve whitespace
@@ -116,8 +131,12 @@ pub struct Parser {
     preserve_comments: bool,
     /// Whether to expand XML entities
     expand_entities: bool,
+    /// Limits on entity expansion
+    entity_limits: EntityLimits,
     /// File path (for error reporting)
     file_path: Option<String>,
+    /// Whether to record source formatting for lossless output
+    preserve_formatting: bool,
 }
 
 impl Parser {
@@ -127,7 +146,9 @@ impl Parser {
             preserve_whitespace: false,
             preserve_comments: false,
             expand_entities: true,
+            entity_limits: EntityLimits::default(),
             file_path: None,
+            preserve_formatting: false,
         }
     }
 
@@ -144,11 +165,30 @@ impl Parser {
     }
 
     /// Set whether to expand XML entities
+    ///
+    /// When disabled, references to entities declared in the DOCTYPE are
+    /// kept as written and listed in `DocumentMetadata::unexpanded_entities`.
     pub fn expand_entities(mut self, expand: bool) -> Self {
         self.expand_entities = expand;
         self
     }
+
+    /// Set the limits on entity expansion
+    pub fn entity_limits(mut self, limits: EntityLimits) -> Self {
+        self.entity_limits = limits;
+        self
+    }
     
+    /// Set whether to record source formatting
+    ///
+    /// Every element keeps its source start and end tags, attribute spacing
+    /// and quoting, and whitespace-only text is kept everywhere, so that the
+    /// stringifier can reproduce unmodified parts of the document exactly.
+    pub fn preserve_formatting(mut self, preserve: bool) -> Self {
+        self.preserve_formatting = preserve;
+        self
+    }
+
     /// Set the file path for error reporting
     pub fn file_path(mut self, path: Option<String>) -> Self {
         self.file_path = path;
@@ -157,23 +197,57 @@ impl Parser {
 
     /// Parse an SVG string into a Document
     pub fn parse(&self, input: &str) -> ParseResult<Document> {
+        let mut expander = EntityExpander::new(self.entity_limits.clone(), self.expand_entities);
+        let mut document = self.parse_with_entities(input, &mut expander)?;
+        document.metadata.unexpanded_entities = expander.into_unexpanded();
+        Ok(document)
+    }
+
+    /// Parse `input`, expanding references with `expander`
+    fn parse_with_entities(
+        &self,
+        input: &str,
+        expander: &mut EntityExpander,
+    ) -> ParseResult<Document> {
         let mut reader = Reader::from_str(input);
         reader.config_mut().expand_empty_elements = true;
-        reader.config_mut().trim_text_start = self.preserve_whitespace;
-        reader.config_mut().trim_text_end = self.preserve_whitespace;
+        reader.config_mut().trim_text_start = self.preserve_whitespace && !self.preserve_formatting;
+        reader.config_mut().trim_text_end = self.preserve_whitespace && !self.preserve_formatting;
 
         let mut document = Document::new();
         let mut element_stack = Vec::new();
         let mut current_element: Option<Element> = None;
         let mut buf = Vec::new();
         let mut found_root = false;
-        let mut entities: HashMap<String, String> = HashMap::new();
         let mut element_name_stack: Vec<String> = Vec::new();
+        let mut pending_text = PendingText::default();
 
         loop {
-            match reader.read_event_into(&mut buf) {
+            let event_start = reader.buffer_position() as usize;
+            let event = reader.read_event_into(&mut buf);
+            // Source text of the event, for preserve-formatting mode
+            let source = input
+                .get(event_start..reader.buffer_position() as usize)
+                .unwrap_or_default();
+
+            // Text is split around references; join it back before anything else
+            if !matches!(event, Ok(Event::Text(_)) | Ok(Event::GeneralRef(_))) {
+                self.flush_text(
+                    &mut pending_text,
+                    &mut current_element,
+                    &mut document,
+                    found_root,
+                    &element_name_stack,
+                );
+            }
+
+            match event {
                 Ok(Event::Start(ref e)) => {
-                    let element = self.parse_start_element(e, &entities, input)?;
+                    let mut element = self.parse_start_element(e, expander)?;
+                    // Source with expanded references can't be written back
+                    if self.preserve_formatting && !expander.expands_any(source) {
+                        element.format = record_element_format(&element, source).map(Box::new);
+                    }
                     
                     // Track element name for whitespace preservation
                     element_name_stack.push(element.name.clone());
@@ -193,7 +267,13 @@ impl Parser {
                     // Pop element name from stack
                     element_name_stack.pop();
                     
-                    if let Some(finished_element) = current_element.take() {
+                    if let Some(mut finished_element) = current_element.take() {
+                        if let Some(format) = finished_element.format.as_deref_mut() {
+                            // Self-closing tags are expanded into an empty end event
+                            if !source.is_empty() {
+                                format.end_tag = Some(source.to_string());
+                            }
+                        }
                         if let Some(mut parent) = element_stack.pop() {
                             parent.add_child(Node::Element(finished_element));
                             current_element = Some(parent);
@@ -205,7 +285,10 @@ impl Parser {
                     }
                 }
                 Ok(Event::Empty(ref e)) => {
-                    let element = self.parse_start_element(e, &entities, input)?;
+                    let mut element = self.parse_start_element(e, expander)?;
+                    if self.preserve_formatting && !expander.expands_any(source) {
+                        element.format = record_element_format(&element, source).map(Box::new);
+                    }
                     
                     // Empty elements don't need name tracking since they have no content
 
@@ -218,28 +301,38 @@ impl Parser {
                     }
                 }
                 Ok(Event::Text(ref e)) => {
-                    // Convert bytes to string and unescape
-                    let text_str = std::str::from_utf8(e.as_ref())?;
-                    let text = quick_xml::escape::unescape(text_str)
-                        .unwrap_or(std::borrow::Cow::Borrowed(text_str));
-                    let mut text_content = text.into_owned();
-                    
-                    // Expand custom entities if enabled
-                    if self.expand_entities && !entities.is_empty() {
-                        text_content = self.expand_entities_in_text(&text_content, &entities);
-                    }
-
-                    // Check if we should preserve whitespace for this element
-                    let should_preserve_whitespace = self.preserve_whitespace || 
-                        element_name_stack.last()
-                            .map(|name| TEXT_ELEMENTS.contains(name.as_str()))
-                            .unwrap_or(false);
-
-                    if should_preserve_whitespace || !text_content.trim().is_empty() {
-                        if let Some(ref mut element) = current_element {
-                            element.add_child(Node::Text(text_content));
+                    // References come as separate events, so the text is plain
+                    pending_text.text.push_str(std::str::from_utf8(e.as_ref())?);
+                    pending_text.source.push_str(source);
+                }
+                Ok(Event::GeneralRef(ref e)) => {
+                    let name = std::str::from_utf8(e.as_ref())?;
+                    match expander.resolve(name)? {
+                        Replacement::Text(text) => {
+                            pending_text.text.push_str(&text);
+                            pending_text.expanded_entity |= expander.is_expanded(name);
+                        }
+                        Replacement::Unexpanded => {
+                            pending_text.text.push_str(source);
+                        }
+                        Replacement::Markup(markup) => {
+                            // Elements declared in an entity become content
+                            self.flush_text(
+                                &mut pending_text,
+                                &mut current_element,
+                                &mut document,
+                                found_root,
+                                &element_name_stack,
+                            );
+                            let nodes = self.parse_fragment(&markup, expander);
+                            expander.leave();
+                            if let Some(ref mut element) = current_element {
+                                element.children.extend(nodes?);
+                            }
+                            continue;
                         }
                     }
+                    pending_text.source.push_str(source);
                 }
                 Ok(Event::Comment(ref e)) => {
                     if self.preserve_comments {
@@ -287,26 +380,24 @@ impl Parser {
                         document.metadata.encoding =
                             Some(String::from_utf8_lossy(&enc).to_string());
                     }
+                    if self.preserve_formatting {
+                        document.metadata.declaration = Some(source.to_string());
+                    }
                 }
                 Ok(Event::DocType(ref e)) => {
                     // For DOCTYPE, just convert bytes to string without unescaping
                     let doctype = std::str::from_utf8(e.as_ref())?.to_string();
-                    
-                    // Parse entity declarations from DOCTYPE if entity expansion is enabled
-                    if self.expand_entities {
-                        self.parse_entities_from_doctype(&doctype, &mut entities);
-                    }
-                    
+
+                    // Entities declared in the internal subset
+                    expander.declare(&doctype);
+
+
                     if !found_root {
                         // DOCTYPE should come before the root element
                         document.prologue.push(Node::DocType(doctype));
                     }
                 }
                 Ok(Event::Eof) => break,
-                Ok(Event::GeneralRef(_)) => {
-                    // Handle general references (new in quick-xml v0.38.0)
-                    // For SVG parsing, we typically ignore these
-                }
                 Err(e) => {
                     // Try to get position information for better error reporting
                     let byte_pos = reader.buffer_position().try_into().unwrap();
@@ -331,7 +422,11 @@ impl Parser {
     }
 
     /// Parse a start element into an Element
-    fn parse_start_element(&self, start: &BytesStart, entities: &HashMap<String, String>, _input: &str) -> ParseResult<Element> {
+    fn parse_start_element(
+        &self,
+        start: &BytesStart,
+        expander: &mut EntityExpander,
+    ) -> ParseResult<Element> {
         let name = std::str::from_utf8(start.name().as_ref())?.to_string();
         let mut element = Element::new(&name);
 
@@ -345,65 +440,70 @@ impl Parser {
                 }
             };
             let key = std::str::from_utf8(attr.key.as_ref())?.to_string();
-            let mut value = attr.unescape_value()?.to_string();
-            
-            // Expand custom entities in attribute values if enabled
-            if self.expand_entities && !entities.is_empty() {
-                value = self.expand_entities_in_text(&value, entities);
-            }
-
-            // Handle namespace declarations
-            if key.starts_with("xmlns") {
-                if key == "xmlns" {
-                    element.namespaces.insert("".to_string(), value.clone());
-                } else if let Some(ns_name) = key.strip_prefix("xmlns:") {
-                    element
-                        .namespaces
-                        .insert(ns_name.to_string(), value.clone());
-                }
-            }
+            let value = expander.expand(std::str::from_utf8(&attr.value)?)?;
 
+            // Namespace declarations stay ordinary attributes; see
+            // `Element::namespace_declarations` and `NamespaceScope`
             element.set_attr(key, value);
         }
 
         Ok(element)
     }
     
-    /// Parse entity declarations from DOCTYPE
-    fn parse_entities_from_doctype(&self, doctype: &str, entities: &mut HashMap<String, String>) {
-        // Simple regex-based parsing for entity declarations
-        // This handles patterns like: <!ENTITY name "value">
-        let entity_pattern = regex::Regex::new(r#"<!ENTITY\s+(\w+)\s+"([^"]+)"\s*>"#).unwrap();
-        
-        for capture in entity_pattern.captures_iter(doctype) {
-            if let (Some(name), Some(value)) = (capture.get(1), capture.get(2)) {
-                entities.insert(name.as_str().to_string(), value.as_str().to_string());
-            }
+    /// Add pending text to the tree as a single text node
+    fn flush_text(
+        &self,
+        pending: &mut PendingText,
+        current_element: &mut Option<Element>,
+        document: &mut Document,
+        found_root: bool,
+        element_name_stack: &[String],
+    ) {
+        if pending.text.is_empty() {
+            return;
         }
-        
-        // Also handle single-quoted entities: <!ENTITY name 'value'>
-        let entity_pattern_single = regex::Regex::new(r#"<!ENTITY\s+(\w+)\s+'([^']+)'\s*>"#).unwrap();
-        
-        for capture in entity_pattern_single.captures_iter(doctype) {
-            if let (Some(name), Some(value)) = (capture.get(1), capture.get(2)) {
-                entities.insert(name.as_str().to_string(), value.as_str().to_string());
+        let PendingText {
+            text,
+            source,
+            expanded_entity,
+        } = std::mem::take(pending);
+
+        // Check if we should preserve whitespace for this element
+        let should_preserve_whitespace = self.preserve_whitespace
+            || self.preserve_formatting
+            || element_name_stack
+                .last()
+                .is_some_and(|name| TEXT_ELEMENTS.contains(name.as_str()));
+
+        if should_preserve_whitespace || !text.trim().is_empty() {
+            if let Some(element) = current_element {
+                if let Some(format) = element.format.as_deref_mut() {
+                    if source != text && !expanded_entity {
+                        format.texts.push((text.clone(), source));
+                    }
+                }
+                element.add_child(Node::Text(text));
+            } else if self.preserve_formatting {
+                // Line breaks between the declaration, DOCTYPE and root
+                if found_root {
+                    document.epilogue.push(Node::Text(text));
+                } else {
+                    document.prologue.push(Node::Text(text));
+                }
             }
         }
     }
-    
-    /// Expand entity references in text
-    fn expand_entities_in_text(&self, text: &str, entities: &HashMap<String, String>) -> String {
-        let mut result = text.to_string();
-        
-        // Replace custom entity references (&entity;)
-        for (name, value) in entities {
-            let entity_ref = format!("&{};", name);
-            result = result.replace(&entity_ref, value);
-        }
-        
-        result
+
+    /// Parse the replacement text of an entity as element content
+    pub(crate) fn parse_fragment(
+        &self,
+        markup: &str,
+        expander: &mut EntityExpander,
+    ) -> ParseResult<Vec<Node>> {
+        let wrapped = format!("<svgn-entity>{markup}</svgn-entity>");
+        Ok(self.parse_with_entities(&wrapped, expander)?.root.children)
     }
-    
+
     /// Calculate line and column from byte position
     fn calculate_line_and_column(&self, input: &str, byte_pos: usize) -> (usize, usize) {
         let mut line = 1;
@@ -477,6 +577,49 @@ pub fn parse_svg(input: &str) -> ParseResult<Document> {
     Parser::new().parse(input)
 }
 
+/// Record how a start tag was written in the source
+///
+/// Returns `None` when the tag can't be matched up with the parsed
+/// attributes, in which case the element is serialized normally.
+fn record_element_format(element: &Element, tag: &str) -> Option<ElementFormat> {
+    let body = tag.strip_prefix('<')?;
+    let body = body.strip_suffix("/>").or_else(|| body.strip_suffix('>'))?;
+    let mut rest = body.strip_prefix(element.name.as_str())?;
+
+    let mut attributes = Vec::with_capacity(element.attributes.len());
+    for (name, value) in &element.attributes {
+        let source_start = rest.len() - rest.trim_start().len();
+        let (leading, after) = rest.split_at(source_start);
+        let after_name = after.strip_prefix(name.as_str())?;
+        let after_eq = after_name.trim_start().strip_prefix('=')?.trim_start();
+        let quote = after_eq.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value_end = after_eq[1..].find(quote)? + 2;
+        let source_len = after.len() - after_eq.len() + value_end;
+        let (source, remaining) = after.split_at(source_len);
+
+        attributes.push(AttributeFormat {
+            name: name.clone(),
+            value: value.clone(),
+            leading: leading.to_string(),
+            source: source.to_string(),
+        });
+        rest = remaining;
+    }
+
+    if !rest.trim().is_empty() {
+        return None;
+    }
+
+    Some(ElementFormat {
+        name: element.name.clone(),
+        attributes,
+        trailing: rest.to_string(),
+        start_tag: tag.to_string(),
+        end_tag: None,
+        texts: Vec::new(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -544,20 +687,115 @@ mod tests {
         let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink"><rect/></svg>"#;
         let document = parse_svg(svg).unwrap();
 
+        let scope = crate::ast::NamespaceScope::new().enter(&document.root);
+        assert_eq!(scope.uri(""), Some("http://www.w3.org/2000/svg"));
+        assert_eq!(scope.uri("xlink"), Some("http://www.w3.org/1999/xlink"));
+        assert!(document.root.has_attr("xmlns:xlink"));
+    }
+
+    #[test]
+    fn test_parse_invalid_xml() {
+        let svg = r#"<svg><rect></svg>"#; // Unclosed rect tag
+        let result = parse_svg(svg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_preserve_formatting_records_source() {
+        let svg = "<svg  viewBox='0 0 10 10' >\n  <rect x = \"1\"/>\n  <g></g>\n</svg>";
+        let document = Parser::new().preserve_formatting(true).parse(svg).unwrap();
+
+        let format = document.root.format.as_deref().unwrap();
+        assert_eq!(format.start_tag, "<svg  viewBox='0 0 10 10' >");
+        assert_eq!(format.attributes[0].leading, "  ");
+        assert_eq!(format.attributes[0].quote(), '\'');
+        assert_eq!(format.trailing, " ");
+        assert_eq!(format.end_tag.as_deref(), Some("</svg>"));
+
+        let children: Vec<_> = document.root.child_elements().collect();
+        let rect = children[0].format.as_deref().unwrap();
+        assert_eq!(rect.attributes[0].source, "x = \"1\"");
+        assert!(rect.is_self_closing());
+        assert!(!children[1].format.as_deref().unwrap().is_self_closing());
+        assert_eq!(document.root.children.len(), 5);
+    }
+
+    #[test]
+    fn test_expand_entities() {
+        let svg = r##"<!DOCTYPE svg [
+            <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+            <!ENTITY color "&red;">
+            <!ENTITY red "#f00">
+            <!ENTITY dot "<circle r='&size;'/>">
+            <!ENTITY size "2">
+        ]>
+        <svg xmlns="&ns_svg;"><text fill="&color;">a &lt; b &amp; &color;&#33;</text><g>&dot;</g></svg>"##;
+        let document = parse_svg(svg).unwrap();
+
         assert_eq!(
-            document.root.namespaces.get(""),
+            document.root.attr("xmlns"),
             Some(&"http://www.w3.org/2000/svg".to_string())
         );
+        let children: Vec<&Element> = document.root.child_elements().collect();
+        assert_eq!(children[0].attr("fill"), Some(&"#f00".to_string()));
         assert_eq!(
-            document.root.namespaces.get("xlink"),
-            Some(&"http://www.w3.org/1999/xlink".to_string())
+            children[0].children,
+            vec![Node::Text("a < b & #f00!".to_string())]
         );
+        let circle = children[1].child_elements().next().unwrap();
+        assert_eq!(circle.name, "circle");
+        assert_eq!(circle.attr("r"), Some(&"2".to_string()));
+        assert!(document.metadata.unexpanded_entities.is_empty());
     }
 
     #[test]
-    fn test_parse_invalid_xml() {
-        let svg = r#"<svg><rect></svg>"#; // Unclosed rect tag
-        let result = parse_svg(svg);
-        assert!(result.is_err());
+    fn test_keep_unexpanded_entities() {
+        let svg = r#"<!DOCTYPE svg [ <!ENTITY w "10"> <!ENTITY ext SYSTEM "ext.xml"> ]>
+        <svg width="&w;"><text>&ext;</text></svg>"#;
+
+        let document = parse_svg(svg).unwrap();
+        assert_eq!(document.root.attr("width"), Some(&"10".to_string()));
+        assert_eq!(
+            document.metadata.unexpanded_entities.iter().collect::<Vec<_>>(),
+            vec!["ext"]
+        );
+
+        let document = Parser::new().expand_entities(false).parse(svg).unwrap();
+        assert_eq!(document.root.attr("width"), Some(&"&w;".to_string()));
+        assert_eq!(
+            crate::stringifier::stringify(&document).unwrap(),
+            r#"<!DOCTYPE svg [ <!ENTITY w "10"> <!ENTITY ext SYSTEM "ext.xml"> ]><svg width="&w;"><text>&ext;</text></svg>"#
+        );
+
+        // Preserved source can't reuse references whose declarations may go
+        let document = Parser::new().preserve_formatting(true).parse(svg).unwrap();
+        assert!(document.root.format.is_none());
+        let text = document.root.child_elements().next().unwrap();
+        assert!(text.format.is_some());
+        assert_eq!(
+            crate::stringifier::stringify(&document).unwrap(),
+            r#"<!DOCTYPE svg [ <!ENTITY w "10"> <!ENTITY ext SYSTEM "ext.xml"> ]><svg width="10"><text>&ext;</text></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_entity_limits() {
+        let svg = r#"<!DOCTYPE svg [
+            <!ENTITY a "xxxxxxxxxx">
+            <!ENTITY b "&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;">
+            <!ENTITY c "&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;">
+        ]>
+        <svg><text>&c;</text></svg>"#;
+
+        assert!(parse_svg(svg).is_ok());
+        let limits = EntityLimits {
+            max_expansions: 50,
+            ..Default::default()
+        };
+        let error = Parser::new().entity_limits(limits).parse(svg).unwrap_err();
+        assert!(matches!(
+            error,
+            ParseError::EntityError(EntityError::TooManyExpansions(50))
+        ));
     }
 }
@@ -93,6 +93,15 @@ pub trait Plugin: Send + Sync {
     }
 }
 
+/// Watches plugins being applied, e.g. to attribute changes to them
+pub trait PluginObserver {
+    /// Called before the plugin named `plugin` is applied to `document`
+    fn before_plugin(&mut self, plugin: &str, plugin_info: &PluginInfo, document: &Document);
+
+    /// Called after the plugin named `plugin` was applied to `document`
+    fn after_plugin(&mut self, plugin: &str, plugin_info: &PluginInfo, document: &Document);
+}
+
 /// Plugin configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfig {
@@ -183,6 +192,18 @@ impl PluginRegistry {
         document: &mut Document,
         configs: &[PluginConfig],
         plugin_info: &PluginInfo,
+    ) -> PluginResult<()> {
+        self.apply_plugins_observed(document, configs, plugin_info, None)
+    }
+
+    /// Apply a list of plugin configurations to a document, reporting each
+    /// plugin that is applied to `observer`
+    pub fn apply_plugins_observed(
+        &mut self,
+        document: &mut Document,
+        configs: &[PluginConfig],
+        plugin_info: &PluginInfo,
+        mut observer: Option<&mut dyn PluginObserver>,
     ) -> PluginResult<()> {
         for config in configs {
             if !config.enabled {
@@ -202,7 +223,13 @@ impl PluginRegistry {
             }
 
             // Apply the plugin
+            if let Some(observer) = observer.as_deref_mut() {
+                observer.before_plugin(&config.name, plugin_info, document);
+            }
             plugin.apply(document, plugin_info, config.params.as_ref())?;
+            if let Some(observer) = observer.as_deref_mut() {
+                observer.after_plugin(&config.name, plugin_info, document);
+            }
         }
 
         Ok(())
@@ -241,6 +268,7 @@ pub fn create_default_registry() -> PluginRegistry {
     registry.register(crate::plugins::MergeStylesPlugin);
     registry.register(crate::plugins::ConvertStyleToAttrsPlugin);
     registry.register(crate::plugins::ConvertColorsPlugin);
+    registry.register(crate::plugins::AddAccessibilityAttrsPlugin);
     registry.register(crate::plugins::AddAttributesToSVGElementPlugin);
     registry.register(crate::plugins::AddClassesToSVGElementPlugin);
     registry.register(crate::plugins::RemoveAttributesBySelectorPlugin);
@@ -270,6 +298,7 @@ pub fn create_default_registry() -> PluginRegistry {
     registry.register(crate::plugins::ConvertPathDataPlugin);
     registry.register(crate::plugins::RemoveUselessTransformsPlugin);
     registry.register(crate::plugins::RemoveUselessStrokeAndFillPlugin);
+    registry.register(crate::plugins::SanitizePlugin);
 
     registry
 }
@@ -0,0 +1,92 @@
+// this_file: svgn/src/plugins/add_accessibility_attrs.rs
+
+//! Add accessibility attributes plugin
+//!
+//! This plugin gives the outer <svg> element `role="img"` when it has an
+//! accessible name, so screen readers announce it as one image, and
+//! `aria-hidden="true"` when it has none, treating it as decorative. An
+//! existing `role` or `aria-hidden` is left alone.
+
+use crate::a11y::{accessible_name, is_hidden};
+use crate::ast::Document;
+use crate::plugin::{Plugin, PluginInfo, PluginResult};
+use serde_json::Value;
+
+/// Plugin that adds `role` or `aria-hidden` to the outer <svg> element
+pub struct AddAccessibilityAttrsPlugin;
+
+impl Plugin for AddAccessibilityAttrsPlugin {
+    fn name(&self) -> &'static str {
+        "addAccessibilityAttrs"
+    }
+
+    fn description(&self) -> &'static str {
+        "adds role=\"img\" or aria-hidden=\"true\" to an outer <svg> element (disabled by default)"
+    }
+
+    fn apply(
+        &mut self,
+        document: &mut Document,
+        _plugin_info: &PluginInfo,
+        _params: Option<&Value>,
+    ) -> PluginResult<()> {
+        let root = &mut document.root;
+        if root.name != "svg" || root.has_attr("role") || is_hidden(root) {
+            return Ok(());
+        }
+
+        if accessible_name(root).is_some() {
+            root.set_attr("role".to_string(), "img".to_string());
+        } else {
+            root.set_attr("aria-hidden".to_string(), "true".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::stringifier::stringify;
+
+    fn apply(svg: &str) -> String {
+        let mut document = Parser::new().parse(svg).unwrap();
+        AddAccessibilityAttrsPlugin
+            .apply(&mut document, &PluginInfo::default(), None)
+            .unwrap();
+        stringify(&document).unwrap()
+    }
+
+    #[test]
+    fn test_named_svg_gets_role() {
+        assert_eq!(
+            apply("<svg><title>Logo</title></svg>"),
+            r#"<svg role="img"><title>Logo</title></svg>"#
+        );
+        assert_eq!(
+            apply(r#"<svg aria-label="Logo"/>"#),
+            r#"<svg aria-label="Logo" role="img"/>"#
+        );
+    }
+
+    #[test]
+    fn test_unnamed_svg_is_hidden() {
+        assert_eq!(
+            apply("<svg><path/></svg>"),
+            r#"<svg aria-hidden="true"><path/></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_existing_attributes_are_kept() {
+        for svg in [
+            r#"<svg role="graphics-document"><path/></svg>"#,
+            r#"<svg aria-hidden="true"><title>Logo</title></svg>"#,
+            r#"<svg role="presentation"><path/></svg>"#,
+        ] {
+            assert_eq!(apply(svg), svg);
+        }
+    }
+}
@@ -6,7 +6,8 @@
 //! It's careful not to break references and respects various preservation options.
 //! Ported from ref/svgo/plugins/cleanupIds.js
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{Document, Element, NamespaceScope, Node};
+use crate::collections::ARIA_IDREF_ATTRS;
 use crate::plugin::{Plugin, PluginResult};
 use regex::Regex;
 use serde_json::Value;
@@ -99,6 +100,23 @@ impl Plugin for CleanupIdsPlugin {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        // Order of the characters minified IDs are built from. Only ASCII
+        // letters are used, so every generated ID is a valid XML name.
+        let mut alphabet: Vec<char> = Vec::new();
+        if let Some(chars) = params
+            .and_then(|v| v.get("alphabet"))
+            .and_then(|v| v.as_str())
+        {
+            for ch in chars.chars() {
+                if ch.is_ascii_alphabetic() && !alphabet.contains(&ch) {
+                    alphabet.push(ch);
+                }
+            }
+        }
+        if alphabet.is_empty() {
+            alphabet = GENERATE_ID_CHARS.to_vec();
+        }
+
         // Check if we should deoptimize (skip processing)
         if !force && (has_scripts(&document.root) || has_styles(&document.root)) {
             return Ok(());
@@ -109,7 +127,12 @@ impl Plugin for CleanupIdsPlugin {
         let mut references_by_id: HashMap<String, Vec<(String, String)>> = HashMap::new();
 
         // First pass: collect IDs and references
-        collect_ids_and_refs(&mut document.root, &mut node_by_id, &mut references_by_id);
+        collect_ids_and_refs(
+            &mut document.root,
+            &NamespaceScope::new(),
+            &mut node_by_id,
+            &mut references_by_id,
+        );
 
         // Helper to check if an ID should be preserved
         let is_id_preserved = |id: &str| -> bool {
@@ -132,8 +155,8 @@ impl Plugin for CleanupIdsPlugin {
                         // Generate new minified ID
                         let mut new_id;
                         loop {
-                            current_id = generate_id(current_id);
-                            new_id = get_id_string(current_id.as_ref().unwrap());
+                            current_id = generate_id(current_id, alphabet.len());
+                            new_id = get_id_string(current_id.as_ref().unwrap(), &alphabet);
 
                             // Make sure the new ID is unique and not preserved
                             if !is_id_preserved(&new_id)
@@ -157,7 +180,7 @@ impl Plugin for CleanupIdsPlugin {
 
         // Update all references with new IDs
         if !id_mappings.is_empty() {
-            update_references(&mut document.root, &id_mappings);
+            update_references(&mut document.root, &NamespaceScope::new(), &id_mappings);
         }
 
         // Remove unreferenced IDs if requested
@@ -231,6 +254,7 @@ fn has_styles(element: &Element) -> bool {
 /// Collect all IDs and references to them
 fn collect_ids_and_refs(
     element: &mut Element,
+    scope: &NamespaceScope,
     node_by_id: &mut HashMap<String, *mut Element>,
     references_by_id: &mut HashMap<String, Vec<(String, String)>>,
 ) {
@@ -242,8 +266,9 @@ fn collect_ids_and_refs(
     }
 
     // Check for references in attributes
+    let scope = scope.enter(element);
     for (attr_name, attr_value) in &element.attributes {
-        let ids = find_references(attr_name, attr_value);
+        let ids = find_references(&scope, attr_name, attr_value);
         for id in ids {
             references_by_id
                 .entry(id)
@@ -255,13 +280,16 @@ fn collect_ids_and_refs(
     // Process children
     for child in &mut element.children {
         if let Node::Element(child_element) = child {
-            collect_ids_and_refs(child_element, node_by_id, references_by_id);
+            collect_ids_and_refs(child_element, &scope, node_by_id, references_by_id);
         }
     }
 }
 
 /// Find ID references in attribute values
-fn find_references(attribute: &str, value: &str) -> Vec<String> {
+///
+/// `style` covers both the attribute and `<style>` element content. `scope`
+/// resolves the prefix of XLink's `href`.
+pub fn find_references(scope: &NamespaceScope, attribute: &str, value: &str) -> Vec<String> {
     let mut results = Vec::new();
 
     // Check for URL references: url(#id) and url('#id')
@@ -281,7 +309,7 @@ fn find_references(attribute: &str, value: &str) -> Vec<String> {
     }
 
     // Check for href references: #id
-    if attribute == "href" || attribute.ends_with(":href") {
+    if scope.attribute_name(attribute).is_href() {
         if let Some(cap) = REG_REFERENCES_HREF.captures(value) {
             if let Some(id_match) = cap.get(1) {
                 results.push(id_match.as_str().to_string());
@@ -289,6 +317,11 @@ fn find_references(attribute: &str, value: &str) -> Vec<String> {
         }
     }
 
+    // Check for ARIA ID lists: aria-labelledby="title desc"
+    if ARIA_IDREF_ATTRS.contains(attribute) {
+        results.extend(value.split_whitespace().map(str::to_string));
+    }
+
     // Check for begin attribute references: elementId.event
     if attribute == "begin" {
         if let Some(cap) = REG_REFERENCES_BEGIN.captures(value) {
@@ -302,9 +335,9 @@ fn find_references(attribute: &str, value: &str) -> Vec<String> {
 }
 
 /// Generate the next ID in sequence
-fn generate_id(current_id: Option<Vec<usize>>) -> Option<Vec<usize>> {
-    let mut id = current_id.unwrap_or_else(|| vec![usize::MAX]); // Start before 'a'
-    let max_index = GENERATE_ID_CHARS.len() - 1;
+fn generate_id(current_id: Option<Vec<usize>>, alphabet_len: usize) -> Option<Vec<usize>> {
+    let mut id = current_id.unwrap_or_else(|| vec![usize::MAX]); // Start before the first char
+    let max_index = alphabet_len - 1;
 
     // Increment the ID
     let last_idx = id.len() - 1;
@@ -332,13 +365,18 @@ fn generate_id(current_id: Option<Vec<usize>>) -> Option<Vec<usize>> {
 }
 
 /// Convert ID array to string
-fn get_id_string(id: &[usize]) -> String {
-    id.iter().map(|&i| GENERATE_ID_CHARS[i]).collect()
+fn get_id_string(id: &[usize], alphabet: &[char]) -> String {
+    id.iter().map(|&i| alphabet[i]).collect()
 }
 
 /// Update all references with new IDs
-fn update_references(element: &mut Element, id_mappings: &HashMap<String, String>) {
+fn update_references(
+    element: &mut Element,
+    scope: &NamespaceScope,
+    id_mappings: &HashMap<String, String>,
+) {
     // Update attributes
+    let scope = scope.enter(element);
     for (attr_name, attr_value) in element.attributes.iter_mut() {
         let mut new_value = attr_value.clone();
 
@@ -366,7 +404,7 @@ fn update_references(element: &mut Element, id_mappings: &HashMap<String, String
         }
 
         // Update href references
-        if attr_name == "href" || attr_name.ends_with(":href") {
+        if scope.attribute_name(attr_name).is_href() {
             for (old_id, new_id) in id_mappings {
                 if new_value == format!("#{}", old_id) {
                     new_value = format!("#{}", new_id);
@@ -374,6 +412,15 @@ fn update_references(element: &mut Element, id_mappings: &HashMap<String, String
             }
         }
 
+        // Update ARIA ID lists
+        if ARIA_IDREF_ATTRS.contains(attr_name.as_str()) {
+            new_value = new_value
+                .split_whitespace()
+                .map(|id| id_mappings.get(id).map_or(id, String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
         // Update begin attribute references
         if attr_name == "begin" {
             for (old_id, new_id) in id_mappings {
@@ -387,7 +434,7 @@ fn update_references(element: &mut Element, id_mappings: &HashMap<String, String
     // Process children
     for child in &mut element.children {
         if let Node::Element(child_element) = child {
-            update_references(child_element, id_mappings);
+            update_references(child_element, &scope, id_mappings);
         }
     }
 }
@@ -466,6 +513,84 @@ mod tests {
         assert!(fill.contains(&format!("#{}", new_id)));
     }
 
+    #[test]
+    fn test_aria_references() {
+        let svg = r#"<svg aria-labelledby="svgTitle svgDesc">
+            <title id="svgTitle">Chart</title>
+            <desc id="svgDesc">Sales by month</desc>
+            <text id="unusedText">Label</text>
+        </svg>"#;
+
+        let mut document = Parser::new().parse(svg).unwrap();
+        CleanupIdsPlugin
+            .apply(&mut document, &crate::plugin::PluginInfo::default(), None)
+            .unwrap();
+
+        // Referenced IDs are kept and minified along with the references
+        let ids: Vec<_> = document
+            .root
+            .child_elements()
+            .map(|child| child.attr("id").cloned())
+            .collect();
+        let (title, desc) = (ids[0].clone().unwrap(), ids[1].clone().unwrap());
+        assert_eq!(title.len(), 1);
+        assert_eq!(ids[2], None);
+        assert_eq!(
+            document.root.attr("aria-labelledby").unwrap(),
+            &format!("{} {}", title, desc)
+        );
+    }
+
+    #[test]
+    fn test_href_references_by_namespace() {
+        let svg = r##"<svg xmlns:l="http://www.w3.org/1999/xlink" xmlns:o="http://example.com/other">
+            <circle id="linked"/>
+            <circle id="other"/>
+            <use l:href="#linked"/>
+            <use o:href="#other"/>
+        </svg>"##;
+
+        let mut document = Parser::new().parse(svg).unwrap();
+        CleanupIdsPlugin
+            .apply(&mut document, &crate::plugin::PluginInfo::default(), None)
+            .unwrap();
+
+        // Only XLink's href is a reference, whatever its prefix
+        let elements: Vec<_> = document.root.child_elements().collect();
+        assert_eq!(elements[0].attr("id").unwrap(), "a");
+        assert_eq!(elements[1].attr("id"), None);
+        assert_eq!(elements[2].attr("l:href").unwrap(), "#a");
+        assert_eq!(elements[3].attr("o:href").unwrap(), "#other");
+    }
+
+    #[test]
+    fn test_minify_ids_with_alphabet() {
+        let svg = r#"<svg>
+            <defs>
+                <linearGradient id="gradient"/>
+            </defs>
+            <rect fill="url(#gradient)" width="100" height="100"/>
+        </svg>"#;
+
+        let parser = Parser::new();
+        let mut document = parser.parse(svg).unwrap();
+
+        let mut plugin = CleanupIdsPlugin;
+        let params = serde_json::json!({"alphabet": "1Qa"});
+        plugin
+            .apply(
+                &mut document,
+                &crate::plugin::PluginInfo::default(),
+                Some(&params),
+            )
+            .unwrap();
+
+        // Non-letters are dropped from the alphabet
+        let defs = document.root.child_elements().next().unwrap();
+        let gradient = defs.child_elements().next().unwrap();
+        assert_eq!(gradient.attr("id").map(String::as_str), Some("Q"));
+    }
+
     #[test]
     fn test_preserve_ids() {
         let svg = r#"<svg>
@@ -96,14 +96,13 @@ mod tests {
     use crate::ast::{Document, Element, Node};
     use crate::plugin::{Plugin, PluginInfo};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_ellipse(cx: &str, cy: &str, rx: &str, ry: &str) -> Element {
         let mut ellipse = Element {
             name: "ellipse".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         ellipse.attributes.insert("cx".to_string(), cx.to_string());
         ellipse.attributes.insert("cy".to_string(), cy.to_string());
@@ -183,9 +182,9 @@ mod tests {
         let mut doc = Document::default();
         let mut ellipse = Element {
             name: "ellipse".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         ellipse
             .attributes
@@ -212,9 +211,9 @@ mod tests {
 
         let mut svg = Element {
             name: "svg".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
 
         svg.children
@@ -1,6 +1,6 @@
 // this_file: svgn/src/plugins/convert_one_stop_gradients.rs
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{ns, Document, Element, NamespaceScope, Node};
 use crate::collections::COLORS_PROPS;
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use serde_json::Value;
@@ -9,7 +9,6 @@ use std::collections::{HashMap, HashSet};
 #[derive(Default)]
 pub struct ConvertOneStopGradientsPlugin;
 
-
 impl ConvertOneStopGradientsPlugin {
     pub fn new() -> Self {
         Self
@@ -18,10 +17,14 @@ impl ConvertOneStopGradientsPlugin {
     fn process_element(
         &self,
         element: &mut Element,
+        scope: &NamespaceScope,
         gradients_to_remove: &mut HashMap<String, String>,
+        gradient_refs: &mut Vec<(String, String)>,
         parent_is_defs: bool,
         affected_defs: &mut HashSet<String>,
     ) {
+        let scope = scope.enter(element);
+
         // Track defs elements
         if element.name == "defs" && element.attributes.contains_key("id") {
             if let Some(id) = element.attributes.get("id") {
@@ -51,13 +54,15 @@ impl ConvertOneStopGradientsPlugin {
 
                 // Check if this gradient references another gradient
                 let href = element
-                    .attributes
-                    .get("xlink:href")
-                    .or_else(|| element.attributes.get("href"));
-
-                // If this gradient has no stops and references another, skip for now
-                // (would need to resolve references, which is complex)
-                if stops.is_empty() && href.is_some() {
+                    .attr_ns(&scope, ns::XLINK, "href")
+                    .or_else(|| element.attributes.get_key_value("href"));
+
+                // A gradient without stops uses those of the gradient it
+                // references, which is resolved once all gradients are seen
+                if let (true, Some((_, href))) = (stops.is_empty(), href) {
+                    if let Some(target) = href.strip_prefix('#') {
+                        gradient_refs.push((id.clone(), target.to_string()));
+                    }
                     return;
                 }
 
@@ -100,7 +105,14 @@ impl ConvertOneStopGradientsPlugin {
         let is_defs = element.name == "defs";
         for child in &mut element.children {
             if let Node::Element(ref mut child_elem) = child {
-                self.process_element(child_elem, gradients_to_remove, is_defs, affected_defs);
+                self.process_element(
+                    child_elem,
+                    &scope,
+                    gradients_to_remove,
+                    gradient_refs,
+                    is_defs,
+                    affected_defs,
+                );
             }
         }
     }
@@ -195,28 +207,35 @@ impl ConvertOneStopGradientsPlugin {
     }
 
     fn remove_unused_xlink_namespace(&self, document: &mut Document) {
-        // Check if any xlink:href attributes remain
-        fn check_xlink(element: &Element) -> bool {
-            if element.attributes.contains_key("xlink:href") {
+        // Check if any attributes in the XLink namespace remain
+        fn check_xlink(element: &Element, scope: &NamespaceScope) -> bool {
+            let scope = scope.enter(element);
+            if element
+                .attributes
+                .keys()
+                .any(|name| scope.attribute_name(name).in_namespace(ns::XLINK))
+            {
                 return true;
             }
 
-            for child in &element.children {
-                if let Node::Element(ref elem) = child {
-                    if check_xlink(elem) {
-                        return true;
-                    }
-                }
-            }
-            false
+            element
+                .child_elements()
+                .any(|child| check_xlink(child, &scope))
         }
 
-        let has_xlink = check_xlink(&document.root);
+        let has_xlink = check_xlink(&document.root, &NamespaceScope::new());
 
-        // Remove xmlns:xlink if no xlink:href attributes remain
+        // Remove XLink namespace declarations if no XLink attributes remain
         if !has_xlink {
-            document.root.namespaces.remove("xlink");
-            document.root.attributes.shift_remove("xmlns:xlink");
+            let prefixes: Vec<String> = document
+                .root
+                .namespace_declarations()
+                .filter(|(prefix, uri)| !prefix.is_empty() && *uri == ns::XLINK)
+                .map(|(prefix, _)| format!("xmlns:{prefix}"))
+                .collect();
+            for name in prefixes {
+                document.root.attributes.shift_remove(&name);
+            }
         }
     }
 }
@@ -237,16 +256,35 @@ impl Plugin for ConvertOneStopGradientsPlugin {
         _params: Option<&Value>,
     ) -> PluginResult<()> {
         let mut gradients_to_remove = HashMap::new();
+        let mut gradient_refs = Vec::new();
         let mut affected_defs = HashSet::new();
 
         // First pass: identify gradients with only one stop
         self.process_element(
             &mut document.root,
+            &NamespaceScope::new(),
             &mut gradients_to_remove,
+            &mut gradient_refs,
             false,
             &mut affected_defs,
         );
 
+        // Gradients referencing a one-stop gradient, possibly through others,
+        // become its color too
+        loop {
+            let resolved: Vec<(String, String)> = gradient_refs
+                .iter()
+                .filter(|(id, _)| !gradients_to_remove.contains_key(id))
+                .filter_map(|(id, target)| {
+                    Some((id.clone(), gradients_to_remove.get(target)?.clone()))
+                })
+                .collect();
+            if resolved.is_empty() {
+                break;
+            }
+            gradients_to_remove.extend(resolved);
+        }
+
         // Second pass: replace gradient references with solid colors
         if !gradients_to_remove.is_empty() {
             self.replace_gradient_references(&mut document.root, &gradients_to_remove);
@@ -274,13 +312,12 @@ mod tests {
     use indexmap::IndexMap;
 
     fn create_test_document() -> Document {
-        use std::collections::HashMap;
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             prologue: vec![],
             epilogue: vec![],
@@ -288,6 +325,9 @@ mod tests {
                 path: None,
                 encoding: None,
                 version: None,
+                declaration: None,
+                unexpanded_entities: Default::default(),
+                sanitize_report: None,
             },
         }
     }
@@ -347,8 +387,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "rect".to_string(),
             attributes: rect_attrs,
-            namespaces: std::collections::HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let info = PluginInfo::default();
@@ -362,4 +402,22 @@ mod tests {
             assert_eq!(rect.attributes.get("fill"), Some(&"red".to_string()));
         }
     }
+
+    #[test]
+    fn test_resolves_xlink_href_by_namespace() {
+        let mut doc = crate::parser::Parser::new()
+            .parse(r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:x="http://www.w3.org/1999/xlink"><defs><linearGradient id="a"><stop stop-color="red"/></linearGradient><linearGradient id="b" x:href="#a"/><linearGradient id="c" x:href="#b"/></defs><rect fill="url(#c)"/></svg>"##)
+            .unwrap();
+
+        ConvertOneStopGradientsPlugin::new()
+            .apply(&mut doc, &PluginInfo::default(), None)
+            .unwrap();
+
+        assert_eq!(
+            crate::stringifier::Stringifier::new()
+                .stringify(&doc)
+                .unwrap(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><rect fill="red"/></svg>"#
+        );
+    }
 }
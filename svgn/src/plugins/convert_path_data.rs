@@ -137,7 +137,7 @@ fn optimize_paths_in_element(
 
 /// Path command types
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum CommandType {
+pub enum CommandType {
     MoveTo,
     LineTo,
     HorizontalLineTo,
@@ -152,10 +152,10 @@ enum CommandType {
 
 /// Represents a path command with its parameters
 #[derive(Debug, Clone)]
-struct PathCommand {
-    cmd_type: CommandType,
-    is_absolute: bool,
-    params: Vec<f64>,
+pub struct PathCommand {
+    pub cmd_type: CommandType,
+    pub is_absolute: bool,
+    pub params: Vec<f64>,
 }
 
 impl PathCommand {
@@ -186,7 +186,10 @@ impl PathCommand {
 }
 
 /// Parse a path data string into commands
-fn parse_path_data(path_data: &str) -> Result<Vec<PathCommand>, String> {
+///
+/// Implicitly repeated commands are split into one command each, and the
+/// coordinate pairs after the first of a moveto become linetos.
+pub fn parse_path_data(path_data: &str) -> Result<Vec<PathCommand>, String> {
     let mut commands = Vec::new();
     let mut chars = path_data.chars().peekable();
     let mut current_nums = Vec::new();
@@ -226,15 +229,27 @@ fn parse_path_data(path_data: &str) -> Result<Vec<PathCommand>, String> {
                     last_cmd_type = Some((cmd_type, is_absolute));
                 }
             }
+            '0' | '1'
+                if current_num.is_empty()
+                    && matches!(last_cmd_type, Some((CommandType::Arc, _)))
+                    && matches!(current_nums.len() % 7, 3 | 4) =>
+            {
+                // Arc flags are single digits and need no separator
+                current_nums.push(if ch == '1' { 1.0 } else { 0.0 });
+            }
             '0'..='9' | '.' | '-' | '+' | 'e' | 'E' => {
-                if ch == '-' || ch == '+' {
-                    // Start new number if not at beginning of current number
-                    if !current_num.is_empty() && in_number {
-                        if let Ok(num) = current_num.parse::<f64>() {
-                            current_nums.push(num);
-                        }
-                        current_num.clear();
+                // A sign starts a new number unless it follows an exponent,
+                // and so does a second decimal point, as in `.5.5`
+                let starts_number = match ch {
+                    '-' | '+' => !current_num.ends_with(['e', 'E']),
+                    '.' => current_num.contains(['.', 'e', 'E']),
+                    _ => false,
+                };
+                if starts_number && !current_num.is_empty() && in_number {
+                    if let Ok(num) = current_num.parse::<f64>() {
+                        current_nums.push(num);
                     }
+                    current_num.clear();
                 }
                 current_num.push(ch);
                 in_number = true;
@@ -317,15 +332,17 @@ fn process_accumulated_params(
     }
 
     // Process params in chunks
+    let mut first = true;
     while params.len() >= expected {
         let chunk: Vec<f64> = params.drain(..expected).collect();
 
         // Special case: MoveTo followed by implicit LineTo
-        let actual_cmd_type = if cmd_type == CommandType::MoveTo && !commands.is_empty() {
+        let actual_cmd_type = if cmd_type == CommandType::MoveTo && !first {
             CommandType::LineTo
         } else {
             cmd_type
         };
+        first = false;
 
         commands.push(PathCommand {
             cmd_type: actual_cmd_type,
@@ -596,12 +613,10 @@ fn stringify_commands(
     let mut current_y = 0.0;
 
     for (i, cmd) in commands.iter().enumerate() {
-        // Decide whether to use absolute or relative
-        let mut use_absolute = cmd.is_absolute;
-        if utilize_absolute && i > 0 {
-            // Calculate which is shorter
-            use_absolute = should_use_absolute(cmd, current_x, current_y, precision);
-        }
+        // The first command is always absolute; without utilizeAbsolute,
+        // the rest are relative
+        let use_absolute =
+            i == 0 || utilize_absolute && should_use_absolute(cmd, current_x, current_y, precision);
 
         // Get the command character
         let cmd_char = if use_absolute {
@@ -825,6 +840,35 @@ mod tests {
         assert!(!commands[1].is_absolute);
     }
 
+    #[test]
+    fn test_parse_subpaths() {
+        let commands = parse_path_data("M0 0 1 1M5 5L6 6").unwrap();
+        let types: Vec<CommandType> = commands.iter().map(|cmd| cmd.cmd_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                CommandType::MoveTo,
+                CommandType::LineTo,
+                CommandType::MoveTo,
+                CommandType::LineTo
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_compact_numbers() {
+        let commands = parse_path_data("M.5.5-1e-2 2a1 1 0 01.5.5").unwrap();
+        let params: Vec<&[f64]> = commands.iter().map(|cmd| cmd.params.as_slice()).collect();
+        assert_eq!(
+            params,
+            vec![
+                &[0.5, 0.5][..],
+                &[-0.01, 2.0][..],
+                &[1.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.5][..]
+            ]
+        );
+    }
+
     #[test]
     fn test_format_number() {
         assert_eq!(format_number(1.0, 3, true), "1");
@@ -849,4 +893,28 @@ mod tests {
         // Should remove the L10 10 as it's the same as current position
         assert!(!optimized.contains("L10 10"));
     }
+
+    #[test]
+    fn test_optimize_without_utilize_absolute() {
+        let mut config = PathOptimizationConfig {
+            float_precision: 3,
+            transform_precision: 5,
+            remove_useless: true,
+            collapse_repeated: true,
+            utilize_absolute: true,
+            leading_zero: true,
+            negative_extra_space: true,
+        };
+        let path = "M10 10L20 10l0 10H10z";
+        assert_eq!(
+            optimize_path_data(path, &config).unwrap(),
+            "M10 10 L20 10 20 20 H10Z"
+        );
+
+        config.utilize_absolute = false;
+        assert_eq!(
+            optimize_path_data(path, &config).unwrap(),
+            "M10 10 l10 0 0 10 h-10z"
+        );
+    }
 }
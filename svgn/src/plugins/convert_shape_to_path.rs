@@ -68,7 +68,14 @@ fn convert_shapes_in_element(
 }
 
 /// Convert a shape element to a path if applicable
-fn convert_shape_element(element: &mut Element, convert_arcs: bool, float_precision: Option<u8>) {
+///
+/// Rectangles with rounded corners, and circles and ellipses unless
+/// `convert_arcs` is set, are left as they are.
+pub fn convert_shape_element(
+    element: &mut Element,
+    convert_arcs: bool,
+    float_precision: Option<u8>,
+) {
     match element.name.as_str() {
         "rect" => convert_rect(element, float_precision),
         "line" => convert_line(element, float_precision),
@@ -365,7 +372,7 @@ mod tests {
             name: name.to_string(),
             attributes,
             children: vec![],
-            namespaces: Default::default(),
+            format: None,
         }
     }
 
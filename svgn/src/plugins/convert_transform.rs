@@ -192,6 +192,51 @@ impl Transform {
     }
 }
 
+/// Translation, rotation and scale that make up a transform, applied in
+/// the order scale, rotate, translate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decomposition {
+    pub translate_x: f64,
+    pub translate_y: f64,
+    /// Rotation in degrees
+    pub rotate: f64,
+    pub scale_x: f64,
+    pub scale_y: f64,
+}
+
+/// Combine a transform list into a single matrix
+pub fn transforms_to_matrix(transforms: &[Transform]) -> Matrix3<f64> {
+    transforms
+        .iter()
+        .fold(Matrix3::identity(), |matrix, transform| {
+            matrix * transform.to_matrix()
+        })
+}
+
+/// Decompose a matrix into `translate · rotate · scale`
+///
+/// Returns `None` if the matrix skews, which those can't express.
+pub fn decompose_matrix(matrix: &Matrix3<f64>) -> Option<Decomposition> {
+    let a = matrix[(0, 0)];
+    let b = matrix[(1, 0)];
+    let c = matrix[(0, 1)];
+    let d = matrix[(1, 1)];
+
+    let scale_x = a.hypot(b);
+    if scale_x < 1e-12 || (a * c + b * d).abs() > 1e-9 * scale_x * c.hypot(d).max(1.0) {
+        return None;
+    }
+
+    Some(Decomposition {
+        translate_x: matrix[(0, 2)],
+        translate_y: matrix[(1, 2)],
+        rotate: b.atan2(a) * 180.0 / PI,
+        scale_x,
+        // A negative determinant mirrors along the y axis
+        scale_y: (a * d - b * c) / scale_x,
+    })
+}
+
 impl ConvertTransformPlugin {
     /// Parse transform string to transform operations
     pub fn parse_transform_string(&self, transform_str: &str) -> Vec<Transform> {
@@ -493,14 +538,13 @@ mod tests {
     use crate::plugin::{Plugin, PluginInfo};
     use indexmap::IndexMap;
     use serde_json::json;
-    use std::collections::HashMap;
 
     fn create_test_element_with_transform(transform: &str) -> Element {
         let mut element = Element {
             name: "rect".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         element
             .attributes
@@ -520,6 +564,23 @@ mod tests {
         assert_eq!(transforms[1].data, vec![2.0]);
     }
 
+    #[test]
+    fn test_decompose_matrix() {
+        let plugin = ConvertTransformPlugin;
+        let matrix =
+            |transform: &str| transforms_to_matrix(&plugin.parse_transform_string(transform));
+
+        let decomposition =
+            decompose_matrix(&matrix("translate(10 20) rotate(90) scale(2 -3)")).unwrap();
+        assert!((decomposition.translate_x - 10.0).abs() < 1e-9);
+        assert!((decomposition.translate_y - 20.0).abs() < 1e-9);
+        assert!((decomposition.rotate - 90.0).abs() < 1e-9);
+        assert!((decomposition.scale_x - 2.0).abs() < 1e-9);
+        assert!((decomposition.scale_y + 3.0).abs() < 1e-9);
+
+        assert_eq!(decompose_matrix(&matrix("skewX(30)")), None);
+    }
+
     #[test]
     fn test_remove_useless_transforms() {
         let plugin = ConvertTransformPlugin;
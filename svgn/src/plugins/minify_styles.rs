@@ -181,7 +181,7 @@ mod tests {
             name: name.to_string(),
             attributes,
             children: vec![],
-            namespaces: Default::default(),
+            format: None,
         }
     }
 
@@ -7,6 +7,7 @@ pub mod remove_useless_transforms;
 // This module contains all the built-in plugins that can be used to optimize
 // SVG documents. Each plugin implements the `Plugin` trait.
 
+pub mod add_accessibility_attrs;
 pub mod add_attributes_to_svg_element;
 pub mod add_classes_to_svg_element;
 pub mod cleanup_attrs;
@@ -54,10 +55,12 @@ pub mod remove_view_box;
 pub mod remove_xlink;
 pub mod remove_xml_proc_inst;
 pub mod remove_xmlns;
+pub mod sanitize;
 pub mod sort_attrs;
 pub mod sort_defs_children;
 
 // Re-export plugins
+pub use add_accessibility_attrs::AddAccessibilityAttrsPlugin;
 pub use add_attributes_to_svg_element::AddAttributesToSVGElementPlugin;
 pub use add_classes_to_svg_element::AddClassesToSVGElementPlugin;
 pub use cleanup_attrs::CleanupAttrsPlugin;
@@ -106,5 +109,6 @@ pub use remove_view_box::RemoveViewBoxPlugin;
 pub use remove_xlink::RemoveXlinkPlugin;
 pub use remove_xml_proc_inst::RemoveXMLProcInstPlugin;
 pub use remove_xmlns::RemoveXMLNSPlugin;
+pub use sanitize::SanitizePlugin;
 pub use sort_attrs::SortAttrsPlugin;
 pub use sort_defs_children::SortDefsChildrenPlugin;
@@ -1,6 +1,6 @@
 // this_file: svgn/src/plugins/prefix_ids.rs
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{ns, Document, Element, NamespaceScope, Node};
 use crate::collections::REFERENCES_PROPS;
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use regex::Regex;
@@ -109,7 +109,15 @@ impl PrefixIdsPlugin {
             .map(|id| format!("#{}", self.prefix_id(prefix, id)))
     }
 
-    fn process_element(&self, element: &mut Element, prefix: &str, config: &PrefixIdsConfig) {
+    fn process_element(
+        &self,
+        element: &mut Element,
+        parent_scope: &NamespaceScope,
+        prefix: &str,
+        config: &PrefixIdsConfig,
+    ) {
+        let scope = parent_scope.enter(element);
+
         // Prefix ID attribute
         if config.prefix_ids {
             if let Some(id) = element.attributes.get_mut("id") {
@@ -132,8 +140,14 @@ impl PrefixIdsPlugin {
             }
         }
 
-        // Prefix href and xlink:href attributes
-        for attr_name in ["href", "xlink:href"] {
+        // Prefix href and xlink:href attributes, whatever the XLink prefix
+        let href_attrs: Vec<String> = element
+            .attributes
+            .keys()
+            .filter(|name| *name == "href" || scope.attribute_name(name).is(ns::XLINK, "href"))
+            .cloned()
+            .collect();
+        for attr_name in &href_attrs {
             if let Some(href) = element.attributes.get_mut(attr_name) {
                 if !href.is_empty() {
                     if let Some(prefixed) = self.prefix_reference(prefix, href) {
@@ -173,7 +187,7 @@ impl PrefixIdsPlugin {
         // Process children recursively
         for child in &mut element.children {
             if let Node::Element(ref mut child_elem) = child {
-                self.process_element(child_elem, prefix, config);
+                self.process_element(child_elem, &scope, prefix, config);
             }
         }
     }
@@ -303,7 +317,7 @@ impl Plugin for PrefixIdsPlugin {
         let config = self.parse_config(params);
         let prefix = self.generate_prefix(&config, info);
 
-        self.process_element(&mut document.root, &prefix, &config);
+        self.process_element(&mut document.root, &NamespaceScope::new(), &prefix, &config);
 
         Ok(())
     }
@@ -316,15 +330,14 @@ mod tests {
     use crate::ast::{Document, Element, Node};
     use crate::plugin::PluginInfo;
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             prologue: vec![],
             epilogue: vec![],
@@ -332,6 +345,9 @@ mod tests {
                 path: None,
                 encoding: None,
                 version: None,
+                declaration: None,
+                unexpanded_entities: Default::default(),
+                sanitize_report: None,
             },
         }
     }
@@ -430,8 +446,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "rect".to_string(),
             attributes: attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let info = PluginInfo::default();
@@ -457,8 +473,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "use".to_string(),
             attributes: attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let info = PluginInfo::default();
@@ -487,8 +503,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "rect".to_string(),
             attributes: attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let config = serde_json::json!({
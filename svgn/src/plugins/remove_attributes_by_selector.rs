@@ -321,7 +321,6 @@ mod tests {
     use crate::plugin::{Plugin, PluginInfo};
     use indexmap::IndexMap;
     use serde_json::json;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         let mut doc = Document::default();
@@ -329,17 +328,17 @@ mod tests {
         // Create a simple SVG structure
         let mut svg = Element {
             name: "svg".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
 
         // Add rect with fill="#00ff00"
         let mut rect = Element {
             name: "rect".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         rect.attributes.insert("x".to_string(), "0".to_string());
         rect.attributes.insert("y".to_string(), "0".to_string());
@@ -406,9 +405,9 @@ mod tests {
         // Add an element with id="remove"
         let mut circle = Element {
             name: "circle".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         circle
             .attributes
@@ -71,7 +71,7 @@ fn remove_comments_from_node(node: &mut crate::ast::Element, preserve_patterns:
         .iter()
         .any(|c| matches!(c, Node::Text(text) if !text.trim().is_empty()));
 
-    if has_element_children && !has_meaningful_text {
+    if has_element_children && !has_meaningful_text && node.format.is_none() {
         // Remove whitespace-only text nodes
         node.children
             .retain(|child| !matches!(child, Node::Text(text) if text.trim().is_empty()));
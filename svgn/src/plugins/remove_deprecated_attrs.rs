@@ -385,9 +385,9 @@ mod tests {
 
         let mut svg = Element {
             name: "svg".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         svg.attributes
             .insert("xml:lang".to_string(), "en".to_string());
@@ -397,9 +397,9 @@ mod tests {
 
         let mut rect = Element {
             name: "rect".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         rect.attributes.insert("x".to_string(), "0".to_string());
         rect.attributes.insert("y".to_string(), "0".to_string());
@@ -465,9 +465,9 @@ mod tests {
 
         let mut svg = Element {
             name: "svg".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         svg.attributes
             .insert("xml:lang".to_string(), "en".to_string());
@@ -490,16 +490,16 @@ mod tests {
 
         let mut svg = Element {
             name: "svg".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
 
         let mut animate = Element {
             name: "animate".to_string(),
-            namespaces: HashMap::new(),
             attributes: IndexMap::new(),
             children: vec![],
+            format: None,
         };
         animate
             .attributes
@@ -5,9 +5,11 @@
 //! This plugin removes <desc> elements from SVG documents.
 //! By default, it only removes empty descriptions or those containing standard
 //! editor content (e.g., "Created with..."). Can be configured to remove all
-//! descriptions.
+//! descriptions. With `keepAccessible`, the first `<desc>` of the root and
+//! those referenced by `aria-describedby` are kept.
 //! Ported from ref/svgo/plugins/removeDesc.js
 
+use crate::a11y::AccessibleText;
 use crate::ast::{Document, Element, Node};
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use regex::Regex;
@@ -41,22 +43,37 @@ impl Plugin for RemoveDescPlugin {
             .and_then(|v| v.get("removeAny"))
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
+        let keep_accessible = params
+            .and_then(|v| v.get("keepAccessible"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let keep = keep_accessible
+            .then(|| AccessibleText::new(&document.root, "desc", "aria-describedby"));
 
         // Process the document
-        remove_desc_from_element(&mut document.root, remove_any);
+        remove_desc_from_element(&mut document.root, remove_any, keep.as_ref(), true);
 
         Ok(())
     }
 }
 
 /// Recursively remove <desc> elements from an element and its descendants
-fn remove_desc_from_element(element: &mut Element, remove_any: bool) {
+fn remove_desc_from_element(
+    element: &mut Element,
+    remove_any: bool,
+    keep: Option<&AccessibleText>,
+    is_root: bool,
+) {
     // Filter out desc elements based on criteria
+    let mut first = is_root;
     element.children.retain(|child| {
         match child {
             Node::Element(child_element) if child_element.name == "desc" => {
+                let accessible = keep.is_some_and(|keep| keep.keeps(child_element, first))
+                    && !child_element.children.is_empty();
+                first = false;
                 // Keep the desc element if we should not remove it
-                !should_remove_desc(child_element, remove_any)
+                accessible || !should_remove_desc(child_element, remove_any)
             }
             _ => true,
         }
@@ -65,7 +82,7 @@ fn remove_desc_from_element(element: &mut Element, remove_any: bool) {
     // Process remaining child elements
     for child in &mut element.children {
         if let Node::Element(child_element) = child {
-            remove_desc_from_element(child_element, remove_any);
+            remove_desc_from_element(child_element, remove_any, keep, false);
         }
     }
 }
@@ -181,6 +198,26 @@ mod tests {
         assert!(!has_desc_element(&document.root));
     }
 
+    #[test]
+    fn test_keep_accessible() {
+        let svg = r#"<svg aria-describedby="about"><desc>Description</desc><g><desc>Group</desc><desc id="about">About</desc></g></svg>"#;
+
+        let mut document = Parser::new().parse(svg).unwrap();
+        let params = json!({"removeAny": true, "keepAccessible": true});
+        RemoveDescPlugin
+            .apply(
+                &mut document,
+                &crate::plugin::PluginInfo::default(),
+                Some(&params),
+            )
+            .unwrap();
+
+        assert_eq!(
+            crate::stringifier::stringify(&document).unwrap(),
+            r#"<svg aria-describedby="about"><desc>Description</desc><g><desc id="about">About</desc></g></svg>"#
+        );
+    }
+
     fn has_desc_element(element: &Element) -> bool {
         for child in &element.children {
             if let Node::Element(child_element) = child {
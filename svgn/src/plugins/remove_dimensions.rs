@@ -7,7 +7,6 @@ use serde_json::Value;
 #[derive(Default)]
 pub struct RemoveDimensionsPlugin;
 
-
 impl RemoveDimensionsPlugin {
     pub fn new() -> Self {
         Self
@@ -22,26 +21,11 @@ impl RemoveDimensionsPlugin {
         if element.attributes.contains_key("viewBox") {
             element.attributes.shift_remove("width");
             element.attributes.shift_remove("height");
-        } else {
-            // Try to create viewBox from width and height if both are present and numeric
-            let width_str = element.attributes.get("width");
-            let height_str = element.attributes.get("height");
-
-            if let (Some(width_str), Some(height_str)) = (width_str, height_str) {
-                // Try to parse width and height as numbers
-                if let (Ok(width), Ok(height)) =
-                    (width_str.parse::<f64>(), height_str.parse::<f64>())
-                {
-                    // Only proceed if both are valid numbers (not NaN)
-                    if !width.is_nan() && !height.is_nan() {
-                        // Create viewBox and remove width/height
-                        let viewbox = format!("0 0 {} {}", width, height);
-                        element.attributes.insert("viewBox".to_string(), viewbox);
-                        element.attributes.shift_remove("width");
-                        element.attributes.shift_remove("height");
-                    }
-                }
-            }
+        } else if let Some(viewbox) = viewbox_from_dimensions(element) {
+            // Create viewBox and remove width/height
+            element.attributes.insert("viewBox".to_string(), viewbox);
+            element.attributes.shift_remove("width");
+            element.attributes.shift_remove("height");
         }
     }
 
@@ -58,6 +42,17 @@ impl RemoveDimensionsPlugin {
     }
 }
 
+/// The viewBox equivalent to numeric `width` and `height` attributes
+///
+/// Returns `None` unless both are present and plain numbers.
+pub fn viewbox_from_dimensions(element: &Element) -> Option<String> {
+    let width = element.attributes.get("width")?.parse::<f64>().ok()?;
+    let height = element.attributes.get("height")?.parse::<f64>().ok()?;
+
+    // Only proceed if both are valid numbers (not NaN)
+    (!width.is_nan() && !height.is_nan()).then(|| format!("0 0 {} {}", width, height))
+}
+
 impl Plugin for RemoveDimensionsPlugin {
     fn name(&self) -> &'static str {
         "removeDimensions"
@@ -85,15 +80,14 @@ mod tests {
     use crate::ast::{Document, Element, Node};
     use crate::plugin::PluginInfo;
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             prologue: vec![],
             epilogue: vec![],
@@ -101,6 +95,9 @@ mod tests {
                 path: None,
                 encoding: None,
                 version: None,
+                declaration: None,
+                unexpanded_entities: Default::default(),
+                sanitize_report: None,
             },
         }
     }
@@ -256,8 +253,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "rect".to_string(),
             attributes: rect_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let info = PluginInfo::default();
@@ -295,8 +292,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "svg".to_string(),
             attributes: nested_svg_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let info = PluginInfo::default();
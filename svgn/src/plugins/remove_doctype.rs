@@ -5,6 +5,9 @@
 //! This plugin removes DOCTYPE declarations from SVG documents.
 //! DOCTYPE declarations are not recommended for SVG documents as they
 //! can be a source of issues and are not required.
+//!
+//! A DOCTYPE is kept when entity references were left unexpanded, since the
+//! document would no longer be well-formed without their declarations.
 //! Ported from ref/svgo/plugins/removeDoctype.js
 
 use crate::ast::Document;
@@ -29,6 +32,10 @@ impl Plugin for RemoveDoctypePlugin {
         _plugin_info: &PluginInfo,
         _params: Option<&Value>,
     ) -> PluginResult<()> {
+        if !document.metadata.unexpanded_entities.is_empty() {
+            return Ok(());
+        }
+
         // Remove DOCTYPE from prologue
         document.prologue.retain(|node| !node.is_doctype());
 
@@ -80,4 +87,19 @@ mod tests {
             .apply(&mut document, &crate::plugin::PluginInfo::default(), None)
             .unwrap();
     }
+
+    #[test]
+    fn test_keep_doctype_with_unexpanded_entities() {
+        let svg = r#"<!DOCTYPE svg [ <!ENTITY ns_svg "http://www.w3.org/2000/svg"> ]>
+<svg xmlns="&ns_svg;"/>"#;
+
+        let mut document = Parser::new().expand_entities(false).parse(svg).unwrap();
+        let mut plugin = RemoveDoctypePlugin;
+        plugin
+            .apply(&mut document, &PluginInfo::default(), None)
+            .unwrap();
+
+        assert!(document.prologue.iter().any(|n| n.is_doctype()));
+        assert_eq!(document.root.attr("xmlns"), Some(&"&ns_svg;".to_string()));
+    }
 }
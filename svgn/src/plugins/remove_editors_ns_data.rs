@@ -1,6 +1,6 @@
 // this_file: svgn/src/plugins/remove_editors_ns_data.rs
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{Document, Element, NamespaceScope, Node};
 use crate::collections::EDITOR_NAMESPACES;
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use serde_json::Value;
@@ -15,7 +15,6 @@ pub struct RemoveEditorsNSDataConfig {
 #[derive(Default)]
 pub struct RemoveEditorsNSDataPlugin;
 
-
 impl RemoveEditorsNSDataPlugin {
     pub fn new() -> Self {
         Self
@@ -36,98 +35,76 @@ impl RemoveEditorsNSDataPlugin {
         config
     }
 
-    fn collect_editor_prefixes(
-        &self,
-        element: &mut Element,
-        config: &RemoveEditorsNSDataConfig,
-    ) -> Vec<String> {
-        let mut prefixes = Vec::new();
-
-        if element.name == "svg" {
-            // Create a combined set of namespaces to check
-            let mut namespaces_to_remove = HashSet::new();
-            for ns in EDITOR_NAMESPACES.iter() {
-                namespaces_to_remove.insert(*ns);
-            }
-            for ns in &config.additional_namespaces {
-                namespaces_to_remove.insert(ns.as_str());
-            }
-
-            // Collect namespace prefixes and remove xmlns declarations
-            let mut attrs_to_remove = Vec::new();
-
-            for (name, value) in &element.attributes {
-                if name.starts_with("xmlns:") && namespaces_to_remove.contains(value.as_str()) {
-                    let prefix = &name[6..]; // Remove "xmlns:" prefix
-                    prefixes.push(prefix.to_string());
-                    attrs_to_remove.push(name.clone());
-                }
-            }
-
-            // Remove the xmlns declarations
-            for attr in attrs_to_remove {
-                element.attributes.shift_remove(&attr);
-            }
-        }
-
-        prefixes
+    fn editor_namespaces<'a>(&self, config: &'a RemoveEditorsNSDataConfig) -> HashSet<&'a str> {
+        EDITOR_NAMESPACES
+            .iter()
+            .copied()
+            .chain(config.additional_namespaces.iter().map(String::as_str))
+            .collect()
     }
 
-    fn remove_editor_attributes(&self, element: &mut Element, prefixes: &[String]) {
-        let mut attrs_to_remove = Vec::new();
-
-        for name in element.attributes.keys() {
-            if name.contains(':') {
-                if let Some(colon_pos) = name.find(':') {
-                    let prefix = &name[..colon_pos];
-                    if prefixes.contains(&prefix.to_string()) {
-                        attrs_to_remove.push(name.clone());
-                    }
-                }
-            }
-        }
+    fn remove_editor_declarations(&self, element: &mut Element, namespaces: &HashSet<&str>) {
+        let attrs_to_remove: Vec<String> = element
+            .namespace_declarations()
+            .filter(|(prefix, uri)| !prefix.is_empty() && namespaces.contains(uri))
+            .map(|(prefix, _)| format!("xmlns:{prefix}"))
+            .collect();
 
-        // Remove the editor attributes
         for attr in attrs_to_remove {
             element.attributes.shift_remove(&attr);
         }
     }
 
-    fn should_remove_element(&self, element: &Element, prefixes: &[String]) -> bool {
-        if element.name.contains(':') {
-            if let Some(colon_pos) = element.name.find(':') {
-                let prefix = &element.name[..colon_pos];
-                return prefixes.contains(&prefix.to_string());
-            }
-        }
-        false
+    fn remove_editor_attributes(
+        &self,
+        element: &mut Element,
+        scope: &NamespaceScope,
+        namespaces: &HashSet<&str>,
+    ) {
+        element.attributes.retain(|name, _| {
+            !scope
+                .attribute_name(name)
+                .uri
+                .is_some_and(|uri| namespaces.contains(uri.as_str()))
+        });
     }
 
+    fn should_remove_element(
+        &self,
+        element: &Element,
+        scope: &NamespaceScope,
+        namespaces: &HashSet<&str>,
+    ) -> bool {
+        element
+            .qname(scope)
+            .uri
+            .is_some_and(|uri| namespaces.contains(uri.as_str()))
+    }
+
+    /// Process `element`, whose own declarations are already in `scope`
     fn process_element(
         &self,
         element: &mut Element,
-        prefixes: &mut Vec<String>,
-        config: &RemoveEditorsNSDataConfig,
+        scope: &NamespaceScope,
+        namespaces: &HashSet<&str>,
     ) {
-        // If this is the SVG root element, collect editor prefixes
-        if element.name == "svg" && prefixes.is_empty() {
-            *prefixes = self.collect_editor_prefixes(element, config);
-        }
-
-        // Remove editor attributes from this element
-        self.remove_editor_attributes(element, prefixes);
+        // Remove editor attributes and namespace declarations from this element
+        self.remove_editor_attributes(element, scope, namespaces);
+        self.remove_editor_declarations(element, namespaces);
 
         // Process children, removing editor elements
         element.children.retain_mut(|child| {
             match child {
                 Node::Element(ref mut child_elem) => {
+                    let child_scope = scope.enter(child_elem);
+
                     // Check if this element should be removed
-                    if self.should_remove_element(child_elem, prefixes) {
+                    if self.should_remove_element(child_elem, &child_scope, namespaces) {
                         return false; // Remove this element
                     }
 
                     // Recursively process this child element
-                    self.process_element(child_elem, prefixes, config);
+                    self.process_element(child_elem, &child_scope, namespaces);
                     true // Keep this element
                 }
                 _ => true, // Keep non-element nodes
@@ -152,9 +129,10 @@ impl Plugin for RemoveEditorsNSDataPlugin {
         params: Option<&Value>,
     ) -> PluginResult<()> {
         let config = self.parse_config(params);
-        let mut prefixes = Vec::new();
+        let namespaces = self.editor_namespaces(&config);
+        let scope = NamespaceScope::new().enter(&document.root);
 
-        self.process_element(&mut document.root, &mut prefixes, &config);
+        self.process_element(&mut document.root, &scope, &namespaces);
 
         Ok(())
     }
@@ -167,15 +145,14 @@ mod tests {
     use crate::ast::{Document, Element, Node};
     use crate::plugin::PluginInfo;
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             prologue: vec![],
             epilogue: vec![],
@@ -183,6 +160,9 @@ mod tests {
                 path: None,
                 encoding: None,
                 version: None,
+                declaration: None,
+                unexpanded_entities: Default::default(),
+                sanitize_report: None,
             },
         }
     }
@@ -198,15 +178,16 @@ mod tests {
     }
 
     #[test]
-    fn test_collect_editor_prefixes() {
+    fn test_remove_editor_declarations() {
         let plugin = RemoveEditorsNSDataPlugin::new();
         let config = RemoveEditorsNSDataConfig::default();
+        let namespaces = plugin.editor_namespaces(&config);
 
         let mut svg_element = Element {
             name: "svg".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         // Add some editor namespace declarations
@@ -223,12 +204,7 @@ mod tests {
             "http://normal.namespace".to_string(),
         );
 
-        let prefixes = plugin.collect_editor_prefixes(&mut svg_element, &config);
-
-        // Should have collected the editor prefixes and removed xmlns declarations
-        assert!(prefixes.contains(&"sodipodi".to_string()));
-        assert!(prefixes.contains(&"inkscape".to_string()));
-        assert!(!prefixes.contains(&"normal".to_string()));
+        plugin.remove_editor_declarations(&mut svg_element, &namespaces);
 
         // Editor xmlns declarations should be removed
         assert!(!svg_element.attributes.contains_key("xmlns:sodipodi"));
@@ -241,13 +217,24 @@ mod tests {
     #[test]
     fn test_remove_editor_attributes() {
         let plugin = RemoveEditorsNSDataPlugin::new();
-        let prefixes = vec!["sodipodi".to_string(), "inkscape".to_string()];
+        let config = RemoveEditorsNSDataConfig::default();
+        let namespaces = plugin.editor_namespaces(&config);
+        let mut svg_element = Element::new("svg");
+        svg_element.set_attr(
+            "xmlns:sodipodi".to_string(),
+            "http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd".to_string(),
+        );
+        svg_element.set_attr(
+            "xmlns:inkscape".to_string(),
+            "http://www.inkscape.org/namespaces/inkscape".to_string(),
+        );
+        let scope = NamespaceScope::new().enter(&svg_element);
 
         let mut element = Element {
             name: "path".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         // Add various attributes
@@ -264,7 +251,7 @@ mod tests {
             .attributes
             .insert("fill".to_string(), "red".to_string());
 
-        plugin.remove_editor_attributes(&mut element, &prefixes);
+        plugin.remove_editor_attributes(&mut element, &scope, &namespaces);
 
         // Editor attributes should be removed
         assert!(!element.attributes.contains_key("sodipodi:nodetypes"));
@@ -280,24 +267,31 @@ mod tests {
     #[test]
     fn test_should_remove_element() {
         let plugin = RemoveEditorsNSDataPlugin::new();
-        let prefixes = vec!["sodipodi".to_string()];
+        let config = RemoveEditorsNSDataConfig::default();
+        let namespaces = plugin.editor_namespaces(&config);
+        let mut svg_element = Element::new("svg");
+        svg_element.set_attr(
+            "xmlns:sodipodi".to_string(),
+            "http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd".to_string(),
+        );
+        let scope = NamespaceScope::new().enter(&svg_element);
 
         let editor_element = Element {
             name: "sodipodi:namedview".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let normal_element = Element {
             name: "path".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
-        assert!(plugin.should_remove_element(&editor_element, &prefixes));
-        assert!(!plugin.should_remove_element(&normal_element, &prefixes));
+        assert!(plugin.should_remove_element(&editor_element, &scope, &namespaces));
+        assert!(!plugin.should_remove_element(&normal_element, &scope, &namespaces));
     }
 
     #[test]
@@ -315,8 +309,8 @@ mod tests {
         let sodipodi_element = Element {
             name: "sodipodi:namedview".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
         doc.root.children.push(Node::Element(sodipodi_element));
 
@@ -327,8 +321,8 @@ mod tests {
         let path_element = Element {
             name: "path".to_string(),
             attributes: path_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
         doc.root.children.push(Node::Element(path_element));
 
@@ -367,8 +361,8 @@ mod tests {
         let custom_element = Element {
             name: "custom:element".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
         doc.root.children.push(Node::Element(custom_element));
 
@@ -387,6 +381,36 @@ mod tests {
         assert_eq!(doc.root.children.len(), 0);
     }
 
+    #[test]
+    fn test_apply_matches_namespaces_by_uri() {
+        let mut plugin = RemoveEditorsNSDataPlugin::new();
+        let mut doc = crate::parser::parse_svg(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:ink="http://www.inkscape.org/namespaces/inkscape" xmlns:sodipodi="http://example.com/not-an-editor">
+                <g ink:label="Layer" sodipodi:keep="yes">
+                    <ink:guide/>
+                    <svg:g xmlns:svg="http://www.w3.org/2000/svg" xmlns:s="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd" s:type="arc"/>
+                </g>
+            </svg>"#,
+        )
+        .unwrap();
+
+        plugin
+            .apply(&mut doc, &PluginInfo::default(), None)
+            .unwrap();
+
+        // Editor prefixes are recognised whatever their name, on any element
+        assert!(!doc.root.has_attr("xmlns:ink"));
+        assert!(doc.root.has_attr("xmlns:sodipodi"));
+        let g = doc.root.child_elements().next().unwrap();
+        assert!(!g.has_attr("ink:label"));
+        assert_eq!(g.attr("sodipodi:keep"), Some(&"yes".to_string()));
+        let children: Vec<&Element> = g.child_elements().collect();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "svg:g");
+        assert!(!children[0].has_attr("xmlns:s"));
+        assert!(!children[0].has_attr("s:type"));
+    }
+
     #[test]
     fn test_apply_preserves_normal_content() {
         let mut plugin = RemoveEditorsNSDataPlugin::new();
@@ -401,8 +425,8 @@ mod tests {
         let rect_element = Element {
             name: "rect".to_string(),
             attributes: rect_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
         doc.root.children.push(Node::Element(rect_element));
 
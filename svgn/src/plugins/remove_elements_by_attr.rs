@@ -144,15 +144,14 @@ mod tests {
     use crate::ast::{Document, Element, Node};
     use crate::plugin::PluginInfo;
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             prologue: vec![],
             epilogue: vec![],
@@ -160,6 +159,9 @@ mod tests {
                 path: None,
                 encoding: None,
                 version: None,
+                declaration: None,
+                unexpanded_entities: Default::default(),
+                sanitize_report: None,
             },
         }
     }
@@ -248,8 +250,8 @@ mod tests {
         let element = Element {
             name: "rect".to_string(),
             attributes: element_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         assert!(plugin.should_remove_element(&element, &config));
@@ -260,8 +262,8 @@ mod tests {
         let element2 = Element {
             name: "rect".to_string(),
             attributes: element_attrs2,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         assert!(!plugin.should_remove_element(&element2, &config));
@@ -284,8 +286,8 @@ mod tests {
         let element = Element {
             name: "rect".to_string(),
             attributes: element_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         assert!(plugin.should_remove_element(&element, &config));
@@ -299,8 +301,8 @@ mod tests {
         let element2 = Element {
             name: "rect".to_string(),
             attributes: element_attrs2,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         assert!(!plugin.should_remove_element(&element2, &config));
@@ -317,8 +319,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "rect".to_string(),
             attributes: attrs_remove,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         // Add element to keep
@@ -327,8 +329,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "circle".to_string(),
             attributes: attrs_keep,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let config = serde_json::json!({
@@ -366,8 +368,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "rect".to_string(),
             attributes: attrs_remove,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         // Add element to keep
@@ -379,8 +381,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "circle".to_string(),
             attributes: attrs_keep,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let config = serde_json::json!({
@@ -415,8 +417,8 @@ mod tests {
         doc.root.children.push(Node::Element(Element {
             name: "rect".to_string(),
             attributes: attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }));
 
         let info = PluginInfo::default();
@@ -438,8 +440,8 @@ mod tests {
         let nested_element = Element {
             name: "rect".to_string(),
             attributes: nested_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let mut group_attrs = IndexMap::new();
@@ -447,8 +449,8 @@ mod tests {
         let group = Element {
             name: "g".to_string(),
             attributes: group_attrs,
-            namespaces: HashMap::new(),
             children: vec![Node::Element(nested_element)],
+            format: None,
         };
 
         doc.root.children.push(Node::Element(group));
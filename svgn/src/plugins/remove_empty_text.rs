@@ -5,7 +5,7 @@
 //! Removes empty `<text>`, `<tspan>`, and `<tref>` elements that serve no purpose.
 //! For `<tref>` elements, they are removed if they don't have a valid `xlink:href` attribute.
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{ns, Document, Element, NamespaceScope, Node};
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use serde_json::Value;
 
@@ -70,24 +70,32 @@ impl Plugin for RemoveEmptyTextPlugin {
         params: Option<&Value>,
     ) -> PluginResult<()> {
         let config = RemoveEmptyTextParams::from_value(params);
-        remove_empty_text(&mut document.root, &config);
+        let scope = NamespaceScope::new().enter(&document.root);
+        remove_empty_text(&mut document.root, &scope, &config);
         Ok(())
     }
 }
 
 /// Remove empty text elements from the tree
-fn remove_empty_text(element: &mut Element, config: &RemoveEmptyTextParams) {
+///
+/// `scope` includes the namespace declarations of `element` itself.
+fn remove_empty_text(
+    element: &mut Element,
+    scope: &NamespaceScope,
+    config: &RemoveEmptyTextParams,
+) {
     // First, recursively process all child elements
     for child in &mut element.children {
         if let Node::Element(child_element) = child {
-            remove_empty_text(child_element, config);
+            let child_scope = scope.enter(child_element);
+            remove_empty_text(child_element, &child_scope, config);
         }
     }
 
     // Then remove empty text elements from this element's children
     element.children.retain(|child| {
         if let Node::Element(child_element) = child {
-            !should_remove_empty_text_element(child_element, config)
+            !should_remove_empty_text_element(child_element, &scope.enter(child_element), config)
         } else {
             true // Keep non-element nodes
         }
@@ -95,7 +103,11 @@ fn remove_empty_text(element: &mut Element, config: &RemoveEmptyTextParams) {
 }
 
 /// Determine if a text element should be removed
-fn should_remove_empty_text_element(element: &Element, config: &RemoveEmptyTextParams) -> bool {
+fn should_remove_empty_text_element(
+    element: &Element,
+    scope: &NamespaceScope,
+    config: &RemoveEmptyTextParams,
+) -> bool {
     match element.name.as_str() {
         "text" => {
             // Remove empty text elements if enabled
@@ -107,7 +119,7 @@ fn should_remove_empty_text_element(element: &Element, config: &RemoveEmptyTextP
         }
         "tref" => {
             // Remove tref elements without xlink:href if enabled
-            config.tref && !element.attributes.contains_key("xlink:href")
+            config.tref && element.attr_ns(scope, ns::XLINK, "href").is_none()
         }
         _ => false,
     }
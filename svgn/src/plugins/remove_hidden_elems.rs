@@ -208,15 +208,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -230,8 +229,8 @@ mod tests {
         Element {
             name: name.to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }
     }
 
@@ -434,18 +433,18 @@ mod tests {
         let empty_group = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let filled_group = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(create_element(
                 "rect",
                 vec![("width", "100"), ("height", "100")],
             ))],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(empty_group), Node::Element(filled_group)];
@@ -489,7 +488,6 @@ mod tests {
         let group = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Element(create_element(
                     "rect",
@@ -501,6 +499,7 @@ mod tests {
                     vec![("visibility", "hidden"), ("width", "100")],
                 )),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(group)];
@@ -74,15 +74,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -96,8 +95,8 @@ mod tests {
         Element {
             name: "g".to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }
     }
 
@@ -214,8 +213,8 @@ mod tests {
         let rect = Element {
             name: "rect".to_string(),
             attributes: rect_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(rect)];
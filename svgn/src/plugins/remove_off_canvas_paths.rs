@@ -247,7 +247,6 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document_with_viewbox(viewbox: &str) -> Document {
         let mut root_attrs = IndexMap::new();
@@ -257,8 +256,8 @@ mod tests {
             root: Element {
                 name: "svg".to_string(),
                 attributes: root_attrs,
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -272,8 +271,8 @@ mod tests {
         Element {
             name: name.to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }
     }
 
@@ -410,8 +409,8 @@ mod tests {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         };
@@ -444,7 +443,6 @@ mod tests {
         let group = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 // Rect outside
                 Node::Element(create_element(
@@ -457,6 +455,7 @@ mod tests {
                     vec![("x", "10"), ("y", "10"), ("width", "40"), ("height", "40")],
                 )),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(group)];
@@ -5,7 +5,7 @@
 //! This plugin removes `<image>` elements that reference raster images (JPEG, PNG, GIF).
 //! This is useful when you want a pure vector SVG without embedded or linked bitmaps.
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{ns, Document, Element, NamespaceScope, Node};
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -33,17 +33,19 @@ impl Plugin for RemoveRasterImagesPlugin {
         _info: &PluginInfo,
         _params: Option<&Value>,
     ) -> PluginResult<()> {
-        self.process_element(&mut document.root);
+        let scope = NamespaceScope::new().enter(&document.root);
+        self.process_element(&mut document.root, &scope);
         Ok(())
     }
 }
 
 impl RemoveRasterImagesPlugin {
-    fn process_element(&self, element: &mut Element) {
+    /// Process `element`, whose own declarations are already in `scope`
+    fn process_element(&self, element: &mut Element, scope: &NamespaceScope) {
         // Filter out raster image elements
         element.children.retain(|child| {
             if let Node::Element(ref elem) = child {
-                !self.is_raster_image(elem)
+                !is_raster_image(elem, &scope.enter(elem))
             } else {
                 true
             }
@@ -52,27 +54,27 @@ impl RemoveRasterImagesPlugin {
         // Process remaining children recursively
         for child in &mut element.children {
             if let Node::Element(ref mut elem) = child {
-                self.process_element(elem);
+                self.process_element(elem, &scope.enter(elem));
             }
         }
     }
+}
 
-    fn is_raster_image(&self, element: &Element) -> bool {
-        if element.name != "image" {
-            return false;
-        }
-
-        // Check both xlink:href and href attributes
-        for attr_name in ["xlink:href", "href"] {
-            if let Some(href_value) = element.attributes.get(attr_name) {
-                if RASTER_IMAGE_REGEX.is_match(href_value) {
-                    return true;
-                }
-            }
-        }
-
-        false
+/// Whether `element` is an `<image>` that references a raster image
+///
+/// `scope` must include the element's own namespace declarations.
+pub fn is_raster_image(element: &Element, scope: &NamespaceScope) -> bool {
+    if element.name != "image" {
+        return false;
     }
+
+    // Check both xlink:href (under any prefix) and href attributes
+    element
+        .attr_ns(scope, ns::XLINK, "href")
+        .map(|(_, value)| value)
+        .into_iter()
+        .chain(element.attr("href"))
+        .any(|href_value| RASTER_IMAGE_REGEX.is_match(href_value))
 }
 
 #[cfg(test)]
@@ -81,15 +83,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -104,8 +105,8 @@ mod tests {
         Element {
             name: "image".to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }
     }
 
@@ -221,8 +222,8 @@ mod tests {
         let rect_element = Element {
             name: "rect".to_string(),
             attributes: rect_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![
@@ -264,12 +265,12 @@ mod tests {
         let g_element = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Element(create_image_element("xlink:href", "photo1.jpg")),
                 Node::Element(create_image_element("href", "icon.svg")),
                 Node::Element(create_image_element("xlink:href", "photo2.png")),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(g_element)];
@@ -13,7 +13,6 @@ use crate::collections::{
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use indexmap::IndexMap;
 use serde_json::Value;
-use std::collections::HashMap;
 
 /// Plugin to remove scripts and script-related attributes
 pub struct RemoveScriptsPlugin;
@@ -98,7 +97,6 @@ impl RemoveScriptsPlugin {
 
             // Clear attributes and replace with children
             element.attributes = IndexMap::new();
-            element.namespaces = HashMap::new();
             element.children = useful_children;
         }
     }
@@ -110,15 +108,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -141,15 +138,15 @@ mod tests {
         let script_element = Element {
             name: "script".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Text("alert('hello')".to_string())],
+            format: None,
         };
 
         let circle_element = Element {
             name: "circle".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![
@@ -182,8 +179,8 @@ mod tests {
         let element = Element {
             name: "rect".to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(element)];
@@ -215,15 +212,15 @@ mod tests {
         let child_element = Node::Element(Element {
             name: "span".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         });
 
         let anchor_element = Element {
             name: "a".to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![child_text, child_element.clone()],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(anchor_element)];
@@ -257,8 +254,8 @@ mod tests {
         let anchor_element = Element {
             name: "a".to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![Node::Text("Normal link".to_string())],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(anchor_element.clone())];
@@ -288,13 +285,13 @@ mod tests {
         let anchor_element = Element {
             name: "a".to_string(),
             attributes,
-            namespaces: HashMap::new(),
             children: vec![Node::Element(Element {
                 name: "rect".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             })],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(anchor_element)];
@@ -2,8 +2,11 @@
 
 //! Remove title plugin
 //!
-//! This plugin removes all <title> elements from the SVG document.
+//! This plugin removes all <title> elements from the SVG document. With
+//! `keepAccessible`, the first `<title>` of the root and those referenced by
+//! `aria-labelledby` are kept.
 
+use crate::a11y::AccessibleText;
 use crate::ast::{Document, Node};
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use serde_json::Value;
@@ -24,23 +27,37 @@ impl Plugin for RemoveTitlePlugin {
         &mut self,
         document: &mut Document,
         _plugin_info: &PluginInfo,
-        _params: Option<&Value>,
+        params: Option<&Value>,
     ) -> PluginResult<()> {
+        let keep_accessible = params
+            .and_then(|v| v.get("keepAccessible"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let keep = keep_accessible
+            .then(|| AccessibleText::new(&document.root, "title", "aria-labelledby"));
+
         // Remove title elements from the document
-        remove_title_from_node(&mut document.root);
+        remove_title_from_node(&mut document.root, keep.as_ref(), true);
         Ok(())
     }
 }
 
 /// Recursively remove title elements from a node and its children
-fn remove_title_from_node(node: &mut crate::ast::Element) {
-    // Filter out title elements
-    node.children.retain(|child| {
-        if let Node::Element(element) = child {
-            element.name != "title"
-        } else {
-            true
+fn remove_title_from_node(
+    node: &mut crate::ast::Element,
+    keep: Option<&AccessibleText>,
+    is_root: bool,
+) {
+    // Filter out title elements, except those that name something
+    let mut first = is_root;
+    node.children.retain(|child| match child {
+        Node::Element(element) if element.name == "title" => {
+            let accessible =
+                keep.is_some_and(|keep| keep.keeps(element, first)) && !element.children.is_empty();
+            first = false;
+            accessible
         }
+        _ => true,
     });
 
     // Clean up whitespace-only text nodes if element now only has element children
@@ -50,7 +67,7 @@ fn remove_title_from_node(node: &mut crate::ast::Element) {
         .iter()
         .any(|c| matches!(c, Node::Text(text) if !text.trim().is_empty()));
 
-    if has_element_children && !has_meaningful_text {
+    if has_element_children && !has_meaningful_text && node.format.is_none() {
         // Remove whitespace-only text nodes
         node.children
             .retain(|child| !matches!(child, Node::Text(text) if text.trim().is_empty()));
@@ -59,7 +76,7 @@ fn remove_title_from_node(node: &mut crate::ast::Element) {
     // Recursively process child elements
     for child in &mut node.children {
         if let Node::Element(element) = child {
-            remove_title_from_node(element);
+            remove_title_from_node(element, keep, false);
         }
     }
 }
@@ -93,6 +110,26 @@ mod tests {
         assert!(!has_title(&document.root));
     }
 
+    #[test]
+    fn test_keep_accessible() {
+        let svg = r#"<svg><title>Name</title><title>Second</title><g><title>Group</title><text aria-labelledby="label"/><title id="label">Label</title></g></svg>"#;
+
+        let mut document = Parser::new().parse(svg).unwrap();
+        let params = serde_json::json!({"keepAccessible": true});
+        RemoveTitlePlugin
+            .apply(
+                &mut document,
+                &crate::plugin::PluginInfo::default(),
+                Some(&params),
+            )
+            .unwrap();
+
+        assert_eq!(
+            crate::stringifier::stringify(&document).unwrap(),
+            r#"<svg><title>Name</title><g><text aria-labelledby="label"/><title id="label">Label</title></g></svg>"#
+        );
+    }
+
     fn has_title(element: &Element) -> bool {
         if element.name == "title" {
             return true;
@@ -5,7 +5,7 @@
 //! This is a simplified implementation that handles common cases without full SVG spec data.
 //! Future versions will include complete SVG specification compliance.
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{ns, Document, Element, NamespaceScope, Node};
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
@@ -187,6 +187,7 @@ static KNOWN_ATTRIBUTES: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
         "pathLength",
         // Presentation attributes
         "fill",
+        "fill-rule",
         "stroke",
         "stroke-width",
         "stroke-linecap",
@@ -263,8 +264,6 @@ static KNOWN_ATTRIBUTES: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
         // Other common attributes
         "preserveAspectRatio",
         "version",
-        "xmlns",
-        "xmlns:xlink",
         "xml:space",
     ]
     .into_iter()
@@ -279,6 +278,7 @@ static DEFAULT_ATTRIBUTE_VALUES: LazyLock<HashMap<&'static str, &'static str>> =
             ("y", "0"),
             ("fill", "#000000"),
             ("fill", "black"),
+            ("fill-rule", "nonzero"),
             ("stroke", "none"),
             ("stroke-width", "1"),
             ("stroke-linecap", "butt"),
@@ -331,7 +331,7 @@ impl Plugin for RemoveUnknownsAndDefaultsPlugin {
             process_xml_declaration(&mut document.metadata);
         }
 
-        visit_elements(&mut document.root, &config, None);
+        visit_elements(&mut document.root, &NamespaceScope::new(), &config, None);
         Ok(())
     }
 }
@@ -352,10 +352,12 @@ fn process_xml_declaration(metadata: &mut crate::ast::DocumentMetadata) {
 /// Visit all elements in the AST and remove unknowns and defaults
 fn visit_elements(
     element: &mut Element,
+    parent_scope: &NamespaceScope,
     config: &RemoveUnknownsAndDefaultsParams,
     parent_element: Option<&Element>,
 ) {
-    remove_unknown_and_default_attributes(element, config, parent_element);
+    let scope = parent_scope.enter(element);
+    remove_unknown_and_default_attributes(element, &scope, config, parent_element);
 
     // First, collect indices of children to remove and process remaining children
     let mut children_to_remove = Vec::new();
@@ -389,7 +391,7 @@ fn visit_elements(
             let mut temp_parent = Element::new(&parent_name);
             temp_parent.attributes = parent_attrs;
 
-            visit_elements(child_element, config, Some(&temp_parent));
+            visit_elements(child_element, &scope, config, Some(&temp_parent));
         }
     }
 }
@@ -408,14 +410,21 @@ fn should_remove_unknown_element(element: &Element) -> bool {
 /// Remove unknown and default attributes from a single element
 fn remove_unknown_and_default_attributes(
     element: &mut Element,
+    scope: &NamespaceScope,
     config: &RemoveUnknownsAndDefaultsParams,
     parent_element: Option<&Element>,
 ) {
     let mut attrs_to_remove = Vec::new();
 
     for (attr_name, attr_value) in &element.attributes {
-        let should_remove =
-            should_remove_attribute(attr_name, attr_value, element, config, parent_element);
+        let should_remove = should_remove_attribute(
+            attr_name,
+            attr_value,
+            element,
+            scope,
+            config,
+            parent_element,
+        );
 
         if should_remove {
             attrs_to_remove.push(attr_name.clone());
@@ -433,6 +442,7 @@ fn should_remove_attribute(
     attr_name: &str,
     attr_value: &str,
     element: &Element,
+    scope: &NamespaceScope,
     config: &RemoveUnknownsAndDefaultsParams,
     parent_element: Option<&Element>,
 ) -> bool {
@@ -451,15 +461,10 @@ fn should_remove_attribute(
         return false;
     }
 
-    // Always keep xmlns
-    if attr_name == "xmlns" {
-        return false;
-    }
-
-    // Keep namespaced attributes (xml:*, xlink:*)
-    if attr_name.contains(':') {
-        let prefix = attr_name.split(':').next().unwrap_or("");
-        if prefix == "xml" || prefix == "xlink" {
+    // Keep namespace declarations, which are removeUnusedNS's job, and
+    // attributes in the XML and XLink namespaces, whatever their prefix
+    if let Some(uri) = scope.attribute_name(attr_name).uri {
+        if uri == ns::XMLNS || uri == ns::XML || uri == ns::XLINK {
             return false;
         }
     }
@@ -563,6 +568,33 @@ mod tests {
         assert!(!document.root.has_attr("fill")); // default value removed
     }
 
+    #[test]
+    fn test_keeps_non_default_fill_and_clip_rules() {
+        let mut document = Document::new();
+        let mut path = Element::new("path");
+        path.set_attr("fill-rule".to_string(), "evenodd".to_string());
+        path.set_attr("clip-rule".to_string(), "evenodd".to_string());
+        let mut nonzero = Element::new("path");
+        nonzero.set_attr("fill-rule".to_string(), "nonzero".to_string());
+        nonzero.set_attr("clip-rule".to_string(), "nonzero".to_string());
+        document.root.children = vec![Node::Element(path), Node::Element(nonzero)];
+
+        let mut plugin = RemoveUnknownsAndDefaultsPlugin;
+        plugin
+            .apply(&mut document, &crate::plugin::PluginInfo::default(), None)
+            .unwrap();
+
+        let Node::Element(path) = &document.root.children[0] else {
+            panic!("expected element");
+        };
+        assert_eq!(path.attr("fill-rule").map(String::as_str), Some("evenodd"));
+        assert_eq!(path.attr("clip-rule").map(String::as_str), Some("evenodd"));
+        let Node::Element(nonzero) = &document.root.children[1] else {
+            panic!("expected element");
+        };
+        assert!(nonzero.attributes.is_empty());
+    }
+
     #[test]
     fn test_preserves_data_attributes() {
         let mut document = Document::new();
@@ -678,6 +710,27 @@ mod tests {
         assert!(!document.root.has_attr("unknown-attr")); // removed
     }
 
+    #[test]
+    fn test_preserves_prefixed_xlink_declaration() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:x="http://www.w3.org/1999/xlink"><image x:href="a.png"/></svg>"#;
+        let mut document = crate::parser::Parser::new().parse(svg).unwrap();
+
+        let mut plugin = RemoveUnknownsAndDefaultsPlugin;
+        plugin
+            .apply(&mut document, &crate::plugin::PluginInfo::default(), None)
+            .unwrap();
+
+        assert!(document.root.has_attr("xmlns:x"));
+        let image = document.root.child_elements().next().unwrap();
+        assert!(image.has_attr("x:href"));
+
+        // The default preset keeps the declaration the href needs
+        let result = crate::optimizer::optimize_default(svg).unwrap();
+        assert!(result
+            .data
+            .contains(r#"xmlns:x="http://www.w3.org/1999/xlink""#));
+    }
+
     #[test]
     fn test_removes_unknown_elements() {
         let mut document = Document::new();
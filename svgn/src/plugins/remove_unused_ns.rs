@@ -5,7 +5,7 @@
 //! This plugin removes unused namespace declarations from the root SVG element
 //! which are not used in elements or attributes throughout the document.
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{Document, Element};
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use serde_json::Value;
 use std::collections::HashSet;
@@ -40,7 +40,7 @@ impl Plugin for RemoveUnusedNSPlugin {
         }
 
         // Traverse the document and remove used namespaces from the unused set
-        self.check_usage(&document.root, &mut unused_namespaces);
+        self.check_usage(&document.root, &HashSet::new(), &mut unused_namespaces);
 
         // Remove unused namespace declarations from root element
         for ns in &unused_namespaces {
@@ -53,31 +53,45 @@ impl Plugin for RemoveUnusedNSPlugin {
 }
 
 impl RemoveUnusedNSPlugin {
-    fn check_usage(&self, element: &Element, unused_namespaces: &mut HashSet<String>) {
-        // Check if element name uses a namespace
-        if element.name.contains(':') {
-            let parts: Vec<&str> = element.name.split(':').collect();
-            if parts.len() >= 2 {
-                let ns = parts[0];
-                unused_namespaces.remove(ns);
-            }
-        }
-
-        // Check if any attributes use namespaces
-        for attr_name in element.attributes.keys() {
-            if attr_name.contains(':') {
-                let parts: Vec<&str> = attr_name.split(':').collect();
-                if parts.len() >= 2 {
-                    let ns = parts[0];
-                    unused_namespaces.remove(ns);
+    /// Mark prefixes used by `element` and its descendants
+    ///
+    /// `shadowed` holds the prefixes redeclared below the root; their uses
+    /// resolve to the nearer declaration and don't keep the root's alive.
+    fn check_usage(
+        &self,
+        element: &Element,
+        shadowed: &HashSet<String>,
+        unused_namespaces: &mut HashSet<String>,
+    ) {
+        // Check if the element name or any attribute uses a namespace prefix;
+        // the declarations themselves don't count as uses
+        let names = std::iter::once(&element.name).chain(
+            element
+                .attributes
+                .keys()
+                .filter(|name| *name != "xmlns" && !name.starts_with("xmlns:")),
+        );
+        for name in names {
+            if let Some((prefix, _)) = name.split_once(':') {
+                if !shadowed.contains(prefix) {
+                    unused_namespaces.remove(prefix);
                 }
             }
         }
 
         // Recursively check children
-        for child in &element.children {
-            if let Node::Element(ref elem) = child {
-                self.check_usage(elem, unused_namespaces);
+        for child in element.child_elements() {
+            let declared: Vec<&str> = child
+                .namespace_declarations()
+                .map(|(prefix, _)| prefix)
+                .filter(|prefix| !prefix.is_empty())
+                .collect();
+            if declared.is_empty() {
+                self.check_usage(child, shadowed, unused_namespaces);
+            } else {
+                let mut shadowed = shadowed.clone();
+                shadowed.extend(declared.into_iter().map(str::to_string));
+                self.check_usage(child, &shadowed, unused_namespaces);
             }
         }
     }
@@ -89,15 +103,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -134,8 +147,8 @@ mod tests {
         let rect_element = Element {
             name: "rect".to_string(),
             attributes: rect_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(rect_element)];
@@ -163,8 +176,8 @@ mod tests {
         let ns_element = Element {
             name: "svg:g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(ns_element)];
@@ -194,8 +207,8 @@ mod tests {
         let element = Element {
             name: "rect".to_string(),
             attributes: element_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(element)];
@@ -230,8 +243,8 @@ mod tests {
         let element = Element {
             name: "rect".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(element)];
@@ -286,22 +299,22 @@ mod tests {
         let deep_element = Element {
             name: "text".to_string(),
             attributes: deep_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let middle_element = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(deep_element)],
+            format: None,
         };
 
         let container_element = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(middle_element)],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(container_element)];
@@ -339,8 +352,8 @@ mod tests {
         let element = Element {
             name: "rect".to_string(),
             attributes: element_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(element)];
@@ -354,4 +367,23 @@ mod tests {
         assert!(!document.root.attributes.contains_key("xmlns:unused"));
         assert!(!document.root.attributes.contains_key("xmlns:alsounused"));
     }
+
+    #[test]
+    fn test_redeclared_prefix_does_not_keep_root_declaration() {
+        let mut document = crate::parser::parse_svg(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:a="http://example.com/a" xmlns:b="http://example.com/b">
+                <g xmlns:a="http://example.com/other" a:data="1" b:data="2"/>
+            </svg>"#,
+        )
+        .unwrap();
+
+        let mut plugin = RemoveUnusedNSPlugin;
+        plugin
+            .apply(&mut document, &PluginInfo::default(), None)
+            .unwrap();
+
+        // `a:data` resolves to the declaration on <g>, so the root's is unused
+        assert!(!document.root.has_attr("xmlns:a"));
+        assert!(document.root.has_attr("xmlns:b"));
+    }
 }
@@ -99,15 +99,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -130,8 +129,8 @@ mod tests {
         let defs_element = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs_element)];
@@ -159,15 +158,15 @@ mod tests {
         let gradient_element = Element {
             name: "linearGradient".to_string(),
             attributes: gradient_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let defs_element = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(gradient_element.clone())],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs_element)];
@@ -198,15 +197,15 @@ mod tests {
         let gradient_element = Element {
             name: "linearGradient".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let defs_element = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(gradient_element)],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs_element)];
@@ -231,15 +230,15 @@ mod tests {
         let style_element = Element {
             name: "style".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Text("rect { fill: red; }".to_string())],
+            format: None,
         };
 
         let defs_element = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(style_element.clone())],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs_element)];
@@ -272,29 +271,29 @@ mod tests {
         let useful_element = Element {
             name: "stop".to_string(),
             attributes: useful_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let useless_element = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(useful_element.clone())],
+            format: None,
         };
 
         let gradient_element = Element {
             name: "linearGradient".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(useless_element)],
+            format: None,
         };
 
         let defs_element = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(gradient_element)],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs_element)];
@@ -328,15 +327,15 @@ mod tests {
         let mask_with_id = Element {
             name: "mask".to_string(),
             attributes: mask_with_id_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let mask_without_id = Element {
             name: "mask".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![
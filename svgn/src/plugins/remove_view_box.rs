@@ -100,15 +100,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -312,8 +311,8 @@ mod tests {
         let pattern_element = Element {
             name: "pattern".to_string(),
             attributes: pattern_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(pattern_element)];
@@ -345,8 +344,8 @@ mod tests {
         let nested_svg = Element {
             name: "svg".to_string(),
             attributes: nested_svg_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(nested_svg)];
@@ -5,13 +5,9 @@
 //! This plugin removes the deprecated XLink namespace and converts XLink attributes
 //! to their SVG 2 equivalents where applicable. XLink was deprecated in SVG 2.
 
-use crate::ast::{Document, Element, Node};
+use crate::ast::{ns, Document, Element, NamespaceScope, Node};
 use crate::plugin::{Plugin, PluginInfo, PluginResult};
 use serde_json::Value;
-use std::collections::HashMap;
-
-/// XLink namespace URI
-const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
 
 /// Elements that use xlink:href but were deprecated in SVG 2
 const LEGACY_ELEMENTS: &[&str] = &["cursor", "filter", "font-face-uri", "glyphRef", "tref"];
@@ -44,14 +40,13 @@ impl Plugin for RemoveXlinkPlugin {
         let config = self.parse_config(params)?;
         let mut context = XlinkContext::new(config);
 
-        self.process_element(&mut document.root, &mut context);
+        self.process_element(&mut document.root, &NamespaceScope::new(), &mut context);
         Ok(())
     }
 }
 
 struct XlinkContext {
     config: RemoveXlinkConfig,
-    xlink_prefixes: Vec<String>,
     used_in_legacy: Vec<String>,
 }
 
@@ -59,7 +54,6 @@ impl XlinkContext {
     fn new(config: RemoveXlinkConfig) -> Self {
         Self {
             config,
-            xlink_prefixes: Vec::new(),
             used_in_legacy: Vec::new(),
         }
     }
@@ -80,32 +74,34 @@ impl RemoveXlinkPlugin {
         Ok(config)
     }
 
-    fn process_element(&self, element: &mut Element, context: &mut XlinkContext) {
-        // Collect xlink namespace prefixes
-        let mut current_xlink_prefixes = Vec::new();
-
-        for (key, value) in &element.attributes {
-            if key.starts_with("xmlns:") && value == XLINK_NAMESPACE {
-                let prefix = key.strip_prefix("xmlns:").unwrap();
-                current_xlink_prefixes.push(prefix.to_string());
-                context.xlink_prefixes.push(prefix.to_string());
-            }
-        }
+    fn process_element(
+        &self,
+        element: &mut Element,
+        parent_scope: &NamespaceScope,
+        context: &mut XlinkContext,
+    ) {
+        // Prefixes bound to the XLink namespace, here and on ancestors
+        let scope = parent_scope.enter(element);
+        let xlink_prefixes: Vec<String> =
+            scope.prefixes_for(ns::XLINK).map(str::to_string).collect();
+        let current_xlink_prefixes: Vec<String> = element
+            .namespace_declarations()
+            .filter(|(prefix, uri)| !prefix.is_empty() && *uri == ns::XLINK)
+            .map(|(prefix, _)| prefix.to_string())
+            .collect();
 
         // Handle xlink:href conversion
         let is_legacy = LEGACY_ELEMENTS.contains(&element.name.as_str());
 
         if is_legacy && !context.config.include_legacy {
             // Mark prefixes as used in legacy elements - check for any xlink attributes
-            let has_xlink_attrs = element.attributes.keys().any(|key| {
-                context
-                    .xlink_prefixes
-                    .iter()
-                    .any(|prefix| key.starts_with(&format!("{}:", prefix)))
-            });
+            let has_xlink_attrs = element
+                .attributes
+                .keys()
+                .any(|key| scope.attribute_name(key).in_namespace(ns::XLINK));
 
             if has_xlink_attrs {
-                for prefix in &context.xlink_prefixes {
+                for prefix in &xlink_prefixes {
                     if !context.used_in_legacy.contains(prefix) {
                         context.used_in_legacy.push(prefix.clone());
                     }
@@ -113,29 +109,25 @@ impl RemoveXlinkPlugin {
             }
         } else {
             // Convert xlink:href to href if no href exists
-            self.convert_href_attributes(element, &context.xlink_prefixes);
+            self.convert_href_attributes(element, &xlink_prefixes);
         }
 
         // Only convert other xlink attributes if not a legacy element or include_legacy is true
         if !is_legacy || context.config.include_legacy {
             // Handle xlink:show conversion to target
-            self.convert_show_attributes(element, &context.xlink_prefixes);
+            self.convert_show_attributes(element, &xlink_prefixes);
 
             // Handle xlink:title conversion to <title> element
-            self.convert_title_attributes(element, &context.xlink_prefixes);
+            self.convert_title_attributes(element, &xlink_prefixes);
 
             // Remove unused xlink attributes
-            self.remove_unused_xlink_attributes(
-                element,
-                &context.xlink_prefixes,
-                &context.used_in_legacy,
-            );
+            self.remove_unused_xlink_attributes(element, &xlink_prefixes, &context.used_in_legacy);
         }
 
         // Process children recursively
         for child in &mut element.children {
             if let Node::Element(ref mut elem) = child {
-                self.process_element(elem, context);
+                self.process_element(elem, &scope, context);
             }
         }
 
@@ -146,11 +138,6 @@ impl RemoveXlinkPlugin {
                 element.attributes.shift_remove(&xmlns_key);
             }
         }
-
-        // Remove processed prefixes from context
-        for prefix in &current_xlink_prefixes {
-            context.xlink_prefixes.retain(|p| p != prefix);
-        }
     }
 
     fn convert_href_attributes(&self, element: &mut Element, xlink_prefixes: &[String]) {
@@ -238,8 +225,8 @@ impl RemoveXlinkPlugin {
                     let title_element = Element {
                         name: "title".to_string(),
                         attributes: indexmap::IndexMap::new(),
-                        namespaces: HashMap::new(),
                         children: vec![Node::Text(title_value)],
+                        format: None,
                     };
                     element.children.insert(0, Node::Element(title_element));
                 }
@@ -282,15 +269,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -311,7 +297,7 @@ mod tests {
         document
             .root
             .attributes
-            .insert("xmlns:xlink".to_string(), XLINK_NAMESPACE.to_string());
+            .insert("xmlns:xlink".to_string(), ns::XLINK.to_string());
 
         // Add element with xlink:href
         let mut use_attrs = IndexMap::new();
@@ -320,8 +306,8 @@ mod tests {
         let use_element = Element {
             name: "use".to_string(),
             attributes: use_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(use_element)];
@@ -352,7 +338,7 @@ mod tests {
         document
             .root
             .attributes
-            .insert("xmlns:xlink".to_string(), XLINK_NAMESPACE.to_string());
+            .insert("xmlns:xlink".to_string(), ns::XLINK.to_string());
 
         let mut element_attrs = IndexMap::new();
         element_attrs.insert("href".to_string(), "#existing".to_string());
@@ -361,8 +347,8 @@ mod tests {
         let element = Element {
             name: "a".to_string(),
             attributes: element_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(element)];
@@ -387,7 +373,7 @@ mod tests {
         document
             .root
             .attributes
-            .insert("xmlns:xlink".to_string(), XLINK_NAMESPACE.to_string());
+            .insert("xmlns:xlink".to_string(), ns::XLINK.to_string());
 
         let mut element_attrs = IndexMap::new();
         element_attrs.insert("xlink:show".to_string(), "new".to_string());
@@ -395,8 +381,8 @@ mod tests {
         let element = Element {
             name: "a".to_string(),
             attributes: element_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(element)];
@@ -421,7 +407,7 @@ mod tests {
         document
             .root
             .attributes
-            .insert("xmlns:xlink".to_string(), XLINK_NAMESPACE.to_string());
+            .insert("xmlns:xlink".to_string(), ns::XLINK.to_string());
 
         let mut element_attrs = IndexMap::new();
         element_attrs.insert("xlink:title".to_string(), "Element title".to_string());
@@ -429,8 +415,8 @@ mod tests {
         let element = Element {
             name: "rect".to_string(),
             attributes: element_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(element)];
@@ -467,7 +453,7 @@ mod tests {
         document
             .root
             .attributes
-            .insert("xmlns:xlink".to_string(), XLINK_NAMESPACE.to_string());
+            .insert("xmlns:xlink".to_string(), ns::XLINK.to_string());
 
         let mut filter_attrs = IndexMap::new();
         filter_attrs.insert("xlink:href".to_string(), "#filter1".to_string());
@@ -475,8 +461,8 @@ mod tests {
         let filter_element = Element {
             name: "filter".to_string(),
             attributes: filter_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(filter_element)];
@@ -504,7 +490,7 @@ mod tests {
         document
             .root
             .attributes
-            .insert("xmlns:xlink".to_string(), XLINK_NAMESPACE.to_string());
+            .insert("xmlns:xlink".to_string(), ns::XLINK.to_string());
 
         let mut filter_attrs = IndexMap::new();
         filter_attrs.insert("xlink:href".to_string(), "#filter1".to_string());
@@ -512,8 +498,8 @@ mod tests {
         let filter_element = Element {
             name: "filter".to_string(),
             attributes: filter_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(filter_element)];
@@ -531,4 +517,29 @@ mod tests {
             panic!("Expected filter element");
         }
     }
+
+    #[test]
+    fn test_non_standard_xlink_prefix() {
+        let mut document = crate::parser::parse_svg(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:x="http://www.w3.org/1999/xlink" xmlns:y="http://example.com/y">
+                <use x:href="#a"/>
+                <use y:href="#b"/>
+            </svg>"##,
+        )
+        .unwrap();
+
+        let mut plugin = RemoveXlinkPlugin;
+        plugin
+            .apply(&mut document, &PluginInfo::default(), None)
+            .unwrap();
+
+        // Only the prefix bound to the XLink namespace is converted
+        let uses: Vec<&Element> = document.root.child_elements().collect();
+        assert_eq!(uses[0].attr("href"), Some(&"#a".to_string()));
+        assert!(!uses[0].has_attr("x:href"));
+        assert_eq!(uses[1].attr("y:href"), Some(&"#b".to_string()));
+        assert!(!uses[1].has_attr("href"));
+        assert!(!document.root.has_attr("xmlns:x"));
+        assert!(document.root.has_attr("xmlns:y"));
+    }
 }
@@ -54,15 +54,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -116,8 +115,8 @@ mod tests {
         let nested_svg = Element {
             name: "svg".to_string(),
             attributes: nested_svg_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(nested_svg)];
@@ -179,8 +178,8 @@ mod tests {
         let rect_element = Element {
             name: "rect".to_string(),
             attributes: rect_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(rect_element)];
@@ -243,15 +242,15 @@ mod tests {
         let inner_svg = Element {
             name: "svg".to_string(),
             attributes: inner_svg_attrs,
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         let g_element = Element {
             name: "g".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![Node::Element(inner_svg)],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(g_element)];
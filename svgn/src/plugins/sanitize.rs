@@ -0,0 +1,776 @@
+// this_file: svgn/src/plugins/sanitize.rs
+
+//! Plugin to sanitize untrusted SVG uploads
+//!
+//! Removes everything that can run script or load other resources when the
+//! SVG is displayed: `<script>`, event attributes, `<foreignObject>`,
+//! embedded XHTML documents, `href`s to other sites, `data:` URLs that
+//! aren't images, CSS `url()` and `@import`, animations that rewrite `href`s
+//! and DTD entity declarations. Elements and attributes are matched by
+//! namespace, so a prefix bound to the SVG namespace doesn't hide them.
+//! Every removal is recorded in
+//! [`DocumentMetadata::sanitize_report`](crate::ast::DocumentMetadata::sanitize_report).
+
+use crate::ast::{ns, Document, Element, NamespaceScope, Node};
+use crate::entities::parse_declarations;
+use crate::plugin::{Plugin, PluginError, PluginInfo, PluginResult};
+use cssparser::{ParseError, Parser, ParserInput, SourcePosition, Token};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Plugin to remove active and external content
+pub struct SanitizePlugin;
+
+/// `data:` URL media types kept by default
+///
+/// `image/svg+xml` is not included because an SVG can carry scripts itself.
+const DEFAULT_DATA_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/jpg",
+    "image/gif",
+    "image/webp",
+    "image/avif",
+    "image/bmp",
+];
+
+/// Configuration parameters for the plugin
+#[derive(Debug, Clone)]
+pub struct SanitizeParams {
+    /// Remove `<script>`, event attributes, `javascript:` URLs and XHTML
+    /// `<iframe>`, `<object>` and `<embed>` (default: true)
+    pub scripts: bool,
+    /// Remove `<foreignObject>` elements (default: true)
+    pub foreign_object: bool,
+    /// Remove `href`s with an absolute URL, unless its host is allowed
+    /// (default: true)
+    pub external_hrefs: bool,
+    /// Hosts whose URLs are kept; subdomains are allowed too
+    pub allowed_domains: Vec<String>,
+    /// Remove `data:` URLs whose media type isn't allowed (default: true)
+    pub data_urls: bool,
+    /// Media types of `data:` URLs that are kept
+    pub allowed_data_types: Vec<String>,
+    /// Neutralize `url()` and `@import` in styles (default: true)
+    pub css_urls: bool,
+    /// Remove `<animate>` and `<set>` that target `href` (default: true)
+    pub href_animations: bool,
+    /// Remove DOCTYPEs that declare entities (default: true)
+    pub entities: bool,
+}
+
+impl Default for SanitizeParams {
+    fn default() -> Self {
+        Self {
+            scripts: true,
+            foreign_object: true,
+            external_hrefs: true,
+            allowed_domains: Vec::new(),
+            data_urls: true,
+            allowed_data_types: DEFAULT_DATA_TYPES.iter().map(|t| t.to_string()).collect(),
+            css_urls: true,
+            href_animations: true,
+            entities: true,
+        }
+    }
+}
+
+impl SanitizeParams {
+    /// Parse parameters from JSON value
+    pub fn from_value(value: Option<&Value>) -> PluginResult<Self> {
+        let mut params = Self::default();
+
+        if let Some(Value::Object(map)) = value {
+            for (key, flag) in [
+                ("scripts", &mut params.scripts),
+                ("foreignObject", &mut params.foreign_object),
+                ("externalHrefs", &mut params.external_hrefs),
+                ("dataUrls", &mut params.data_urls),
+                ("cssUrls", &mut params.css_urls),
+                ("hrefAnimations", &mut params.href_animations),
+                ("entities", &mut params.entities),
+            ] {
+                if let Some(Value::Bool(value)) = map.get(key) {
+                    *flag = *value;
+                }
+            }
+            if let Some(domains) = map.get("allowedDomains") {
+                params.allowed_domains = string_list(domains, "allowedDomains")?
+                    .into_iter()
+                    .map(|domain| domain.trim_start_matches('.').to_ascii_lowercase())
+                    .collect();
+            }
+            if let Some(types) = map.get("allowedDataTypes") {
+                params.allowed_data_types = string_list(types, "allowedDataTypes")?
+                    .into_iter()
+                    .map(|media_type| media_type.to_ascii_lowercase())
+                    .collect();
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// What is wrong with a URL, regardless of which checks are enabled
+    fn url_problem(&self, url: &str) -> Option<RemovalReason> {
+        // Browsers ignore whitespace and control characters in URLs
+        let url: String = url
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+            .collect();
+        if url.is_empty() || url.starts_with('#') {
+            return None;
+        }
+
+        match url_scheme(&url).as_deref() {
+            Some("javascript" | "vbscript") => Some(RemovalReason::Script),
+            Some("data") => {
+                let media_type = url[5..]
+                    .split([';', ','])
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                (!self.allowed_data_types.contains(&media_type)).then_some(RemovalReason::DataUrl)
+            }
+            scheme => {
+                // Relative URLs stay on the site the SVG is served from;
+                // browsers read `\\` like `//` in URLs
+                let absolute = scheme.is_some()
+                    || url.starts_with(['/', '\\']) && url[1..].starts_with(['/', '\\']);
+                let allowed = !absolute
+                    || matches!(scheme, None | Some("http" | "https"))
+                        && url_host(&url).is_some_and(|host| self.is_allowed_host(&host));
+                (!allowed).then_some(RemovalReason::ExternalHref)
+            }
+        }
+    }
+
+    fn is_allowed_host(&self, host: &str) -> bool {
+        self.allowed_domains.iter().any(|domain| {
+            host == domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+    }
+
+    /// Whether `href`s with this problem are removed
+    fn removes(&self, reason: RemovalReason) -> bool {
+        match reason {
+            RemovalReason::Script => self.scripts,
+            RemovalReason::DataUrl => self.data_urls,
+            RemovalReason::ExternalHref => self.external_hrefs,
+            _ => true,
+        }
+    }
+}
+
+fn string_list(value: &Value, key: &str) -> PluginResult<Vec<String>> {
+    let invalid = || PluginError::InvalidConfig(format!("{} must be a list of strings", key));
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().map(str::to_string).ok_or_else(invalid))
+            .collect(),
+        _ => Err(invalid()),
+    }
+}
+
+/// Lowercased scheme of an absolute URL
+fn url_scheme(url: &str) -> Option<String> {
+    let (scheme, _) = url.split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then(|| scheme.to_ascii_lowercase())
+}
+
+/// Lowercased host of a URL with an authority (`scheme://host/...` or `//host/...`)
+fn url_host(url: &str) -> Option<String> {
+    let rest = match url.split_once("//") {
+        Some((scheme, rest)) if scheme.is_empty() || scheme.ends_with(':') => rest,
+        _ => return None,
+    };
+    let authority = rest.split(['/', '?', '#', '\\']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Why content was removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovalReason {
+    /// `<script>` element or `javascript:` URL
+    Script,
+    /// Event handler attribute such as `onclick`
+    EventHandler,
+    /// `<foreignObject>` element
+    ForeignObject,
+    /// XHTML `<iframe>`, `<object>` or `<embed>`
+    EmbeddedDocument,
+    /// `href` to another document
+    ExternalHref,
+    /// `data:` URL whose media type isn't allowed
+    DataUrl,
+    /// CSS `url()` that would load something, replaced with `none`
+    CssUrl,
+    /// CSS `@import` rule
+    CssImport,
+    /// `<animate>` or `<set>` that changes an `href`
+    HrefAnimation,
+    /// Entity declared in the DOCTYPE
+    EntityDeclaration,
+}
+
+impl fmt::Display for RemovalReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RemovalReason::Script => "script",
+            RemovalReason::EventHandler => "event handler",
+            RemovalReason::ForeignObject => "foreign object",
+            RemovalReason::EmbeddedDocument => "embedded document",
+            RemovalReason::ExternalHref => "external href",
+            RemovalReason::DataUrl => "data URL",
+            RemovalReason::CssUrl => "CSS url()",
+            RemovalReason::CssImport => "CSS @import",
+            RemovalReason::HrefAnimation => "href animation",
+            RemovalReason::EntityDeclaration => "entity declaration",
+        })
+    }
+}
+
+/// A single piece of removed content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Removal {
+    /// Why it was removed
+    pub reason: RemovalReason,
+    /// Removed element, or the element the content was removed from
+    pub element: String,
+    /// Removed or rewritten attribute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribute: Option<String>,
+    /// Removed value, such as a URL, CSS rule or entity name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl fmt::Display for Removal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: <{}>", self.reason, self.element)?;
+        if let Some(attribute) = &self.attribute {
+            write!(f, " {}", attribute)?;
+        }
+        if let Some(value) = &self.value {
+            write!(f, " {:?}", value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything the `sanitize` plugin removed from a document
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SanitizeReport {
+    /// Removals in document order
+    pub removed: Vec<Removal>,
+}
+
+impl Plugin for SanitizePlugin {
+    fn name(&self) -> &'static str {
+        "sanitize"
+    }
+
+    fn description(&self) -> &'static str {
+        "removes scripts, external references and other active content (disabled by default)"
+    }
+
+    fn apply(
+        &mut self,
+        document: &mut Document,
+        _plugin_info: &PluginInfo,
+        params: Option<&Value>,
+    ) -> PluginResult<()> {
+        let params = SanitizeParams::from_value(params)?;
+        let mut removed = Vec::new();
+
+        if params.entities {
+            remove_entity_declarations(document, &mut removed);
+        }
+        let scope = NamespaceScope::new().enter(&document.root);
+        sanitize_element(&mut document.root, &scope, &params, &mut removed);
+
+        document
+            .metadata
+            .sanitize_report
+            .get_or_insert_with(Default::default)
+            .removed
+            .extend(removed);
+        Ok(())
+    }
+
+    fn validate_params(&self, params: Option<&Value>) -> PluginResult<()> {
+        SanitizeParams::from_value(params)?;
+        Ok(())
+    }
+}
+
+/// Drop DOCTYPEs with entity declarations
+///
+/// References to entities that were left unexpanded no longer resolve, so
+/// they are written as plain text from then on.
+fn remove_entity_declarations(document: &mut Document, removed: &mut Vec<Removal>) {
+    let mut dropped = false;
+    document.prologue.retain(|node| {
+        let Node::DocType(doctype) = node else {
+            return true;
+        };
+        let mut names: Vec<String> = parse_declarations(doctype).into_keys().collect();
+        if names.is_empty() {
+            return true;
+        }
+        names.sort();
+        removed.extend(names.into_iter().map(|name| Removal {
+            reason: RemovalReason::EntityDeclaration,
+            element: "!DOCTYPE".to_string(),
+            attribute: None,
+            value: Some(name),
+        }));
+        dropped = true;
+        false
+    });
+
+    if dropped {
+        document.metadata.unexpanded_entities.clear();
+    }
+}
+
+/// Sanitize `element`, whose namespace declarations are included in `scope`
+fn sanitize_element(
+    element: &mut Element,
+    scope: &NamespaceScope,
+    params: &SanitizeParams,
+    removed: &mut Vec<Removal>,
+) {
+    sanitize_attributes(element, scope, params, removed);
+
+    if is_svg(scope, &element.name, "style") && params.css_urls {
+        for child in &mut element.children {
+            if let Node::Text(css) | Node::CData(css) = child {
+                *css = neutralize_css(css, params, "style", None, removed);
+            }
+        }
+    }
+
+    element.children.retain_mut(|child| match child {
+        Node::Element(child) => {
+            let scope = scope.enter(child);
+            match removed_element_reason(child, &scope, params) {
+                Some(reason) => {
+                    removed.push(Removal {
+                        reason,
+                        element: child.name.clone(),
+                        attribute: None,
+                        value: None,
+                    });
+                    false
+                }
+                None => {
+                    sanitize_element(child, &scope, params, removed);
+                    true
+                }
+            }
+        }
+        _ => true,
+    });
+}
+
+/// Whether `name` is the SVG element `local`
+///
+/// Elements without a namespace count as SVG, since browsers parse inline
+/// SVG into the SVG namespace whether it declares one or not.
+fn is_svg(scope: &NamespaceScope, name: &str, local: &str) -> bool {
+    let name = scope.element_name(name);
+    name.local == local && (name.uri.is_none() || name.in_namespace(ns::SVG))
+}
+
+/// Why a whole element is removed, if it is
+fn removed_element_reason(
+    element: &Element,
+    scope: &NamespaceScope,
+    params: &SanitizeParams,
+) -> Option<RemovalReason> {
+    let name = scope.element_name(&element.name);
+    if name.in_namespace(ns::XHTML) {
+        return match name.local.as_str() {
+            "script" if params.scripts => Some(RemovalReason::Script),
+            "iframe" | "object" | "embed" if params.scripts => {
+                Some(RemovalReason::EmbeddedDocument)
+            }
+            _ => None,
+        };
+    }
+    if !is_svg(scope, &element.name, &name.local) {
+        return None;
+    }
+
+    match name.local.as_str() {
+        "script" if params.scripts => Some(RemovalReason::Script),
+        "foreignObject" if params.foreign_object => Some(RemovalReason::ForeignObject),
+        "animate" | "set" if params.href_animations => {
+            let target = element.attr("attributeName")?;
+            let local = target.rsplit(':').next().unwrap_or_default();
+            (local.trim() == "href").then_some(RemovalReason::HrefAnimation)
+        }
+        _ => None,
+    }
+}
+
+fn sanitize_attributes(
+    element: &mut Element,
+    scope: &NamespaceScope,
+    params: &SanitizeParams,
+    removed: &mut Vec<Removal>,
+) {
+    let element_name = element.name.clone();
+
+    element.attributes.retain(|name, value| {
+        let qname = scope.attribute_name(name);
+        // Namespace declarations only bind prefixes
+        let is_declaration = qname.in_namespace(ns::XMLNS);
+        // No SVG attribute other than an event handler starts with `on`
+        let reason = if params.scripts
+            && !is_declaration
+            && qname.local.to_ascii_lowercase().starts_with("on")
+        {
+            Some(RemovalReason::EventHandler)
+        } else if !is_declaration && qname.local == "href" {
+            params
+                .url_problem(value)
+                .filter(|reason| params.removes(*reason))
+        } else {
+            // A `url()` always has a literal `(`, even with its name escaped
+            if params.css_urls && (name == "style" || value.contains('(')) {
+                *value = neutralize_css(value, params, &element_name, Some(name), removed);
+            }
+            None
+        };
+
+        match reason {
+            Some(reason) => {
+                removed.push(Removal {
+                    reason,
+                    element: element_name.clone(),
+                    attribute: Some(name.clone()),
+                    value: Some(value.clone()),
+                });
+                false
+            }
+            None => true,
+        }
+    });
+}
+
+/// Remove `@import` rules and replace `url()`s that would load something with `none`
+///
+/// The CSS is tokenized the way browsers read it, so escaped names such as
+/// `u\72l(` and `@im\70 ort` are found too.
+fn neutralize_css(
+    css: &str,
+    params: &SanitizeParams,
+    element: &str,
+    attribute: Option<&str>,
+    removed: &mut Vec<Removal>,
+) -> String {
+    let mut input = ParserInput::new(css);
+    let mut edits = Vec::new();
+    find_css_edits(&mut Parser::new(&mut input), params, &mut edits);
+
+    let mut output = String::with_capacity(css.len());
+    let mut copied = 0;
+    for edit in edits {
+        removed.push(Removal {
+            reason: edit.reason,
+            element: element.to_string(),
+            attribute: attribute.map(str::to_string),
+            value: Some(edit.value.trim().to_string()),
+        });
+        output.push_str(&css[copied..edit.start]);
+        output.push_str(edit.replacement);
+        copied = edit.end;
+    }
+    output.push_str(&css[copied..]);
+    output
+}
+
+/// A range of CSS to replace
+struct CssEdit {
+    start: usize,
+    end: usize,
+    replacement: &'static str,
+    reason: RemovalReason,
+    /// The rule or URL, for the report
+    value: String,
+}
+
+impl CssEdit {
+    /// Replace the `url()` from `start` to the parser's position with `none`
+    fn url(start: SourcePosition, parser: &Parser, url: String) -> Self {
+        Self {
+            start: start.byte_index(),
+            end: parser.position().byte_index(),
+            replacement: "none",
+            reason: RemovalReason::CssUrl,
+            value: url,
+        }
+    }
+}
+
+/// Collect the `@import` rules and loading `url()`s of a block, in order
+fn find_css_edits(parser: &mut Parser, params: &SanitizeParams, edits: &mut Vec<CssEdit>) {
+    // `/**/` inside an unquoted URL is part of it, but check the URL without
+    // comments too in case whatever reads the CSS next drops them
+    let loads = |url: &str| {
+        params.url_problem(url).is_some() || params.url_problem(&strip_css_comments(url)).is_some()
+    };
+
+    loop {
+        let start = parser.position();
+        let Ok(token) = parser.next_including_whitespace_and_comments() else {
+            break;
+        };
+        match token.clone() {
+            Token::AtKeyword(name) if name.eq_ignore_ascii_case("import") => {
+                // The rule runs to the next `;` or the end of the block
+                while let Ok(token) = parser.next_including_whitespace_and_comments() {
+                    if *token == Token::Semicolon {
+                        break;
+                    }
+                }
+                edits.push(CssEdit {
+                    start: start.byte_index(),
+                    end: parser.position().byte_index(),
+                    replacement: "",
+                    reason: RemovalReason::CssImport,
+                    value: parser.slice_from(start).to_string(),
+                });
+            }
+            // A malformed URL can't be checked, and browsers ignore it anyway
+            Token::BadUrl(url) => edits.push(CssEdit::url(start, parser, url.to_string())),
+            Token::UnquotedUrl(url) if loads(&url) => {
+                edits.push(CssEdit::url(start, parser, url.to_string()))
+            }
+            Token::Function(name) if name.eq_ignore_ascii_case("url") => {
+                let url = parser
+                    .parse_nested_block(|parser| {
+                        Ok::<_, ParseError<()>>(parser.expect_string()?.to_string())
+                    })
+                    .unwrap_or_default();
+                if loads(&url) {
+                    edits.push(CssEdit::url(start, parser, url));
+                }
+            }
+            Token::Function(_)
+            | Token::ParenthesisBlock
+            | Token::SquareBracketBlock
+            | Token::CurlyBracketBlock => {
+                let _ = parser.parse_nested_block(|parser| {
+                    find_css_edits(parser, params, edits);
+                    Ok::<_, ParseError<()>>(())
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+fn strip_css_comments(css: &str) -> String {
+    let mut output = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        output.push_str(&rest[..start]);
+        rest = rest[start + 2..]
+            .find("*/")
+            .map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::stringifier::stringify;
+    use serde_json::json;
+
+    fn sanitize(svg: &str, params: Option<Value>) -> (String, Vec<Removal>) {
+        let mut document = Parser::new().parse(svg).unwrap();
+        SanitizePlugin
+            .apply(&mut document, &PluginInfo::default(), params.as_ref())
+            .unwrap();
+        let removed = document.metadata.sanitize_report.take().unwrap().removed;
+        (stringify(&document).unwrap(), removed)
+    }
+
+    fn reasons(removed: &[Removal]) -> Vec<RemovalReason> {
+        removed.iter().map(|removal| removal.reason).collect()
+    }
+
+    #[test]
+    fn test_removes_active_elements() {
+        let (output, removed) = sanitize(
+            r##"<svg onload="run()"><script>run()</script><foreignObject><div/></foreignObject><a href="#x"><set attributeName="xlink:href" to="javascript:run()"/><animate attributeName="fill"/></a></svg>"##,
+            None,
+        );
+        assert_eq!(
+            output,
+            r##"<svg><a href="#x"><animate attributeName="fill"/></a></svg>"##
+        );
+        assert_eq!(
+            reasons(&removed),
+            vec![
+                RemovalReason::EventHandler,
+                RemovalReason::Script,
+                RemovalReason::ForeignObject,
+                RemovalReason::HrefAnimation,
+            ]
+        );
+        assert_eq!(
+            removed[0].to_string(),
+            r#"event handler: <svg> onload "run()""#
+        );
+    }
+
+    #[test]
+    fn test_matches_by_namespace() {
+        let (output, removed) = sanitize(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:s="http://www.w3.org/2000/svg" xmlns:h="http://www.w3.org/1999/xhtml" xmlns:x="http://www.w3.org/1999/xlink" xmlns:e="urn:example"><s:script>alert(1)</s:script><s:foreignObject><div/></s:foreignObject><s:set attributeName="href" to="javascript:run()"/><s:a x:href="javascript:run()" onclick="run()"/><h:script>run()</h:script><h:iframe src="https://evil.test"/><h:object/><h:embed/><e:script/></svg>"#,
+            None,
+        );
+        assert_eq!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:s="http://www.w3.org/2000/svg" xmlns:h="http://www.w3.org/1999/xhtml" xmlns:x="http://www.w3.org/1999/xlink" xmlns:e="urn:example"><s:a/><e:script/></svg>"#
+        );
+        assert_eq!(
+            reasons(&removed),
+            vec![
+                RemovalReason::Script,
+                RemovalReason::ForeignObject,
+                RemovalReason::HrefAnimation,
+                RemovalReason::Script,
+                RemovalReason::EventHandler,
+                RemovalReason::Script,
+                RemovalReason::EmbeddedDocument,
+                RemovalReason::EmbeddedDocument,
+                RemovalReason::EmbeddedDocument,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hrefs_and_allowed_domains() {
+        let svg = r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink"><a href="https://cdn.example.com/a"/><a href="//evil.test/a"/><a href="\\evil.test/a"/><use xlink:href="https://evil.test/sprite.svg#icon"/><a href="java&#9;script:run()"/><image href="data:image/png;base64,AA"/><image href="data:text/html,x"/><use xlink:href="icons/sprite.svg#icon"/><a href="/about"/></svg>"#;
+
+        let (output, removed) = sanitize(svg, Some(json!({"allowedDomains": ["example.com"]})));
+        assert!(output.contains(r#"<a href="https://cdn.example.com/a"/>"#));
+        assert!(output.contains(r#"<image href="data:image/png;base64,AA"/>"#));
+        // Relative URLs stay on the same site
+        assert!(output.contains(r#"<use xlink:href="icons/sprite.svg#icon"/><a href="/about"/>"#));
+        assert_eq!(
+            reasons(&removed),
+            vec![
+                RemovalReason::ExternalHref,
+                RemovalReason::ExternalHref,
+                RemovalReason::ExternalHref,
+                RemovalReason::Script,
+                RemovalReason::DataUrl,
+            ]
+        );
+
+        let (output, removed) = sanitize(svg, Some(json!({"externalHrefs": false})));
+        assert!(output.contains(r#"xlink:href="https://evil.test/sprite.svg#icon""#));
+        assert_eq!(
+            reasons(&removed),
+            vec![RemovalReason::Script, RemovalReason::DataUrl]
+        );
+    }
+
+    #[test]
+    fn test_neutralizes_css() {
+        let (output, removed) = sanitize(
+            r#"<svg><style>@import url(https://evil.test/a.css); .a { fill: url( "https://evil.test/x" ) } .b { fill: url(#g) }</style><rect style="fill:URL(data:text/html,x)" mask="url(//evil.test/m.svg#m)" clip-path="url(shapes.svg#c)"/></svg>"#,
+            None,
+        );
+        assert_eq!(
+            output,
+            r##"<svg><style> .a { fill: none } .b { fill: url(#g) }</style><rect style="fill:none" mask="none" clip-path="url(shapes.svg#c)"/></svg>"##
+        );
+        assert_eq!(
+            reasons(&removed),
+            vec![
+                RemovalReason::CssImport,
+                RemovalReason::CssUrl,
+                RemovalReason::CssUrl,
+                RemovalReason::CssUrl,
+            ]
+        );
+        assert_eq!(
+            removed[0].value.as_deref(),
+            Some("@import url(https://evil.test/a.css);")
+        );
+        assert_eq!(removed[1].value.as_deref(), Some("https://evil.test/x"));
+    }
+
+    #[test]
+    fn test_neutralizes_escaped_css() {
+        let (output, removed) = sanitize(
+            r#"<svg><style>@im\70 ort "https://evil.test/x.css";rect{fill:u\72l(https://evil.test/x)}circle{fill:url(/**/https://evil.test/y)}path{fill:url(/**/"https://evil.test/z")}</style><rect fill="\75 rl(https://evil.test/w)"/></svg>"#,
+            None,
+        );
+        assert_eq!(
+            output,
+            r#"<svg><style>rect{fill:none}circle{fill:none}path{fill:none}</style><rect fill="none"/></svg>"#
+        );
+        assert_eq!(
+            reasons(&removed),
+            vec![
+                RemovalReason::CssImport,
+                RemovalReason::CssUrl,
+                RemovalReason::CssUrl,
+                RemovalReason::CssUrl,
+                RemovalReason::CssUrl,
+            ]
+        );
+        assert_eq!(
+            removed[0].value.as_deref(),
+            Some(r#"@im\70 ort "https://evil.test/x.css";"#)
+        );
+        assert_eq!(removed[1].value.as_deref(), Some("https://evil.test/x"));
+        assert_eq!(removed[4].value.as_deref(), Some("https://evil.test/w"));
+    }
+
+    #[test]
+    fn test_drops_entity_declarations() {
+        let svg = r#"<!DOCTYPE svg [ <!ENTITY w "10"> <!ENTITY ext SYSTEM "file:///etc/passwd"> ]><svg width="&w;"><text>&ext;</text></svg>"#;
+        let (output, removed) = sanitize(svg, None);
+        assert_eq!(output, r#"<svg width="10"><text>&amp;ext;</text></svg>"#);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[0].value.as_deref(), Some("ext"));
+        assert_eq!(
+            removed[1].to_string(),
+            r#"entity declaration: <!DOCTYPE> "w""#
+        );
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let plugin = SanitizePlugin;
+        assert!(plugin
+            .validate_params(Some(&json!({"allowedDomains": "example.com"})))
+            .is_err());
+        assert!(plugin
+            .validate_params(Some(&json!({"allowedDomains": ["example.com"]})))
+            .is_ok());
+    }
+}
@@ -95,15 +95,14 @@ mod tests {
     use super::*;
     use crate::ast::{Document, Element, Node};
     use indexmap::IndexMap;
-    use std::collections::HashMap;
 
     fn create_test_document() -> Document {
         Document {
             root: Element {
                 name: "svg".to_string(),
                 attributes: IndexMap::new(),
-                namespaces: HashMap::new(),
                 children: vec![],
+                format: None,
             },
             ..Default::default()
         }
@@ -113,8 +112,8 @@ mod tests {
         Element {
             name: name.to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         }
     }
 
@@ -135,7 +134,6 @@ mod tests {
         let defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Element(create_element("pattern")),
                 Node::Element(create_element("linearGradient")),
@@ -144,6 +142,7 @@ mod tests {
                 Node::Element(create_element("pattern")),
                 Node::Element(create_element("linearGradient")),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs)];
@@ -189,13 +188,13 @@ mod tests {
         let defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Element(create_element("g")),
                 Node::Element(create_element("use")),
                 Node::Element(create_element("path")),
                 Node::Element(create_element("rect")),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs)];
@@ -232,13 +231,13 @@ mod tests {
         let defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Element(create_element("abc")),
                 Node::Element(create_element("xyz")),
                 Node::Element(create_element("def")),
                 Node::Element(create_element("mno")),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs)];
@@ -274,7 +273,6 @@ mod tests {
         let defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Comment(" Start ".to_string()),
                 Node::Element(create_element("pattern")),
@@ -282,6 +280,7 @@ mod tests {
                 Node::Element(create_element("mask")),
                 Node::Comment(" End ".to_string()),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs)];
@@ -310,22 +309,22 @@ mod tests {
         let inner_defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Element(create_element("rect")),
                 Node::Element(create_element("circle")),
             ],
+            format: None,
         };
 
         let outer_defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 Node::Element(create_element("path")),
                 Node::Element(inner_defs),
                 Node::Element(create_element("ellipse")),
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(outer_defs)];
@@ -381,8 +380,8 @@ mod tests {
         let defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs)];
@@ -406,7 +405,6 @@ mod tests {
         let defs = Element {
             name: "defs".to_string(),
             attributes: IndexMap::new(),
-            namespaces: HashMap::new(),
             children: vec![
                 // Mix of frequencies and lengths
                 Node::Element(create_element("a")), // freq=1, len=1
@@ -416,6 +414,7 @@ mod tests {
                 Node::Element(create_element("bb")), // freq=2, len=2
                 Node::Element(create_element("dddd")), // freq=1, len=4
             ],
+            format: None,
         };
 
         document.root.children = vec![Node::Element(defs)];
@@ -0,0 +1,364 @@
+// this_file: svgn/src/report.rs
+
+//! Machine-readable run reports
+//!
+//! A [`Report`] collects one [`FileReport`] per processed file and renders
+//! them as JSON, CSV or JUnit XML so that CI systems can consume the results
+//! of a batch run.
+
+use crate::optimizer::{OptimizationInfo, OptimizationResult};
+use crate::plugins::sanitize::Removal;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Supported report formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// A single JSON document with a summary and per-file entries
+    Json,
+    /// One CSV row per file
+    Csv,
+    /// JUnit XML with one test case per file
+    Junit,
+}
+
+/// Outcome of processing a single file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    /// The file was optimized (or would be, in a dry run)
+    Optimized,
+    /// Optimization did not change the file
+    Unchanged,
+    /// Check mode: the file on disk is not optimized
+    NotOptimized,
+    /// The file could not be read, parsed or written
+    Failed,
+}
+
+/// Report entry for a single file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    /// Input path
+    pub path: String,
+    /// Output path, if different from the input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Outcome
+    pub status: FileStatus,
+    /// Optimization statistics (absent for failed files)
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub info: Option<OptimizationInfo>,
+    /// Wall-clock processing time in seconds
+    pub duration_secs: f64,
+    /// Whether the result came from the cache
+    pub cache_hit: bool,
+    /// Non-fatal messages produced while optimizing
+    pub diagnostics: Vec<String>,
+    /// Content removed by the `sanitize` plugin
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<Removal>,
+    /// Error message for failed files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Aggregate numbers over all files in a report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportSummary {
+    pub files: usize,
+    pub optimized: usize,
+    pub unchanged: usize,
+    pub not_optimized: usize,
+    pub failed: usize,
+    pub original_size: usize,
+    pub optimized_size: usize,
+    pub duration_secs: f64,
+}
+
+/// A complete run report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report {
+    /// svgn version that produced the report
+    pub version: String,
+    /// Aggregated numbers
+    pub summary: ReportSummary,
+    /// Per-file entries, in processing order
+    pub files: Vec<FileReport>,
+}
+
+impl FileReport {
+    /// Create an entry for a successfully processed file
+    pub fn from_result(path: &str, result: &OptimizationResult, changed: bool) -> Self {
+        let mut diagnostics = Vec::new();
+        if let Some(error) = &result.error {
+            diagnostics.push(error.clone());
+        }
+        if result.info.size_reduction() < 0 {
+            diagnostics.push(format!(
+                "Optimized output is {} bytes larger than the input",
+                -result.info.size_reduction()
+            ));
+        }
+
+        Self {
+            path: path.to_string(),
+            output: None,
+            status: if changed {
+                FileStatus::Optimized
+            } else {
+                FileStatus::Unchanged
+            },
+            info: Some(result.info.clone()),
+            duration_secs: 0.0,
+            cache_hit: false,
+            diagnostics,
+            removed: result
+                .sanitize_report
+                .as_ref()
+                .map(|report| report.removed.clone())
+                .unwrap_or_default(),
+            error: None,
+        }
+    }
+
+    /// Create an entry for a file that failed to process
+    pub fn failed(path: &str, error: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            output: None,
+            status: FileStatus::Failed,
+            info: None,
+            duration_secs: 0.0,
+            cache_hit: false,
+            diagnostics: Vec::new(),
+            removed: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+impl Report {
+    /// Create a report from per-file entries
+    pub fn new(files: Vec<FileReport>) -> Self {
+        let mut summary = ReportSummary {
+            files: files.len(),
+            ..Default::default()
+        };
+
+        for file in &files {
+            match file.status {
+                FileStatus::Optimized => summary.optimized += 1,
+                FileStatus::Unchanged => summary.unchanged += 1,
+                FileStatus::NotOptimized => summary.not_optimized += 1,
+                FileStatus::Failed => summary.failed += 1,
+            }
+            if let Some(info) = &file.info {
+                summary.original_size += info.original_size;
+                summary.optimized_size += info.optimized_size;
+            }
+            summary.duration_secs += file.duration_secs;
+        }
+
+        Self {
+            version: crate::VERSION.to_string(),
+            summary,
+            files,
+        }
+    }
+
+    /// Render the report in the given format
+    pub fn render(&self, format: ReportFormat) -> Result<String, serde_json::Error> {
+        match format {
+            ReportFormat::Json => serde_json::to_string_pretty(self),
+            ReportFormat::Csv => Ok(self.to_csv()),
+            ReportFormat::Junit => Ok(self.to_junit()),
+        }
+    }
+
+    /// Render as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from(
+            "path,output,status,original_size,optimized_size,compression_ratio,passes,\
+             plugins_applied,compressed_size,brotli_size,duration_secs,cache_hit,diagnostics,error\n",
+        );
+
+        for file in &self.files {
+            let info = file.info.as_ref();
+            let number = |value: Option<usize>| value.map(|n| n.to_string()).unwrap_or_default();
+            let fields = [
+                csv_field(&file.path),
+                csv_field(file.output.as_deref().unwrap_or("")),
+                status_name(file.status).to_string(),
+                number(info.map(|info| info.original_size)),
+                number(info.map(|info| info.optimized_size)),
+                info.map(|info| format!("{:.4}", info.compression_ratio))
+                    .unwrap_or_default(),
+                number(info.map(|info| info.passes)),
+                number(info.map(|info| info.plugins_applied)),
+                number(info.and_then(|info| info.compressed_size)),
+                number(info.and_then(|info| info.brotli_size)),
+                format!("{:.6}", file.duration_secs),
+                file.cache_hit.to_string(),
+                csv_field(&file.diagnostics.join("; ")),
+                csv_field(file.error.as_deref().unwrap_or("")),
+            ];
+            output.push_str(&fields.join(","));
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Render as JUnit XML
+    ///
+    /// Each file is a test case; failed files are reported as errors and
+    /// files that are not optimized (check mode) as failures.
+    pub fn to_junit(&self) -> String {
+        let summary = &self.summary;
+        let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            output,
+            r#"<testsuites name="svgn" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+            summary.files, summary.not_optimized, summary.failed, summary.duration_secs
+        );
+        let _ = writeln!(
+            output,
+            r#"  <testsuite name="svgn" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+            summary.files, summary.not_optimized, summary.failed, summary.duration_secs
+        );
+
+        for file in &self.files {
+            let _ = write!(
+                output,
+                r#"    <testcase classname="svgn" name="{}" time="{:.3}""#,
+                xml_escape(&file.path),
+                file.duration_secs
+            );
+            match file.status {
+                FileStatus::Failed => {
+                    let message = file.error.as_deref().unwrap_or("");
+                    let _ = writeln!(
+                        output,
+                        ">\n      <error message=\"{}\"/>\n    </testcase>",
+                        xml_escape(message)
+                    );
+                }
+                FileStatus::NotOptimized => {
+                    let saved = file.info.as_ref().map_or(0, |info| info.size_reduction());
+                    let _ = writeln!(
+                        output,
+                        ">\n      <failure message=\"File is not optimized (would save {} bytes)\"/>\n    </testcase>",
+                        saved
+                    );
+                }
+                FileStatus::Optimized | FileStatus::Unchanged => {
+                    if file.diagnostics.is_empty() {
+                        output.push_str("/>\n");
+                    } else {
+                        let _ = writeln!(
+                            output,
+                            ">\n      <system-out>{}</system-out>\n    </testcase>",
+                            xml_escape(&file.diagnostics.join("\n"))
+                        );
+                    }
+                }
+            }
+        }
+
+        output.push_str("  </testsuite>\n</testsuites>\n");
+        output
+    }
+}
+
+fn status_name(status: FileStatus) -> &'static str {
+    match status {
+        FileStatus::Optimized => "optimized",
+        FileStatus::Unchanged => "unchanged",
+        FileStatus::NotOptimized => "not_optimized",
+        FileStatus::Failed => "failed",
+    }
+}
+
+/// Quote a CSV field when it contains separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> Report {
+        let result = OptimizationResult {
+            data: "<svg/>".to_string(),
+            info: OptimizationInfo::new(100, 60, 5, 1),
+            error: None,
+            modern: true,
+            compressed: None,
+            sanitize_report: None,
+        };
+        let mut not_optimized = FileReport::from_result("b.svg", &result, true);
+        not_optimized.status = FileStatus::NotOptimized;
+
+        Report::new(vec![
+            FileReport::from_result("a,1.svg", &result, true),
+            not_optimized,
+            FileReport::failed("c.svg", "Parse error: <bad> \"tag\""),
+        ])
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = sample_report().summary;
+        assert_eq!(summary.files, 3);
+        assert_eq!(summary.optimized, 1);
+        assert_eq!(summary.not_optimized, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.original_size, 200);
+        assert_eq!(summary.optimized_size, 120);
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&sample_report().render(ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["files"][0]["path"], "a,1.svg");
+        assert_eq!(json["files"][0]["status"], "optimized");
+        assert_eq!(json["files"][0]["optimized_size"], 60);
+        assert_eq!(json["files"][2]["status"], "failed");
+        assert!(json["files"][2].get("original_size").is_none());
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = sample_report().to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("path,output,status,"));
+        assert!(lines[1].starts_with("\"a,1.svg\",,optimized,100,60,0.4000,1,5,"));
+        assert!(lines[3].ends_with(",\"Parse error: <bad> \"\"tag\"\"\""));
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = sample_report().to_junit();
+        assert!(xml.contains(r#"tests="3" failures="1" errors="1""#));
+        assert!(xml.contains(r#"<testcase classname="svgn" name="a,1.svg" time="0.000"/>"#));
+        assert!(xml.contains("would save 40 bytes"));
+        assert!(xml.contains(r#"<error message="Parse error: &lt;bad&gt; &quot;tag&quot;"/>"#));
+    }
+}
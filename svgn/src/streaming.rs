@@ -0,0 +1,527 @@
+// this_file: svgn/src/streaming.rs
+
+//! Streaming optimization for very large SVGs
+//!
+//! [`optimize_stream`] reads XML events one at a time, runs the purely local
+//! plugins on each element as it arrives and writes the result straight out,
+//! so memory is bounded by the depth of the tree rather than the size of the
+//! file. Each plugin sees a small document holding the current node and its
+//! open ancestors (attributes only), which is all the context a local plugin
+//! looks at. Elements the plugins remove are skipped with their subtree.
+//!
+//! Plugins that need the whole tree (ID cleanup, group collapsing, style
+//! inlining and so on) are skipped and reported in [`StreamInfo`]. Output is
+//! always minified and written in a single pass, so `multipass`, `pretty`
+//! and `preserve_formatting` have no effect.
+
+use crate::ast::{Document, Element, Node};
+use crate::config::Config;
+use crate::entities::{EntityExpander, Replacement};
+use crate::optimizer::OptimizeResult;
+use crate::parser::{ParseError, Parser, TEXT_ELEMENTS};
+use crate::plugin::{create_default_registry, PluginConfig, PluginInfo, PluginRegistry};
+use crate::stringifier::Stringifier;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::io::{BufRead, Write};
+
+/// Plugins that only look at one node and its ancestors at a time
+pub const STREAMING_PLUGINS: &[&str] = &[
+    "cleanupAttrs",
+    "cleanupNumericValues",
+    "convertColors",
+    "convertPathData",
+    "removeComments",
+    "removeDoctype",
+    "removeMetadata",
+    "removeXMLProcInst",
+];
+
+/// Whether a plugin can run in streaming mode
+pub fn is_streamable(name: &str) -> bool {
+    STREAMING_PLUGINS.contains(&name)
+}
+
+/// Summary of a streaming run
+#[derive(Debug, Clone, Default)]
+pub struct StreamInfo {
+    /// Bytes read from the input
+    pub original_size: usize,
+    /// Bytes written to the output
+    pub optimized_size: usize,
+    /// Enabled plugins that were skipped because they need the whole tree
+    pub skipped_plugins: Vec<String>,
+}
+
+/// Optimize an SVG from `input` to `output` without building the full tree
+///
+/// The input must be UTF-8.
+pub fn optimize_stream<R: BufRead, W: Write>(
+    input: R,
+    output: W,
+    config: &Config,
+) -> OptimizeResult<StreamInfo> {
+    let mut registry = create_default_registry();
+    optimize_stream_with_registry(input, output, config, &mut registry)
+}
+
+/// Optimize an SVG from `input` to `output` with a custom plugin registry
+pub fn optimize_stream_with_registry<R: BufRead, W: Write>(
+    input: R,
+    output: W,
+    config: &Config,
+    registry: &mut PluginRegistry,
+) -> OptimizeResult<StreamInfo> {
+    let (plugins, skipped_plugins): (Vec<PluginConfig>, Vec<PluginConfig>) = config
+        .effective_plugins()
+        .iter()
+        .filter(|plugin| plugin.enabled)
+        .cloned()
+        .partition(|plugin| is_streamable(&plugin.name));
+
+    let mut stream = StreamOptimizer {
+        output,
+        stringifier: Stringifier::from_options(&config.js2svg)
+            .pretty(false)
+            .preserve_formatting(false),
+        registry,
+        plugins,
+        plugin_info: PluginInfo {
+            path: config.path.clone(),
+            multipass_count: 0,
+        },
+        preserve_whitespace: config.parser.preserve_whitespace,
+        entities: EntityExpander::new(
+            config.parser.entity_limits.clone(),
+            config.parser.expand_entities,
+        ),
+        entity_refs: 0,
+        ancestors: Vec::new(),
+        open_tag: false,
+        skip_depth: 0,
+        root_seen: false,
+        buffer: String::new(),
+        written: 0,
+    };
+
+    let mut reader = Reader::from_reader(input);
+    let original_size = stream.run(&mut reader)?;
+
+    if config.js2svg.final_newline && !stream.buffer.ends_with('\n') {
+        stream.stringifier.write_newline(&mut stream.buffer)?;
+    }
+    stream.flush()?;
+    stream.output.flush()?;
+
+    Ok(StreamInfo {
+        original_size,
+        optimized_size: stream.written,
+        skipped_plugins: skipped_plugins.into_iter().map(|p| p.name).collect(),
+    })
+}
+
+/// State of a streaming run
+struct StreamOptimizer<'a, W: Write> {
+    output: W,
+    stringifier: Stringifier,
+    registry: &'a mut PluginRegistry,
+    plugins: Vec<PluginConfig>,
+    plugin_info: PluginInfo,
+    preserve_whitespace: bool,
+    /// Entities declared in the DOCTYPE
+    entities: EntityExpander,
+    /// Number of unexpanded references the stringifier keeps as written
+    entity_refs: usize,
+    /// Open elements (attributes only), outermost first
+    ancestors: Vec<Element>,
+    /// Whether the last start tag is still missing its `>`, so that an
+    /// element without content can be self-closed
+    open_tag: bool,
+    /// Nesting depth inside a removed element (0 when not skipping)
+    skip_depth: usize,
+    /// Whether the root element has been seen
+    root_seen: bool,
+    /// Output not yet written
+    buffer: String,
+    /// Bytes written so far
+    written: usize,
+}
+
+impl<W: Write> StreamOptimizer<'_, W> {
+    /// Process every event, returning the number of bytes read
+    fn run<R: BufRead>(&mut self, reader: &mut Reader<R>) -> OptimizeResult<usize> {
+        let mut buf = Vec::new();
+
+        loop {
+            let event = reader.read_event_into(&mut buf).map_err(ParseError::from)?;
+            if self.skip_depth > 0 {
+                match event {
+                    Event::Start(_) => self.skip_depth += 1,
+                    Event::End(_) => self.skip_depth -= 1,
+                    Event::Eof => return Err(ParseError::UnexpectedEnd.into()),
+                    _ => {}
+                }
+                buf.clear();
+                continue;
+            }
+
+            match event {
+                Event::Start(ref e) => self.start_element(e, false)?,
+                Event::Empty(ref e) => self.start_element(e, true)?,
+                Event::End(_) => self.end_element()?,
+                Event::Text(ref e) => {
+                    let text = std::str::from_utf8(e.as_ref()).map_err(ParseError::from)?;
+                    let keep_whitespace = self.preserve_whitespace
+                        || self
+                            .ancestors
+                            .last()
+                            .is_some_and(|parent| TEXT_ELEMENTS.contains(parent.name.as_str()));
+                    // Text is passed through still escaped
+                    if !self.ancestors.is_empty() && (keep_whitespace || !text.trim().is_empty()) {
+                        self.close_open_tag();
+                        self.buffer.push_str(text);
+                    }
+                }
+                Event::GeneralRef(ref e) => {
+                    if !self.ancestors.is_empty() {
+                        let name = std::str::from_utf8(e.as_ref()).map_err(ParseError::from)?;
+                        self.general_ref(name)?;
+                    }
+                }
+                Event::CData(ref e) => {
+                    let cdata = std::str::from_utf8(e.as_ref()).map_err(ParseError::from)?;
+                    self.close_open_tag();
+                    self.buffer.push_str("<![CDATA[");
+                    self.buffer.push_str(cdata);
+                    self.buffer.push_str("]]>");
+                }
+                Event::Comment(ref e) => {
+                    let comment = std::str::from_utf8(e.as_ref()).map_err(ParseError::from)?;
+                    self.write_node(Node::Comment(comment.to_string()))?;
+                }
+                Event::PI(ref e) => {
+                    let pi_data = std::str::from_utf8(e.as_ref()).map_err(ParseError::from)?;
+                    let (target, data) = pi_data.split_once(' ').unwrap_or((pi_data, ""));
+                    self.write_node(Node::ProcessingInstruction {
+                        target: target.to_string(),
+                        data: data.to_string(),
+                    })?;
+                }
+                Event::DocType(ref e) => {
+                    let doctype = std::str::from_utf8(e.as_ref()).map_err(ParseError::from)?;
+                    self.entities.declare(doctype);
+                    if !self.root_seen {
+                        self.write_node(Node::DocType(doctype.to_string()))?;
+                    }
+                }
+                Event::Decl(ref e) => {
+                    let mut document = Document::new();
+                    if let Ok(version) = e.version() {
+                        document.metadata.version =
+                            Some(String::from_utf8_lossy(&version).to_string());
+                    }
+                    if let Some(Ok(encoding)) = e.encoding() {
+                        document.metadata.encoding =
+                            Some(String::from_utf8_lossy(&encoding).to_string());
+                    }
+                    self.registry
+                        .apply_plugins(&mut document, &self.plugins, &self.plugin_info)?;
+                    if self
+                        .stringifier
+                        .write_declaration(&document, &mut self.buffer)?
+                    {
+                        self.stringifier.write_newline(&mut self.buffer)?;
+                    }
+                }
+                Event::Eof => break,
+            }
+
+            buf.clear();
+            self.flush()?;
+        }
+
+        if !self.ancestors.is_empty() {
+            return Err(ParseError::UnexpectedEnd.into());
+        }
+        if !self.root_seen {
+            return Err(ParseError::StructureError("No root element found".to_string()).into());
+        }
+
+        Ok(reader.buffer_position() as usize)
+    }
+
+    /// Optimize and write a start tag, or skip the element if a plugin removed it
+    fn start_element(&mut self, start: &BytesStart, empty: bool) -> OptimizeResult<()> {
+        let element = element_from_start(start, &mut self.entities)?;
+        self.sync_entity_refs();
+        let kept = self
+            .apply_plugins(Node::Element(element))?
+            .into_iter()
+            .find_map(|node| match node {
+                Node::Element(element) => Some(element),
+                _ => None,
+            });
+
+        let Some(mut element) = kept else {
+            if !empty {
+                self.skip_depth = 1;
+            }
+            return Ok(());
+        };
+
+        self.close_open_tag();
+        self.root_seen = true;
+        self.buffer.push('<');
+        self.buffer.push_str(&element.name);
+        self.stringifier
+            .write_attributes(&element, &mut self.buffer)?;
+
+        if empty {
+            self.stringifier
+                .close_empty_element(&element, &mut self.buffer)?;
+        } else {
+            element.children.clear();
+            self.ancestors.push(element);
+            self.open_tag = true;
+        }
+        Ok(())
+    }
+
+    /// Write the end tag of the innermost open element
+    fn end_element(&mut self) -> OptimizeResult<()> {
+        let element = self.ancestors.pop().ok_or(ParseError::UnexpectedEnd)?;
+        if self.open_tag {
+            self.open_tag = false;
+            self.stringifier
+                .close_empty_element(&element, &mut self.buffer)?;
+        } else {
+            self.buffer.push_str("</");
+            self.buffer.push_str(&element.name);
+            self.buffer.push('>');
+        }
+        Ok(())
+    }
+
+    /// Write the expansion of an entity reference in element content
+    fn general_ref(&mut self, name: &str) -> OptimizeResult<()> {
+        match self.entities.resolve(name).map_err(ParseError::from)? {
+            Replacement::Text(text) => {
+                self.close_open_tag();
+                let escaped = self.stringifier.escape_text(&text);
+                self.buffer.push_str(&escaped);
+            }
+            Replacement::Unexpanded => {
+                self.close_open_tag();
+                self.buffer.push('&');
+                self.buffer.push_str(name);
+                self.buffer.push(';');
+            }
+            Replacement::Markup(markup) => {
+                let nodes = Parser::new()
+                    .preserve_whitespace(self.preserve_whitespace)
+                    .preserve_comments(true)
+                    .parse_fragment(&markup, &mut self.entities);
+                self.entities.leave();
+                self.sync_entity_refs();
+                for node in nodes? {
+                    self.write_node(node)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Tell the stringifier about references the expander left as written
+    fn sync_entity_refs(&mut self) {
+        let unexpanded = self.entities.unexpanded();
+        if unexpanded.len() != self.entity_refs {
+            self.entity_refs = unexpanded.len();
+            self.stringifier = self.stringifier.clone().entity_refs(unexpanded.clone());
+        }
+    }
+
+    /// Run the plugins on a comment, PI or DOCTYPE and write what remains
+    fn write_node(&mut self, node: Node) -> OptimizeResult<()> {
+        for node in self.apply_plugins(node)? {
+            self.close_open_tag();
+            self.stringifier
+                .stringify_node(&node, &mut self.buffer, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Run the plugins on `node` in the context of its open ancestors
+    ///
+    /// Returns the nodes left in its place.
+    fn apply_plugins(&mut self, node: Node) -> OptimizeResult<Vec<Node>> {
+        let mut document = Document::new();
+        let is_element = node.is_element();
+        // References that will stay in the output need the DOCTYPE
+        document.metadata.unexpanded_entities = self
+            .entities
+            .unexpanded()
+            .iter()
+            .map(String::as_str)
+            .chain(self.entities.unexpandable())
+            .map(str::to_string)
+            .collect();
+
+        if self.ancestors.is_empty() {
+            match node {
+                Node::Element(element) => document.root = element,
+                node if self.root_seen => document.epilogue.push(node),
+                node => document.prologue.push(node),
+            }
+        } else {
+            let mut chain = vec![node];
+            for ancestor in self.ancestors.iter().rev() {
+                let mut parent = ancestor.clone();
+                parent.children = chain;
+                chain = vec![Node::Element(parent)];
+            }
+            if let Some(Node::Element(root)) = chain.pop() {
+                document.root = root;
+            }
+        }
+
+        self.registry
+            .apply_plugins(&mut document, &self.plugins, &self.plugin_info)?;
+
+        if self.ancestors.is_empty() {
+            return Ok(if is_element {
+                vec![Node::Element(document.root)]
+            } else if self.root_seen {
+                document.epilogue
+            } else {
+                document.prologue
+            });
+        }
+
+        // Walk back down the ancestor chain to the node's parent
+        let mut parent = document.root;
+        for _ in 1..self.ancestors.len() {
+            match parent.children.into_iter().find_map(|child| match child {
+                Node::Element(element) => Some(element),
+                _ => None,
+            }) {
+                Some(element) => parent = element,
+                None => return Ok(Vec::new()),
+            }
+        }
+        Ok(parent.children)
+    }
+
+    /// Write the `>` of a start tag that turned out to have content
+    fn close_open_tag(&mut self) {
+        if self.open_tag {
+            self.buffer.push('>');
+            self.open_tag = false;
+        }
+    }
+
+    /// Write buffered output
+    fn flush(&mut self) -> OptimizeResult<()> {
+        self.output.write_all(self.buffer.as_bytes())?;
+        self.written += self.buffer.len();
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+/// Build an element (without children) from a start tag
+fn element_from_start(
+    start: &BytesStart,
+    entities: &mut EntityExpander,
+) -> OptimizeResult<Element> {
+    let qname = start.name();
+    let name = std::str::from_utf8(qname.as_ref()).map_err(ParseError::from)?;
+    let mut element = Element::new(name);
+
+    for attr in start.attributes() {
+        let attr =
+            attr.map_err(|e| ParseError::AttrError(format!("Attribute parsing error: {}", e)))?;
+        let key = std::str::from_utf8(attr.key.as_ref()).map_err(ParseError::from)?;
+        let raw = std::str::from_utf8(&attr.value).map_err(ParseError::from)?;
+        let value = entities.expand(raw).map_err(ParseError::from)?;
+        element.set_attr(key.to_string(), value);
+    }
+
+    Ok(element)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer::optimize_with_config;
+
+    fn stream(input: &str, config: &Config) -> (String, StreamInfo) {
+        let mut output = Vec::new();
+        let info = optimize_stream(input.as_bytes(), &mut output, config).unwrap();
+        (String::from_utf8(output).unwrap(), info)
+    }
+
+    #[test]
+    fn test_stream_matches_tree_optimizer_for_local_plugins() {
+        let input = r##"<?xml version="1.0" encoding="UTF-8"?>
+<!-- exported -->
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100.000 100">
+    <metadata><rdf>big</rdf></metadata>
+    <g fill="#FF0000">
+        <path d="M 10.00000 10.00000 L 20 20 L 30 30"/>
+        <text x="1.50000">a  b</text>
+    </g>
+    <rect width="10" height="10"></rect>
+</svg>"##;
+        let mut config = Config::default();
+        for name in STREAMING_PLUGINS {
+            config.add_plugin(PluginConfig::new(name.to_string()));
+        }
+
+        let (streamed, info) = stream(input, &config);
+        let tree = optimize_with_config(input, config).unwrap();
+
+        assert_eq!(streamed, tree.data);
+        assert_eq!(info.original_size, input.len());
+        assert_eq!(info.optimized_size, streamed.len());
+        assert!(info.skipped_plugins.is_empty());
+    }
+
+    #[test]
+    fn test_stream_skips_whole_tree_plugins() {
+        let mut config = Config::default();
+        config.add_plugin(PluginConfig::new("cleanupIds".to_string()));
+        config.add_plugin(PluginConfig::new("removeComments".to_string()));
+
+        let (streamed, info) = stream(r#"<svg><!-- c --><g id="unused"/></svg>"#, &config);
+        assert_eq!(streamed, r#"<svg><g id="unused"/></svg>"#);
+        assert_eq!(info.skipped_plugins, vec!["cleanupIds".to_string()]);
+    }
+
+    #[test]
+    fn test_stream_reports_unclosed_elements() {
+        let mut output = Vec::new();
+        let result = optimize_stream(&b"<svg><g>"[..], &mut output, &Config::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stream_expands_entities() {
+        let input = r##"<!DOCTYPE svg [ <!ENTITY ns_svg "http://www.w3.org/2000/svg"> <!ENTITY red "#f00"> ]>
+<svg xmlns="&ns_svg;"><text fill="&red;">&red; &amp; &lt;</text></svg>"##;
+        let mut config = Config::default();
+        config.add_plugin(PluginConfig::new("removeDoctype".to_string()));
+
+        let (streamed, _) = stream(input, &config);
+        assert_eq!(
+            streamed,
+            r##"<svg xmlns="http://www.w3.org/2000/svg"><text fill="#f00">#f00 &amp; &lt;</text></svg>"##
+        );
+
+        config.parser.expand_entities = false;
+        let (streamed, _) = stream(input, &config);
+        assert!(streamed.starts_with("<!DOCTYPE svg ["));
+        assert!(streamed.ends_with(
+            r#"<svg xmlns="&ns_svg;"><text fill="&red;">&red; &amp; &lt;</text></svg>"#
+        ));
+    }
+}
@@ -5,8 +5,9 @@
 //! This module provides functionality to convert our AST back into optimized
 //! SVG strings with configurable formatting options.
 
-use crate::ast::{Document, Element, Node};
-use crate::config::{LineEnding, QuoteAttrsStyle};
+use crate::ast::{Document, Element, ElementFormat, Node};
+use crate::config::{Js2SvgOptions, LineEnding, QuoteAttrsStyle, XmlDeclaration};
+use std::collections::BTreeSet;
 use std::fmt::Write;
 use thiserror::Error;
 
@@ -23,6 +24,7 @@ pub enum StringifyError {
 pub type StringifyResult<T> = Result<T, StringifyError>;
 
 /// SVG stringifier with configurable output options
+#[derive(Clone)]
 pub struct Stringifier {
     /// Pretty-print the output
     pretty: bool,
@@ -39,6 +41,26 @@ pub struct Stringifier {
     eol: LineEnding,
     /// Add final newline
     final_newline: bool,
+    /// Re-emit recorded source formatting instead of reformatting
+    preserve_formatting: bool,
+    /// Whether to keep, strip or force the XML declaration
+    xml_declaration: XmlDeclaration,
+    /// Text between attribute name and value, overriding `quote_attrs`
+    attr_start: Option<String>,
+    /// Text after attribute value, overriding `quote_attrs`
+    attr_end: Option<String>,
+    /// Text written before each text node
+    text_start: String,
+    /// Text written after each text node
+    text_end: String,
+    /// Start of the DOCTYPE declaration
+    doctype_start: String,
+    /// End of the DOCTYPE declaration
+    doctype_end: String,
+    /// Emit SVG for inlining into HTML5
+    html: bool,
+    /// Entity references to write as-is instead of escaping their `&`
+    entity_refs: BTreeSet<String>,
 }
 
 impl Stringifier {
@@ -52,9 +74,36 @@ impl Stringifier {
             quote_attrs: QuoteAttrsStyle::Auto,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            xml_declaration: XmlDeclaration::Keep,
+            attr_start: None,
+            attr_end: None,
+            text_start: String::new(),
+            text_end: String::new(),
+            doctype_start: "<!DOCTYPE".to_string(),
+            doctype_end: ">".to_string(),
+            html: false,
+            entity_refs: BTreeSet::new(),
         }
     }
 
+    /// Create a stringifier configured from `js2svg` options
+    pub fn from_options(options: &Js2SvgOptions) -> Self {
+        Self::new()
+            .pretty(options.pretty)
+            .indent(options.indent)
+            .self_closing(options.self_closing)
+            .quote_attrs(options.quote_attrs.clone())
+            .eol(options.eol)
+            .final_newline(options.final_newline)
+            .preserve_formatting(options.preserve_formatting)
+            .xml_declaration(options.xml_declaration)
+            .attr_delimiters(options.attr_start.clone(), options.attr_end.clone())
+            .text_delimiters(options.text_start.clone(), options.text_end.clone())
+            .doctype_delimiters(options.doctype_start.clone(), options.doctype_end.clone())
+            .html(options.html)
+    }
+
     /// Set pretty-printing
     pub fn pretty(mut self, pretty: bool) -> Self {
         self.pretty = pretty;
@@ -97,23 +146,82 @@ impl Stringifier {
         self
     }
 
+    /// Set formatting preservation
+    ///
+    /// Elements parsed with formatting preservation are written back exactly
+    /// as they appeared in the source unless a plugin changed them; changed
+    /// elements keep their attribute spacing and quote style. Overrides
+    /// pretty-printing.
+    pub fn preserve_formatting(mut self, preserve: bool) -> Self {
+        self.preserve_formatting = preserve;
+        self
+    }
+
+    /// Set XML declaration handling
+    pub fn xml_declaration(mut self, mode: XmlDeclaration) -> Self {
+        self.xml_declaration = mode;
+        self
+    }
+
+    /// Set the text written around attribute values
+    ///
+    /// Either side left as `None` falls back to the `quote_attrs` style.
+    pub fn attr_delimiters(mut self, start: Option<String>, end: Option<String>) -> Self {
+        self.attr_start = start;
+        self.attr_end = end;
+        self
+    }
+
+    /// Set the text written around text nodes
+    pub fn text_delimiters(mut self, start: String, end: String) -> Self {
+        self.text_start = start;
+        self.text_end = end;
+        self
+    }
+
+    /// Set the text written around DOCTYPE declarations
+    pub fn doctype_delimiters(mut self, start: String, end: String) -> Self {
+        self.doctype_start = start;
+        self.doctype_end = end;
+        self
+    }
+
+    /// Set HTML5 inline output
+    ///
+    /// Drops the XML declaration, DOCTYPE and `xmlns` attributes, which the
+    /// HTML parser supplies itself, and writes every attribute with an
+    /// explicit value so empty attributes never read as boolean ones. When
+    /// preserving formatting, start tags with `xmlns` attributes are
+    /// rewritten without them and everything else is kept as written.
+    pub fn html(mut self, html: bool) -> Self {
+        self.html = html;
+        self
+    }
+
+    /// Keep references to these entities unescaped in text and attributes
+    pub fn entity_refs(mut self, names: BTreeSet<String>) -> Self {
+        self.entity_refs = names;
+        self
+    }
+
     /// Convert a document to an SVG string
     pub fn stringify(&self, document: &Document) -> StringifyResult<String> {
+        // References the parser left unexpanded must stay references
+        let unexpanded = &document.metadata.unexpanded_entities;
+        if !unexpanded.is_subset(&self.entity_refs) {
+            let names = self.entity_refs.union(unexpanded).cloned().collect();
+            return self.clone().entity_refs(names).stringify(document);
+        }
+
+        if self.preserve_formatting {
+            return self.stringify_preserved(document);
+        }
+
         let mut output = String::new();
 
         // Add XML declaration if needed
-        if let Some(version) = &document.metadata.version {
-            if let Some(encoding) = &document.metadata.encoding {
-                write!(
-                    output,
-                    r#"<?xml version="{}" encoding="{}"?>"#,
-                    version, encoding
-                )?;
-                self.write_newline(&mut output)?;
-            } else {
-                write!(output, r#"<?xml version="{}"?>"#, version)?;
-                self.write_newline(&mut output)?;
-            }
+        if self.write_declaration(document, &mut output)? {
+            self.write_newline(&mut output)?;
         }
 
         // Add prologue nodes (comments, PIs before root element)
@@ -147,8 +255,193 @@ impl Stringifier {
         Ok(output)
     }
 
+    /// Convert a document to an SVG string, keeping the source formatting
+    fn stringify_preserved(&self, document: &Document) -> StringifyResult<String> {
+        let mut output = String::new();
+
+        let is_utf8 = document
+            .metadata
+            .encoding
+            .as_deref()
+            .is_none_or(|encoding| encoding.eq_ignore_ascii_case("utf-8"));
+        match &document.metadata.declaration {
+            Some(declaration)
+                if is_utf8
+                    && document.metadata.version.is_some()
+                    && self.xml_declaration != XmlDeclaration::Strip
+                    && !self.html =>
+            {
+                output.push_str(declaration)
+            }
+            _ => {
+                self.write_declaration(document, &mut output)?;
+            }
+        }
+
+        // Whitespace between prologue nodes is kept as text nodes; drop the
+        // runs left behind when a plugin removed the node they separated
+        let mut previous_whitespace = output.is_empty();
+        for node in &document.prologue {
+            if let Node::Text(text) = node {
+                if !previous_whitespace {
+                    output.push_str(text);
+                }
+                previous_whitespace = true;
+            } else {
+                self.stringify_node(node, &mut output, 0)?;
+                previous_whitespace = false;
+            }
+        }
+
+        self.stringify_preserved_element(&document.root, &mut output)?;
+
+        previous_whitespace = false;
+        for node in &document.epilogue {
+            if let Node::Text(text) = node {
+                if !previous_whitespace {
+                    output.push_str(text);
+                }
+                previous_whitespace = true;
+            } else {
+                self.stringify_node(node, &mut output, 0)?;
+                previous_whitespace = false;
+            }
+        }
+
+        if self.final_newline && !self.ends_with_newline(&output) {
+            self.write_newline(&mut output)?;
+        }
+
+        Ok(output)
+    }
+
+    /// Stringify an element, keeping its recorded source formatting
+    fn stringify_preserved_element(
+        &self,
+        element: &Element,
+        output: &mut String,
+    ) -> StringifyResult<()> {
+        let format = element.format.as_deref();
+        let self_close = element.children.is_empty()
+            && format.map_or(self.self_closing, ElementFormat::is_self_closing);
+        let omits_attributes = element
+            .attributes
+            .keys()
+            .any(|name| self.omits_attribute(name));
+
+        match format {
+            Some(format) if format.matches(element) && !omits_attributes => {
+                let start_tag = format.start_tag.as_str();
+                match start_tag.strip_suffix("/>") {
+                    // Written as `<name/>` but has gained children
+                    Some(open) if !self_close => write!(output, "{}>", open)?,
+                    _ => output.push_str(start_tag),
+                }
+            }
+            Some(format) => {
+                write!(output, "<{}", element.name)?;
+                for (name, value) in &element.attributes {
+                    if self.omits_attribute(name) {
+                        continue;
+                    }
+                    match format.attributes.iter().find(|attr| &attr.name == name) {
+                        Some(attr) if &attr.value == value => {
+                            write!(output, "{}{}", attr.leading, attr.source)?;
+                        }
+                        Some(attr) => {
+                            let quote = attr.quote();
+                            write!(
+                                output,
+                                "{}{}={}{}{}",
+                                attr.leading,
+                                name,
+                                quote,
+                                self.escape_attr_value(value, Some(quote)),
+                                quote
+                            )?;
+                        }
+                        None => self.write_attribute(name, value, output)?,
+                    }
+                }
+                output.push_str(&format.trailing);
+                output.push_str(if self_close { "/>" } else { ">" });
+            }
+            None => {
+                write!(output, "<{}", element.name)?;
+                self.write_attributes(element, output)?;
+                output.push_str(if self_close { "/>" } else { ">" });
+            }
+        }
+
+        if self_close {
+            return Ok(());
+        }
+
+        let is_whitespace =
+            |node: Option<&Node>| matches!(node, Some(Node::Text(text)) if text.trim().is_empty());
+        for (index, child) in element.children.iter().enumerate() {
+            match child {
+                Node::Element(child_element) => {
+                    self.stringify_preserved_element(child_element, output)?;
+                }
+                Node::Text(text) => {
+                    // Collapse the whitespace runs left around removed elements
+                    if text.trim().is_empty() && is_whitespace(element.children.get(index + 1)) {
+                        continue;
+                    }
+                    match format.and_then(|format| format.text_source(text)) {
+                        Some(source) => output.push_str(source),
+                        None => output.push_str(&self.escape_text(text)),
+                    }
+                }
+                Node::CData(cdata) => {
+                    write!(output, "<![CDATA[{}]]>", cdata)?;
+                }
+                Node::DocType(_) => {}
+                _ => self.stringify_node(child, output, 0)?,
+            }
+        }
+
+        match format {
+            Some(format) if format.name == element.name && format.end_tag.is_some() => {
+                output.push_str(format.end_tag.as_deref().unwrap_or_default());
+            }
+            _ => write!(output, "</{}>", element.name)?,
+        }
+
+        Ok(())
+    }
+
+    /// Write the XML declaration, returning whether one was written
+    ///
+    /// The output is always UTF-8, whatever encoding the input was
+    /// transcoded from.
+    pub(crate) fn write_declaration(
+        &self,
+        document: &Document,
+        output: &mut String,
+    ) -> StringifyResult<bool> {
+        let metadata = &document.metadata;
+        match (self.xml_declaration, &metadata.version) {
+            _ if self.html => Ok(false),
+            (XmlDeclaration::Strip, _) | (XmlDeclaration::Keep, None) => Ok(false),
+            (XmlDeclaration::Keep, Some(version)) if metadata.encoding.is_none() => {
+                write!(output, r#"<?xml version="{}"?>"#, version)?;
+                Ok(true)
+            }
+            (_, version) => {
+                write!(
+                    output,
+                    r#"<?xml version="{}" encoding="UTF-8"?>"#,
+                    version.as_deref().unwrap_or("1.0")
+                )?;
+                Ok(true)
+            }
+        }
+    }
+
     /// Write a newline with the configured line ending
-    fn write_newline(&self, output: &mut String) -> StringifyResult<()> {
+    pub(crate) fn write_newline(&self, output: &mut String) -> StringifyResult<()> {
         output.push_str(self.eol.as_str());
         Ok(())
     }
@@ -181,11 +474,7 @@ impl Stringifier {
             element.children.is_empty() || (self.pretty && element.is_whitespace_only());
 
         if is_effectively_empty {
-            if self.self_closing {
-                write!(output, "/>")?;
-            } else {
-                write!(output, "></{}>", element.name)?;
-            }
+            self.close_empty_element(element, output)?;
 
             if self.pretty {
                 self.write_newline(output)?;
@@ -217,13 +506,17 @@ impl Stringifier {
                         let trimmed = escaped_text.trim();
                         if !trimmed.is_empty() {
                             self.write_indent(output, depth + 1);
-                            write!(output, "{}", trimmed)?;
+                            write!(output, "{}{}{}", self.text_start, trimmed, self.text_end)?;
                             self.write_newline(output)?;
                         }
                         // Skip whitespace-only text nodes when pretty-printing
                     } else if !self.pretty || !text.trim().is_empty() {
                         // Only write non-whitespace text or all text when not pretty-printing
-                        write!(output, "{}", escaped_text)?;
+                        write!(
+                            output,
+                            "{}{}{}",
+                            self.text_start, escaped_text, self.text_end
+                        )?;
                     }
                 }
                 Node::Comment(comment) => {
@@ -277,8 +570,29 @@ impl Stringifier {
         Ok(())
     }
 
+    /// Finish the start tag of an element without content
+    pub(crate) fn close_empty_element(
+        &self,
+        element: &Element,
+        output: &mut String,
+    ) -> StringifyResult<()> {
+        if self.self_closing {
+            // Keep `/` from being read as part of an unquoted value
+            if matches!(self.quote_attrs, QuoteAttrsStyle::Never)
+                && !element.attributes.is_empty()
+                && output.ends_with(|c: char| c != '"' && c != '\'')
+            {
+                output.push(' ');
+            }
+            write!(output, "/>")?;
+        } else {
+            write!(output, "></{}>", element.name)?;
+        }
+        Ok(())
+    }
+
     /// Stringify a node (for use with prologue/epilogue)
-    fn stringify_node(
+    pub(crate) fn stringify_node(
         &self,
         node: &Node,
         output: &mut String,
@@ -295,8 +609,13 @@ impl Stringifier {
                     write!(output, "<?{} {}?>", target, data)?;
                 }
             }
-            Node::DocType(doctype) => {
-                write!(output, "<!DOCTYPE {}>", doctype)?;
+            // HTML5 inline SVG has no DOCTYPE of its own
+            Node::DocType(doctype) if !self.html => {
+                write!(
+                    output,
+                    "{} {}{}",
+                    self.doctype_start, doctype, self.doctype_end
+                )?;
             }
             _ => {
                 // Other node types should not appear in prologue/epilogue
@@ -306,7 +625,11 @@ impl Stringifier {
     }
 
     /// Write element attributes
-    fn write_attributes(&self, element: &Element, output: &mut String) -> StringifyResult<()> {
+    pub(crate) fn write_attributes(
+        &self,
+        element: &Element,
+        output: &mut String,
+    ) -> StringifyResult<()> {
         // Collect attributes with their original index to preserve order when priorities are equal
         let mut attrs: Vec<_> = element.attributes.iter().enumerate().collect();
         attrs.sort_by(|(a_idx, (a_name, _)), (b_idx, (b_name, _))| {
@@ -321,27 +644,68 @@ impl Stringifier {
         });
 
         for (_idx, (name, value)) in attrs {
-            write!(output, " {}", name)?;
+            if self.omits_attribute(name) {
+                continue;
+            }
+            self.write_attribute(name, value, output)?;
+        }
 
-            if !value.is_empty() {
-                let quote_char = self.choose_quote_char(value);
-                write!(
+        Ok(())
+    }
+
+    /// Whether an attribute is left out of the output
+    fn omits_attribute(&self, name: &str) -> bool {
+        // The HTML parser assigns SVG namespaces itself
+        self.html && (name == "xmlns" || name.starts_with("xmlns:"))
+    }
+
+    /// Write a single attribute, preceded by a space
+    fn write_attribute(&self, name: &str, value: &str, output: &mut String) -> StringifyResult<()> {
+        write!(output, " {}", name)?;
+
+        if self.attr_start.is_some() || self.attr_end.is_some() {
+            let quote = self
+                .choose_quote_char(value)
+                .map(String::from)
+                .unwrap_or_default();
+            let start = match &self.attr_start {
+                Some(start) => start.clone(),
+                None => format!("={}", quote),
+            };
+            let end = match &self.attr_end {
+                Some(end) => end.clone(),
+                None => quote,
+            };
+            // Escape whichever quote character closes the value
+            let closing = end.chars().next();
+            write!(
+                output,
+                "{}{}{}",
+                start,
+                self.escape_attr_value(value, closing),
+                end
+            )?;
+        } else if !value.is_empty() || self.html {
+            match self.choose_quote_char(value) {
+                Some(quote_char) => write!(
                     output,
                     "={}{}{}",
                     quote_char,
-                    self.escape_attr_value(value, quote_char),
+                    self.escape_attr_value(value, Some(quote_char)),
                     quote_char
-                )?;
+                )?,
+                None => write!(output, "={}", self.escape_attr_value(value, None))?,
             }
         }
 
         Ok(())
     }
 
-    /// Choose appropriate quote character for attribute value
-    fn choose_quote_char(&self, value: &str) -> char {
+    /// Choose appropriate quote character for attribute value, or `None` to
+    /// leave it unquoted
+    fn choose_quote_char(&self, value: &str) -> Option<char> {
         match self.quote_attrs {
-            QuoteAttrsStyle::Always => '"',
+            QuoteAttrsStyle::Always => Some('"'),
             QuoteAttrsStyle::Never => {
                 // Only use quotes if necessary
                 if value.contains(' ')
@@ -353,36 +717,41 @@ impl Stringifier {
                     || value.contains('<')
                     || value.contains('>')
                     || value.contains('&')
+                    || value.contains('/')
+                    || value.contains('=')
+                    || value.contains('`')
+                    || value.is_empty()
                 {
                     if value.contains('"') && !value.contains('\'') {
-                        '\''
+                        Some('\'')
                     } else {
-                        '"'
+                        Some('"')
                     }
                 } else {
-                    '\0' // No quotes needed
+                    None
                 }
             }
             QuoteAttrsStyle::Auto => {
                 if value.contains('"') && !value.contains('\'') {
-                    '\''
+                    Some('\'')
                 } else {
-                    '"'
+                    Some('"')
                 }
             }
         }
     }
 
-    /// Escape attribute value based on quote character
-    fn escape_attr_value(&self, value: &str, quote_char: char) -> String {
+    /// Escape attribute value based on the quote character around it, if any
+    fn escape_attr_value(&self, value: &str, quote_char: Option<char>) -> String {
         let mut result = String::with_capacity(value.len());
 
-        for ch in value.chars() {
+        for (index, ch) in value.char_indices() {
             match ch {
+                '&' if self.is_entity_ref(&value[index + 1..]) => result.push('&'),
                 '&' => result.push_str("&amp;"),
                 '<' => result.push_str("&lt;"),
-                '"' if quote_char == '"' => result.push_str("&quot;"),
-                '\'' if quote_char == '\'' => result.push_str("&apos;"),
+                '"' if quote_char == Some('"') => result.push_str("&quot;"),
+                '\'' if quote_char == Some('\'') => result.push_str("&apos;"),
                 _ => result.push(ch),
             }
         }
@@ -391,11 +760,12 @@ impl Stringifier {
     }
 
     /// Escape text content
-    fn escape_text(&self, text: &str) -> String {
+    pub(crate) fn escape_text(&self, text: &str) -> String {
         let mut result = String::with_capacity(text.len());
 
-        for ch in text.chars() {
+        for (index, ch) in text.char_indices() {
             match ch {
+                '&' if self.is_entity_ref(&text[index + 1..]) => result.push('&'),
                 '&' => result.push_str("&amp;"),
                 '<' => result.push_str("&lt;"),
                 '>' => result.push_str("&gt;"),
@@ -406,6 +776,14 @@ impl Stringifier {
         result
     }
 
+    /// Whether text following a `&` is a reference to keep as written
+    fn is_entity_ref(&self, rest: &str) -> bool {
+        !self.entity_refs.is_empty()
+            && rest
+                .split_once(';')
+                .is_some_and(|(name, _)| self.entity_refs.contains(name))
+    }
+
     /// Write indentation
     fn write_indent(&self, output: &mut String, depth: usize) {
         for _ in 0..depth {
@@ -566,4 +944,152 @@ mod tests {
         assert!(result.contains("simple=\"value\""));
         assert!(result.contains("with_quotes='value with \"quotes\"'"));
     }
+
+    #[test]
+    fn test_preserve_formatting_round_trip() {
+        let svg = "<?xml version='1.0'?>\n<!-- c -->\n<svg xmlns=\"http://www.w3.org/2000/svg\"   viewBox='0 0 10 10'>\n\t<g\n    fill=\"red\" ><rect x='1' /></g>\n  <path d=\"M0 0\"></path>\n</svg>\n";
+        let document = crate::parser::Parser::new()
+            .preserve_comments(true)
+            .preserve_formatting(true)
+            .parse(svg)
+            .unwrap();
+
+        let result = Stringifier::new()
+            .preserve_formatting(true)
+            .stringify(&document)
+            .unwrap();
+        assert_eq!(result, svg);
+    }
+
+    #[test]
+    fn test_preserve_formatting_changes_only_modified_attribute() {
+        let svg = "<svg>\n  <rect  x='1'   fill = 'red' y=\"2\"/>\n  <circle r=\"1\"/>\n</svg>";
+        let mut document = crate::parser::Parser::new()
+            .preserve_formatting(true)
+            .parse(svg)
+            .unwrap();
+
+        let rect = document.root.child_elements_mut().next().unwrap();
+        rect.set_attr("fill".to_string(), "#f00".to_string());
+        document
+            .root
+            .children
+            .retain(|child| !matches!(child, Node::Element(element) if element.name == "circle"));
+
+        let result = Stringifier::new()
+            .preserve_formatting(true)
+            .stringify(&document)
+            .unwrap();
+        assert_eq!(
+            result,
+            "<svg>\n  <rect  x='1'   fill='#f00' y=\"2\"/>\n</svg>"
+        );
+    }
+
+    #[test]
+    fn test_quote_never_omits_quotes() {
+        let mut element = Element::new("rect");
+        element.set_attr("x".to_string(), "10".to_string());
+        element.set_attr("class".to_string(), "a b".to_string());
+
+        let mut document = Document::new();
+        document.root = element;
+
+        let result = Stringifier::new()
+            .quote_attrs(QuoteAttrsStyle::Never)
+            .stringify(&document)
+            .unwrap();
+        assert_eq!(result, r#"<rect x=10 class="a b"/>"#);
+
+        let mut element = Element::new("rect");
+        element.set_attr("width".to_string(), "5".to_string());
+        document.root = element;
+        let result = Stringifier::new()
+            .quote_attrs(QuoteAttrsStyle::Never)
+            .stringify(&document)
+            .unwrap();
+        assert_eq!(result, "<rect width=5 />");
+    }
+
+    #[test]
+    fn test_xml_declaration_modes() {
+        let mut document = Document::new();
+        document.root = Element::new("svg");
+
+        let keep = Stringifier::new().stringify(&document).unwrap();
+        assert_eq!(keep, "<svg/>");
+
+        let force = Stringifier::new()
+            .xml_declaration(XmlDeclaration::Force)
+            .stringify(&document)
+            .unwrap();
+        assert!(force.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+
+        document.metadata.version = Some("1.0".to_string());
+        let strip = Stringifier::new()
+            .xml_declaration(XmlDeclaration::Strip)
+            .stringify(&document)
+            .unwrap();
+        assert_eq!(strip, "<svg/>");
+    }
+
+    #[test]
+    fn test_custom_delimiters() {
+        let mut element = Element::new("text");
+        element.set_attr("x".to_string(), "1".to_string());
+        element.add_child(Node::Text("Hi".to_string()));
+
+        let mut document = Document::new();
+        document.prologue.push(Node::DocType("svg".to_string()));
+        document.root = element;
+
+        let result = Stringifier::new()
+            .attr_delimiters(Some("='".to_string()), Some("'".to_string()))
+            .text_delimiters("<![CDATA[".to_string(), "]]>".to_string())
+            .doctype_delimiters("<!doctype".to_string(), " >".to_string())
+            .stringify(&document)
+            .unwrap();
+        assert_eq!(result, "<!doctype svg ><text x='1'><![CDATA[Hi]]></text>");
+    }
+
+    #[test]
+    fn test_html_output() {
+        let mut element = Element::new("svg");
+        element.set_attr(
+            "xmlns".to_string(),
+            "http://www.w3.org/2000/svg".to_string(),
+        );
+        element.set_attr(
+            "xmlns:xlink".to_string(),
+            "http://www.w3.org/1999/xlink".to_string(),
+        );
+        element.set_attr("data-flag".to_string(), String::new());
+
+        let mut document = Document::new();
+        document.metadata.version = Some("1.0".to_string());
+        document.prologue.push(Node::DocType("svg".to_string()));
+        document.root = element;
+
+        let result = Stringifier::new().html(true).stringify(&document).unwrap();
+        assert_eq!(result, r#"<svg data-flag=""/>"#);
+    }
+
+    #[test]
+    fn test_html_output_preserving_formatting() {
+        let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\"  viewBox='0 0 10 10'>\n  <rect x='1'/>\n</svg>";
+        let document = crate::parser::Parser::new()
+            .preserve_formatting(true)
+            .parse(svg)
+            .unwrap();
+
+        let result = Stringifier::new()
+            .preserve_formatting(true)
+            .html(true)
+            .stringify(&document)
+            .unwrap();
+        assert_eq!(
+            result,
+            "<svg  viewBox='0 0 10 10'>\n  <rect x='1'/>\n</svg>"
+        );
+    }
 }
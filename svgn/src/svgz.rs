@@ -0,0 +1,92 @@
+// this_file: svgn/src/svgz.rs
+
+//! Gzip (SVGZ) compression helpers
+//!
+//! `.svgz` files are plain SVG documents compressed with gzip. Input is
+//! recognized by the gzip magic bytes rather than by file extension, so
+//! compressed data piped through stdin works as well.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+/// The first two bytes of every gzip stream
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Compression level used when none is configured
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 9;
+
+/// Gzip output options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SvgzOptions {
+    /// Compression level from 0 (store) to 9 (smallest output)
+    #[serde(default = "default_level")]
+    pub level: u32,
+}
+
+fn default_level() -> u32 {
+    DEFAULT_COMPRESSION_LEVEL
+}
+
+impl SvgzOptions {
+    /// Create options with the given compression level (clamped to 0-9)
+    pub fn new(level: u32) -> Self {
+        Self {
+            level: level.min(9),
+        }
+    }
+}
+
+impl Default for SvgzOptions {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPRESSION_LEVEL)
+    }
+}
+
+/// Whether `data` starts with the gzip magic bytes
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&GZIP_MAGIC)
+}
+
+/// Inflate gzip-compressed data
+pub fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len() * 4);
+    GzDecoder::new(data).read_to_end(&mut output)?;
+    Ok(output)
+}
+
+/// Gzip-compress `data` at the given level
+///
+/// The header carries no file name or timestamp, so the same input always
+/// produces the same bytes.
+pub fn compress(data: &[u8], level: u32) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level.min(9)));
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+        let compressed = compress(svg, DEFAULT_COMPRESSION_LEVEL).unwrap();
+
+        assert!(is_gzip(&compressed));
+        assert!(!is_gzip(svg));
+        assert_eq!(decompress(&compressed).unwrap(), svg);
+        assert_eq!(
+            compressed,
+            compress(svg, DEFAULT_COMPRESSION_LEVEL).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        assert!(decompress(&[0x1f, 0x8b, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+// this_file: svgn/src/tuning.rs
+
+//! Compressed-size tuning
+//!
+//! Several optimizations have no effect on the raw output size but change how
+//! well the output compresses: attribute order (`sortAttrs`), the order of
+//! sorting plugins in the pipeline, the alphabet minified IDs are drawn from
+//! (`cleanupIds`) and whether path commands are written relative or absolute
+//! (`convertPathData`). When a compressed [`SizeMetric`] is configured, each of
+//! these knobs is tried in turn and the variant that yields the smallest
+//! compressed output is kept (a greedy search, so the number of optimizer runs
+//! grows with the number of knobs rather than their combinations).
+
+use crate::config::{Config, SizeMetric};
+use crate::optimizer::{OptimizationResult, OptimizeResult};
+use serde_json::{json, Value};
+use std::io::Write;
+
+/// Plugins that only reorder content to help compression
+const SORTING_PLUGINS: &[&str] = &["sortAttrs", "sortDefsChildren"];
+
+/// Size of `data` under `metric`
+pub fn measure(metric: SizeMetric, data: &[u8]) -> usize {
+    match metric {
+        SizeMetric::Raw => data.len(),
+        SizeMetric::Gzip => crate::svgz::compress(data, crate::svgz::DEFAULT_COMPRESSION_LEVEL)
+            .map_or(data.len(), |compressed| compressed.len()),
+        SizeMetric::Brotli => {
+            let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+            match writer.write_all(data) {
+                Ok(()) => writer.into_inner().len(),
+                Err(_) => data.len(),
+            }
+        }
+    }
+}
+
+/// Run `optimize` for the configured variants and keep the smallest result
+///
+/// `input` is the source document; it is used to derive a character
+/// frequency ordering for minified IDs.
+pub fn tune<F>(input: &str, config: &Config, optimize: F) -> OptimizeResult<OptimizationResult>
+where
+    F: FnMut(&Config) -> OptimizeResult<OptimizationResult>,
+{
+    Ok(tune_with_config(input, config, optimize)?.0)
+}
+
+/// Like [`tune`], but also return the configuration of the smallest result
+pub fn tune_with_config<F>(
+    input: &str,
+    config: &Config,
+    mut optimize: F,
+) -> OptimizeResult<(OptimizationResult, Config)>
+where
+    F: FnMut(&Config) -> OptimizeResult<OptimizationResult>,
+{
+    let metric = config.size_metric;
+    let mut best_config = config.clone();
+    let mut best = optimize(&best_config)?;
+    let mut best_size = measure(metric, best.data.as_bytes());
+
+    for knob in KNOBS {
+        for candidate in knob(&best_config, input) {
+            // A variant that fails is simply not a candidate
+            let Ok(result) = optimize(&candidate) else {
+                continue;
+            };
+            let size = measure(metric, result.data.as_bytes());
+            if size < best_size {
+                best = result;
+                best_size = size;
+                best_config = candidate;
+            }
+        }
+    }
+
+    match metric {
+        SizeMetric::Gzip if best.info.compressed_size.is_none() => {
+            best.info.compressed_size = Some(best_size)
+        }
+        SizeMetric::Brotli => best.info.brotli_size = Some(best_size),
+        _ => {}
+    }
+    Ok((best, best_config))
+}
+
+/// A knob produces alternative configurations to the given one
+type Knob = fn(&Config, &str) -> Vec<Config>;
+
+const KNOBS: &[Knob] = &[
+    plugin_order_variants,
+    sort_attrs_variants,
+    id_alphabet_variants,
+    path_command_variants,
+];
+
+/// Run the sorting plugins last, after everything else has changed the tree
+fn plugin_order_variants(config: &Config, _input: &str) -> Vec<Config> {
+    let mut variant = config.clone();
+    let (sorting, mut others): (Vec<_>, Vec<_>) = variant
+        .plugins
+        .drain(..)
+        .partition(|plugin| SORTING_PLUGINS.contains(&plugin.name.as_str()));
+    others.extend(sorting);
+    variant.plugins = others;
+
+    if variant
+        .plugins
+        .iter()
+        .map(|plugin| &plugin.name)
+        .eq(config.plugins.iter().map(|plugin| &plugin.name))
+    {
+        Vec::new()
+    } else {
+        vec![variant]
+    }
+}
+
+/// Paint attributes first, so `<path fill="...` style runs repeat across
+/// elements, and alphabetical `xmlns` placement
+fn sort_attrs_variants(config: &Config, _input: &str) -> Vec<Config> {
+    [
+        json!({"order": ["fill", "stroke", "id", "width", "height", "x", "y", "d", "points"]}),
+        json!({"xmlnsOrder": "alphabetical"}),
+    ]
+    .into_iter()
+    .filter_map(|params| with_params(config, "sortAttrs", params))
+    .collect()
+}
+
+/// Uppercase-first IDs, and IDs built from the letters most frequent in the
+/// document so they repeat substrings the compressor has already seen
+fn id_alphabet_variants(config: &Config, input: &str) -> Vec<Config> {
+    let mut counts = [0usize; 128];
+    for byte in input.bytes().filter(u8::is_ascii_alphabetic) {
+        counts[byte as usize] += 1;
+    }
+    let mut by_frequency: Vec<u8> = (b'a'..=b'z').chain(b'A'..=b'Z').collect();
+    by_frequency.sort_by_key(|&byte| std::cmp::Reverse(counts[byte as usize]));
+    let by_frequency: String = by_frequency.into_iter().map(char::from).collect();
+
+    [
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz".to_string(),
+        by_frequency,
+    ]
+    .into_iter()
+    .filter_map(|alphabet| with_params(config, "cleanupIds", json!({ "alphabet": alphabet })))
+    .collect()
+}
+
+/// Relative path commands instead of absolute ones
+fn path_command_variants(config: &Config, _input: &str) -> Vec<Config> {
+    with_params(config, "convertPathData", json!({"utilizeAbsolute": false}))
+        .into_iter()
+        .collect()
+}
+
+/// Copy of `config` with `params` merged into the params of an enabled
+/// plugin, or `None` if the plugin is not enabled or already configured so
+fn with_params(config: &Config, plugin: &str, params: Value) -> Option<Config> {
+    let mut variant = config.clone();
+    let plugin = variant
+        .plugins
+        .iter_mut()
+        .find(|p| p.enabled && p.name == plugin)?;
+
+    let mut merged = match plugin.params.take() {
+        Some(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let mut changed = false;
+    for (key, value) in params.as_object()? {
+        if merged.get(key) != Some(value) {
+            merged.insert(key.clone(), value.clone());
+            changed = true;
+        }
+    }
+    plugin.params = Some(Value::Object(merged));
+
+    changed.then_some(variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PluginConfig;
+
+    #[test]
+    fn test_measure() {
+        let data = "<svg>".repeat(100);
+        assert_eq!(measure(SizeMetric::Raw, data.as_bytes()), 500);
+        assert!(measure(SizeMetric::Gzip, data.as_bytes()) < 100);
+        assert!(measure(SizeMetric::Brotli, data.as_bytes()) < 100);
+    }
+
+    #[test]
+    fn test_variants_only_for_enabled_plugins() {
+        let mut config = Config::new();
+        config.add_plugin(PluginConfig::new("sortAttrs".to_string()));
+        config.add_plugin(PluginConfig::new("removeComments".to_string()));
+
+        let variants = plugin_order_variants(&config, "");
+        assert_eq!(variants.len(), 1);
+        assert_eq!(variants[0].plugins[1].name, "sortAttrs");
+
+        assert_eq!(sort_attrs_variants(&config, "").len(), 2);
+        assert!(id_alphabet_variants(&config, "").is_empty());
+        assert!(path_command_variants(&config, "").is_empty());
+
+        // Variants that match the current params are not repeated
+        let variant = &sort_attrs_variants(&config, "")[0];
+        assert_eq!(sort_attrs_variants(variant, "").len(), 1);
+    }
+
+    #[test]
+    fn test_path_command_variant_changes_output() {
+        let svg =
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><path d="M10 10L20 10L20 20z"/></svg>"#;
+        let mut config = Config::new();
+        config.add_plugin(PluginConfig::new("convertPathData".to_string()));
+        let variants = path_command_variants(&config, svg);
+        assert_eq!(variants.len(), 1);
+
+        let mut registry = crate::plugin::create_default_registry();
+        let mut optimize = |config: &Config| {
+            crate::optimizer::optimize_with_registry(svg, config, &mut registry)
+                .unwrap()
+                .data
+        };
+        assert_ne!(optimize(&config), optimize(&variants[0]));
+    }
+
+    #[test]
+    fn test_tune_keeps_smallest_result() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><rect height="1" width="2" x="3"/><rect height="1" width="2" x="3"/></svg>"#;
+        let mut config = Config::new();
+        config.add_plugin(PluginConfig::new("sortAttrs".to_string()));
+        config.size_metric = SizeMetric::Gzip;
+
+        let mut registry = crate::plugin::create_default_registry();
+        let result = tune(svg, &config, |candidate| {
+            let mut candidate = candidate.clone();
+            candidate.size_metric = SizeMetric::Raw;
+            crate::optimizer::optimize_with_registry(svg, &candidate, &mut registry)
+        })
+        .unwrap();
+
+        let compressed = result.info.compressed_size.unwrap();
+        assert_eq!(
+            compressed,
+            measure(SizeMetric::Gzip, result.data.as_bytes())
+        );
+    }
+}
@@ -0,0 +1,926 @@
+// this_file: svgn/tests/cli_test.rs
+
+//! Command-line tests for the svgn binary
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const SIMPLE_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg">
+    <!-- comment -->
+    <rect x="10" y="10" width="50" height="50"/>
+</svg>"#;
+
+fn svgn() -> Command {
+    Command::cargo_bin("svgn").unwrap()
+}
+
+#[test]
+fn test_folder_parallel_jobs() {
+    let dir = TempDir::new().unwrap();
+    for i in 0..8 {
+        fs::write(dir.path().join(format!("icon{}.svg", i)), SIMPLE_SVG).unwrap();
+    }
+
+    svgn()
+        .args(["--folder", dir.path().to_str().unwrap(), "--jobs", "4"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Total: 8 files processed"));
+
+    for i in 0..8 {
+        let output = fs::read_to_string(dir.path().join(format!("icon{}.svg", i))).unwrap();
+        assert!(!output.contains("<!--"));
+    }
+}
+
+#[test]
+fn test_folder_failure_does_not_abort_run() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("a.svg"), SIMPLE_SVG).unwrap();
+    fs::write(dir.path().join("b.svg"), "<svg><rect></svg>").unwrap();
+    fs::write(dir.path().join("c.svg"), SIMPLE_SVG).unwrap();
+
+    let assert = svgn()
+        .args(["--folder", dir.path().to_str().unwrap(), "-j", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Total: 2 files processed, 1 failed",
+        ));
+
+    // Per-file lines are reported in input order regardless of scheduling
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+    let a = stderr.find("a.svg").unwrap();
+    let b = stderr.find("Error processing").unwrap();
+    let c = stderr.find("c.svg").unwrap();
+    assert!(a < b && b < c);
+
+    assert!(!fs::read_to_string(dir.path().join("c.svg"))
+        .unwrap()
+        .contains("<!--"));
+}
+
+#[test]
+fn test_check_reports_unoptimized_files() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("raw.svg"), SIMPLE_SVG).unwrap();
+
+    svgn()
+        .args(["--folder", dir.path().to_str().unwrap()])
+        .assert()
+        .success();
+    let optimized = fs::read_to_string(dir.path().join("raw.svg")).unwrap();
+    fs::write(dir.path().join("done.svg"), &optimized).unwrap();
+    fs::write(dir.path().join("raw.svg"), SIMPLE_SVG).unwrap();
+
+    svgn()
+        .args(["--check", "--folder", dir.path().to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not optimized:").and(predicate::str::contains("raw.svg")))
+        .stderr(predicate::str::contains("done.svg").not())
+        .stderr(predicate::str::contains("1 not optimized"));
+
+    // Nothing is written in check mode
+    assert_eq!(
+        fs::read_to_string(dir.path().join("raw.svg")).unwrap(),
+        SIMPLE_SVG
+    );
+
+    svgn()
+        .args(["--check", dir.path().join("done.svg").to_str().unwrap()])
+        .assert()
+        .success();
+
+    // Pretty printing grows the file
+    svgn()
+        .args([
+            "--check",
+            "--pretty",
+            dir.path().join("done.svg").to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("would grow by"))
+        .stderr(predicate::str::contains("would save").not());
+    svgn()
+        .args(["--check", "--pretty", "-i", "-"])
+        .write_stdin(optimized)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("would grow by"));
+}
+
+#[test]
+fn test_diff_prints_unified_diff_without_writing() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("icon.svg");
+    fs::write(&path, SIMPLE_SVG).unwrap();
+
+    svgn()
+        .args(["--diff", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+++ b/"))
+        .stdout(predicate::str::contains("-  <!-- comment -->"));
+
+    svgn()
+        .args(["--diff", "--diff-format", "json", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""plugin": "removeComments""#));
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), SIMPLE_SVG);
+}
+
+#[test]
+fn test_check_and_diff_compare_svg_whatever_the_format() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("icon.svg");
+    fs::write(&path, SIMPLE_SVG).unwrap();
+    svgn().arg(&path).assert().success();
+
+    svgn()
+        .args(["--check", "--format", "jsx"])
+        .arg(&path)
+        .assert()
+        .success();
+
+    fs::write(&path, SIMPLE_SVG).unwrap();
+    for format in ["jsx", "compose"] {
+        svgn()
+            .args(["--diff", "--format", format, "--folder"])
+            .arg(dir.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("-  <!-- comment -->"))
+            .stdout(predicate::str::contains("+<svg/>").not());
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), SIMPLE_SVG);
+}
+
+/// A `svgn --watch` process, stopped when dropped, even on test failure
+struct Watch(std::process::Child);
+
+impl Watch {
+    fn spawn(dir: &std::path::Path, args: &[&str]) -> Self {
+        Self(
+            std::process::Command::new(assert_cmd::cargo::cargo_bin("svgn"))
+                .arg("--watch")
+                .args(args)
+                .current_dir(dir)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap(),
+        )
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Wait for `path` to hold `expected`, comparing whole files so that a
+/// partly written one doesn't count
+fn wait_for_content(path: &std::path::Path, expected: &[u8]) {
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while fs::read(path).ok().as_deref() != Some(expected) {
+        assert!(
+            Instant::now() < deadline,
+            "{} was not optimized",
+            path.display()
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// `SIMPLE_SVG` optimized with `args`
+fn optimized(args: &[&str]) -> Vec<u8> {
+    svgn()
+        .args(args)
+        .args(["-i", "-", "-o", "-"])
+        .write_stdin(SIMPLE_SVG)
+        .output()
+        .unwrap()
+        .stdout
+}
+
+#[test]
+fn test_watch_reoptimizes_changed_files() {
+    let optimized = optimized(&[]);
+    let dir = TempDir::new().unwrap();
+    let icon = dir.path().join("icon.svg");
+    fs::write(&icon, SIMPLE_SVG).unwrap();
+    let _watch = Watch::spawn(dir.path(), &["--folder", "."]);
+    wait_for_content(&icon, &optimized);
+
+    // Edited and new files are picked up
+    fs::write(&icon, SIMPLE_SVG).unwrap();
+    wait_for_content(&icon, &optimized);
+    let added = dir.path().join("added.svg");
+    fs::write(&added, SIMPLE_SVG).unwrap();
+    wait_for_content(&added, &optimized);
+}
+
+#[test]
+fn test_watch_reloads_config() {
+    let dir = TempDir::new().unwrap();
+    let config = dir.path().join("svgn.config.toml");
+    fs::write(&config, "plugins = []\n").unwrap();
+    fs::create_dir(dir.path().join("icons")).unwrap();
+    let icon = dir.path().join("icons/icon.svg");
+    fs::write(&icon, SIMPLE_SVG).unwrap();
+    let _watch = Watch::spawn(dir.path(), &["--folder", "icons"]);
+    wait_for_content(&icon, SIMPLE_SVG.as_bytes());
+
+    // A changed config re-optimizes the folder
+    fs::write(&config, "plugins = [\"removeComments\"]\n").unwrap();
+    let config_arg = config.to_str().unwrap();
+    wait_for_content(&icon, &optimized(&["--config", config_arg]));
+}
+
+#[test]
+fn test_recursive_folder_mirrors_tree_into_output() {
+    let input = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::create_dir_all(input.path().join("a")).unwrap();
+    fs::create_dir_all(input.path().join("b/c")).unwrap();
+    fs::write(input.path().join("a/icon.svg"), SIMPLE_SVG).unwrap();
+    fs::write(input.path().join("b/c/icon.svg"), SIMPLE_SVG).unwrap();
+
+    svgn()
+        .args([
+            "--folder",
+            input.path().to_str().unwrap(),
+            "-r",
+            "-o",
+            &format!("{}/", output.path().display()),
+            "--output-ext",
+            ".min.svg",
+        ])
+        .assert()
+        .success();
+
+    assert!(output.path().join("a/icon.min.svg").is_file());
+    assert!(output.path().join("b/c/icon.min.svg").is_file());
+    // Inputs are left untouched
+    assert_eq!(
+        fs::read_to_string(input.path().join("a/icon.svg")).unwrap(),
+        SIMPLE_SVG
+    );
+}
+
+#[test]
+fn test_flattened_output_collision_is_rejected() {
+    let input = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    fs::create_dir_all(input.path().join("a")).unwrap();
+    fs::create_dir_all(input.path().join("b")).unwrap();
+    let first = input.path().join("a/icon.svg");
+    let second = input.path().join("b/icon.svg");
+    fs::write(&first, SIMPLE_SVG).unwrap();
+    fs::write(&second, SIMPLE_SVG).unwrap();
+
+    svgn()
+        .args([
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "-o",
+            output.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("both write to"));
+
+    assert!(!output.path().join("icon.svg").exists());
+}
+
+#[test]
+fn test_cache_reuses_results_for_unchanged_files() {
+    let dir = TempDir::new().unwrap();
+    let cache = TempDir::new().unwrap();
+    let input = dir.path().join("in");
+    fs::create_dir_all(&input).unwrap();
+    fs::write(input.join("a.svg"), SIMPLE_SVG).unwrap();
+    fs::write(input.join("b.svg"), SIMPLE_SVG.replace("10", "20")).unwrap();
+    let output = format!("{}/", dir.path().join("out").display());
+    let args = [
+        "--folder",
+        input.to_str().unwrap(),
+        "-o",
+        &output,
+        "--cache-location",
+        cache.path().to_str().unwrap(),
+    ];
+
+    svgn()
+        .args(args)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Cache: 0 hits, 2 misses"));
+
+    fs::write(input.join("b.svg"), SIMPLE_SVG.replace("10", "30")).unwrap();
+    svgn()
+        .args(args)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Cache: 1 hits, 1 misses"));
+
+    svgn()
+        .args(args)
+        .arg("--no-cache")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Cache:").not());
+
+    // A cache that can't be written doesn't fail the run
+    let blocked = dir.path().join("blocked");
+    fs::write(&blocked, "").unwrap();
+    fs::remove_dir_all(dir.path().join("out")).unwrap();
+    svgn()
+        .args(&args[..4])
+        .args(["--cache-location", blocked.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: could not cache"));
+    assert!(dir.path().join("out/a.svg").exists());
+}
+
+#[test]
+fn test_json_report_file() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("in");
+    fs::create_dir_all(&input).unwrap();
+    fs::write(input.join("a.svg"), SIMPLE_SVG).unwrap();
+    fs::write(input.join("b.svg"), "<svg><rect></svg>").unwrap();
+    let report_path = dir.path().join("report.json");
+
+    svgn()
+        .args([
+            "--folder",
+            input.to_str().unwrap(),
+            "--report",
+            "json",
+            "--report-file",
+            report_path.to_str().unwrap(),
+        ])
+        .assert()
+        .failure();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(report["summary"]["files"], 2);
+    assert_eq!(report["summary"]["failed"], 1);
+    assert_eq!(report["files"][0]["status"], "optimized");
+    assert!(report["files"][0]["original_size"].as_u64().unwrap() > 0);
+    assert_eq!(report["files"][1]["status"], "failed");
+    assert!(report["files"][1]["error"].is_string());
+}
+
+#[test]
+fn test_svgz_input_stays_compressed() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("icon.svgz");
+    fs::write(
+        &path,
+        svgn::svgz::compress(SIMPLE_SVG.as_bytes(), 9).unwrap(),
+    )
+    .unwrap();
+    fs::write(dir.path().join("plain.svg"), SIMPLE_SVG).unwrap();
+
+    svgn()
+        .args(["--folder", dir.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Total: 2 files processed"))
+        .stderr(predicate::str::contains("gzip"));
+
+    let output = fs::read(&path).unwrap();
+    assert!(svgn::svgz::is_gzip(&output));
+    let svg = String::from_utf8(svgn::svgz::decompress(&output).unwrap()).unwrap();
+    assert!(svg.starts_with("<svg") && !svg.contains("<!--"));
+    assert!(!svgn::svgz::is_gzip(
+        &fs::read(dir.path().join("plain.svg")).unwrap()
+    ));
+
+    let svg_path = dir.path().join("plain.svg");
+    svgn()
+        .args([
+            svg_path.to_str().unwrap(),
+            "--svgz",
+            "--compression-level",
+            "1",
+        ])
+        .assert()
+        .success();
+    assert!(svgn::svgz::is_gzip(
+        &fs::read(dir.path().join("plain.svgz")).unwrap()
+    ));
+}
+
+#[test]
+fn test_utf16_input_is_written_as_utf8() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("legacy.svg");
+    let utf16: Vec<u8> = [0xff, 0xfe]
+        .into_iter()
+        .chain(
+            r#"<?xml version="1.0" encoding="UTF-16"?><svg><text>café</text></svg>"#
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        )
+        .collect();
+    fs::write(&path, utf16).unwrap();
+
+    svgn()
+        .args([path.to_str().unwrap(), "--disable", "removeXMLProcInst"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg><text>café</text></svg>"
+    );
+
+    // STDIN is handled like files, even when only the byte order mark
+    // gives the encoding
+    let undeclared: Vec<u8> = [0xff, 0xfe]
+        .into_iter()
+        .chain(
+            r#"<?xml version="1.0"?><svg><text>café</text></svg>"#
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        )
+        .collect();
+    svgn()
+        .args(["-i", "-", "-o", "-", "--disable", "removeXMLProcInst"])
+        .write_stdin(undeclared)
+        .assert()
+        .success()
+        .stdout("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg><text>café</text></svg>");
+}
+
+#[test]
+fn test_preserve_formatting_keeps_untouched_markup() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("hand.svg");
+    let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\"  viewBox='0 0 10 10'>\n    <rect width=\"5\" height=\"5\"  x='1'/>\n    <!-- note -->\n</svg>\n";
+    fs::write(&path, svg).unwrap();
+
+    svgn()
+        .args([path.to_str().unwrap(), "--preserve-formatting"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"  viewBox='0 0 10 10'>\n    <rect width=\"5\" height=\"5\"  x='1'/>\n</svg>\n"
+    );
+}
+
+#[test]
+fn test_stream_writes_output_file() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("map.svg");
+    let output = dir.path().join("map.min.svg");
+    fs::write(&input, SIMPLE_SVG).unwrap();
+
+    svgn()
+        .args([
+            "--stream",
+            input.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipped plugins"));
+
+    assert_eq!(
+        fs::read_to_string(&output).unwrap(),
+        r#"<svg xmlns="http://www.w3.org/2000/svg"><rect x="10" y="10" width="50" height="50"/></svg>"#
+    );
+}
+
+#[test]
+fn test_keep_entities_keeps_doctype() {
+    let input = r#"<!DOCTYPE svg [ <!ENTITY ns_svg "http://www.w3.org/2000/svg"> ]>
+<svg xmlns="&ns_svg;"><rect width="10" height="10"/></svg>"#;
+
+    svgn()
+        .args(["-s", input, "-o", "-"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#"<svg xmlns="http://www.w3.org/2000/svg">"#,
+        ))
+        .stdout(predicate::str::contains("DOCTYPE").not());
+
+    svgn()
+        .args(["-s", input, "-o", "-", "--keep-entities"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#"<!DOCTYPE svg [ <!ENTITY ns_svg "http://www.w3.org/2000/svg"> ]>"#,
+        ))
+        .stdout(predicate::str::contains(r#"<svg xmlns="&ns_svg;">"#));
+}
+
+#[test]
+fn test_sanitize_lists_removals() {
+    let input = r#"<svg xmlns="http://www.w3.org/2000/svg" onload="alert(1)"><script>alert(1)</script><a href="https://evil.test"><rect width="10" height="10"/></a></svg>"#;
+
+    svgn()
+        .args(["-s", input, "-o", "-", "--sanitize"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("script").not())
+        .stdout(predicate::str::contains("evil.test").not())
+        .stderr(predicate::str::contains(
+            r#"Removed event handler: <svg> onload "alert(1)""#,
+        ))
+        .stderr(predicate::str::contains("Removed script: <script>"))
+        .stderr(predicate::str::contains(
+            r#"Removed external href: <a> href "https://evil.test""#,
+        ));
+}
+
+#[test]
+fn test_a11y_lint_reports_unnamed_svgs() {
+    let temp_dir = TempDir::new().unwrap();
+    let named = temp_dir.path().join("named.svg");
+    let unnamed = temp_dir.path().join("unnamed.svg");
+    fs::write(&named, r#"<svg><title>Logo</title></svg>"#).unwrap();
+    fs::write(&unnamed, r#"<svg><path d="M0 0h1"/></svg>"#).unwrap();
+
+    svgn().args(["--a11y-lint"]).arg(&named).assert().success();
+
+    svgn()
+        .args(["--a11y-lint"])
+        .arg(&named)
+        .arg(&unnamed)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "unnamed.svg: SVG has neither an accessible name nor aria-hidden=\"true\"",
+        ))
+        .stderr(predicate::str::contains("1 accessibility problems"));
+    assert_eq!(
+        fs::read_to_string(&unnamed).unwrap(),
+        r#"<svg><path d="M0 0h1"/></svg>"#
+    );
+}
+
+#[test]
+fn test_lint_subcommand() {
+    let temp_dir = TempDir::new().unwrap();
+    let icon = temp_dir.path().join("icon.svg");
+    fs::write(
+        &icon,
+        r#"<svg width="24" height="24"><path style="fill:red" d="M0 0h1"/></svg>"#,
+    )
+    .unwrap();
+
+    svgn()
+        .arg("lint")
+        .arg(&icon)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "error [require-viewbox] missing viewBox; width and height give viewBox=\"0 0 24 24\" at /svg",
+        ))
+        .stdout(predicate::str::contains(
+            "warning [no-inline-styles] inline style=\"fill:red\" at /svg/path[1]",
+        ))
+        .stdout(predicate::str::contains("Checked: 1 files, 1 errors, 1 warnings"));
+
+    let config = temp_dir.path().join("svgn.config.json");
+    fs::write(
+        &config,
+        r#"{"lint": {"rules": {"require-viewbox": "warning", "no-inline-styles": "off"}}}"#,
+    )
+    .unwrap();
+    let output = svgn()
+        .args(["lint", "--format", "sarif", "--config"])
+        .arg(&config)
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "require-viewbox");
+    assert_eq!(results[0]["level"], "warning");
+}
+
+#[test]
+fn test_lint_fix() {
+    let temp_dir = TempDir::new().unwrap();
+    let icon = temp_dir.path().join("icon.svg");
+    fs::write(
+        &icon,
+        "<svg xmlns:inkscape=\"http://www.inkscape.org/namespaces/inkscape\" width=\"24\" height=\"24\" inkscape:label=\"x\">\n  <path  d=\"M0 0h1\"/>\n</svg>\n",
+    )
+    .unwrap();
+
+    svgn()
+        .args(["lint", "--fix"])
+        .arg(&icon)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "icon.svg: fixed with removeDimensions, removeEditorsNSData",
+        ))
+        .stdout(predicate::str::contains("0 errors, 0 warnings, 1 fixed"));
+    assert_eq!(
+        fs::read_to_string(&icon).unwrap(),
+        "<svg viewBox=\"0 0 24 24\">\n  <path  d=\"M0 0h1\"/>\n</svg>\n"
+    );
+
+    // Fixes stay SVG when the project exports components
+    let config = temp_dir.path().join("svgn.config.toml");
+    fs::write(&config, "format = \"jsx\"\n").unwrap();
+    fs::write(
+        &icon,
+        "<svg width=\"24\" height=\"24\"><path d=\"M0 0h1\"/></svg>",
+    )
+    .unwrap();
+    svgn()
+        .args(["lint", "--fix", "--config"])
+        .arg(&config)
+        .arg(&icon)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "icon.svg: fixed with removeDimensions",
+        ));
+    assert_eq!(
+        fs::read_to_string(&icon).unwrap(),
+        "<svg viewBox=\"0 0 24 24\"><path d=\"M0 0h1\"/></svg>"
+    );
+}
+
+#[test]
+fn test_analyze_folder() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("a.svg"),
+        r##"<svg><defs><linearGradient id="g"/></defs><path d="M0 0h1.5"/></svg>"##,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("b.svg"),
+        r#"<svg><path d="M0 0h1" style="fill:red"/></svg>"#,
+    )
+    .unwrap();
+
+    svgn()
+        .arg("analyze")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files: 2"))
+        .stdout(predicate::str::is_match(r"path\s+2\s+").unwrap())
+        .stdout(predicate::str::contains("Decimal places: 0: 5, 1: 1"))
+        .stdout(predicate::str::contains("Unused defs: <linearGradient> #g"));
+
+    let output = svgn()
+        .args(["analyze", "--format", "json"])
+        .arg(temp_dir.path().join("b.svg"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["total"]["files"], 1);
+    assert_eq!(report["files"][0]["bytes"]["styles"], 17);
+    assert_eq!(report["files"][0]["bytes"]["path_data"], 11);
+}
+
+#[test]
+fn test_format_jsx() {
+    svgn()
+        .args(["--format", "jsx", "-s"])
+        .arg(r#"<svg viewBox="0 0 24 24"><path stroke-width="2" d="M0 0h24"/></svg>"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "const SvgComponent = (props) => (",
+        ))
+        .stdout(predicate::str::contains(
+            r#"<svg viewBox="0 0 24 24" {...props}>"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#"<path strokeWidth="2" d="M0 0h24" />"#,
+        ));
+
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("icons");
+    fs::create_dir_all(input.join("ui")).unwrap();
+    fs::write(
+        input.join("arrow-left.svg"),
+        "<svg><path d=\"M0 0h1\"/></svg>",
+    )
+    .unwrap();
+    fs::write(
+        input.join("ui").join("close.svg"),
+        "<svg><path d=\"M0 0v1\"/></svg>",
+    )
+    .unwrap();
+    let output = temp_dir.path().join("out");
+
+    svgn()
+        .arg("-f")
+        .arg(&input)
+        .arg("-r")
+        .arg("-o")
+        .arg(format!("{}/", output.display()))
+        .args(["--format", "tsx", "--title-prop", "-q"])
+        .assert()
+        .success();
+
+    let component = fs::read_to_string(output.join("arrow-left.tsx")).unwrap();
+    assert!(component.contains(
+        "const SvgArrowLeft = ({ title, ...props }: SVGProps<SVGSVGElement> & TitleProps) => ("
+    ));
+    assert!(output.join("ui").join("close.tsx").exists());
+    assert_eq!(
+        fs::read_to_string(output.join("index.ts")).unwrap(),
+        "export { default as SvgArrowLeft } from \"./arrow-left\";\n\
+         export { default as SvgClose } from \"./ui/close\";\n"
+    );
+}
+
+#[test]
+fn test_format_vue_and_svelte() {
+    let svg = r#"<svg width="24" height="24" viewBox="0 0 24 24"><text>{{ a }}</text></svg>"#;
+
+    svgn()
+        .args(["--format", "vue", "--size-prop", "-s", svg])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<script setup>"))
+        .stdout(predicate::str::contains(
+            r#":width="size ?? '24'" :height="size ?? '24'""#,
+        ))
+        .stdout(predicate::str::contains("{{ '{{' }} a }}"));
+
+    svgn()
+        .args(["--format", "svelte", "-s", svg])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("let props = $props();"))
+        .stdout(predicate::str::contains(
+            r#"<svg width="24" height="24" viewBox="0 0 24 24" {...props}>"#,
+        ))
+        .stdout(predicate::str::contains("&#123;&#123; a &#125;&#125;"));
+}
+
+#[test]
+fn test_format_vector_drawable() {
+    svgn()
+        .args([
+            "--format",
+            "vector-drawable",
+            "-s",
+            r#"<svg width="48" height="48" viewBox="0 0 24 24"><rect width="24" height="12" fill="red"/></svg>"#,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"<vector xmlns:android="http://schemas.android.com/apk/res/android""#,
+        ))
+        .stdout(predicate::str::contains(r#"android:width="48dp""#))
+        .stdout(predicate::str::contains(r#"android:viewportWidth="24""#))
+        .stdout(predicate::str::contains(r##"android:fillColor="#FFFF0000""##));
+
+    svgn()
+        .args([
+            "--format",
+            "vector-drawable",
+            "-s",
+            r#"<svg viewBox="0 0 24 24"><text>A</text></svg>"#,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "VectorDrawable does not support <text> at /svg/text[1]",
+        ));
+
+    // The default preset keeps the fill rule
+    svgn()
+        .args([
+            "--format",
+            "vector-drawable",
+            "-s",
+            r#"<svg viewBox="0 0 24 24"><path d="M2 2h20v20H2zm4 4v12h12V6z" fill-rule="evenodd"/></svg>"#,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"android:fillType="evenOdd""#));
+}
+
+#[test]
+fn test_format_compose() {
+    svgn()
+        .args([
+            "--format",
+            "compose",
+            "--package",
+            "com.example.icons",
+            "-s",
+            r#"<svg viewBox="0 0 24 24"><path d="M2 2h20v20H2zm4 4v12h12V6z" fill="red" fill-rule="evenodd"/></svg>"#,
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("package com.example.icons\n"))
+        .stdout(predicate::str::contains("public val Icon: ImageVector"))
+        .stdout(predicate::str::contains(
+            "fill = SolidColor(Color(0xFFFF0000)),",
+        ))
+        .stdout(predicate::str::contains(
+            "pathFillType = PathFillType.EvenOdd,",
+        ))
+        .stdout(predicate::str::contains("moveTo(2f, 2f)"));
+
+    // One image vector per file, named after it
+    let dir = TempDir::new().unwrap();
+    let icon = r#"<svg viewBox="0 0 24 24"><path d="M4 4h16v16H4z"/></svg>"#;
+    fs::write(dir.path().join("arrow-left.svg"), icon).unwrap();
+    fs::write(dir.path().join("close.svg"), icon).unwrap();
+    let output = dir.path().join("out");
+    fs::create_dir(&output).unwrap();
+    svgn()
+        .args([
+            "-f",
+            dir.path().to_str().unwrap(),
+            "--format",
+            "compose",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let arrow = fs::read_to_string(output.join("arrow-left.kt")).unwrap();
+    assert!(arrow.contains("public val ArrowLeft: ImageVector"));
+    assert!(arrow.contains("private var _arrowLeft: ImageVector? = null"));
+    let close = fs::read_to_string(output.join("close.kt")).unwrap();
+    assert!(close.contains("public val Close: ImageVector"));
+}
+
+#[test]
+fn test_input_format_vector_drawable() {
+    let drawable = r##"<vector xmlns:android="http://schemas.android.com/apk/res/android" android:width="24dp" android:height="24dp" android:viewportWidth="24" android:viewportHeight="24"><path android:fillColor="#80FF0000" android:fillType="evenOdd" android:pathData="M0 0h24v24H0z"/></vector>"##;
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("ic_square.xml"), drawable).unwrap();
+    fs::write(dir.path().join("icon.svg"), SIMPLE_SVG).unwrap();
+
+    // Detected by extension
+    svgn()
+        .args([
+            dir.path().join("ic_square.xml").to_str().unwrap(),
+            "-o",
+            "-",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"viewBox="0 0 24 24""#))
+        .stdout(predicate::str::contains(
+            r##"<path fill="#f00" fill-opacity="0.502""##,
+        ));
+
+    // Folders are searched for .xml files, written with the .svg extension
+    let output = dir.path().join("out");
+    svgn()
+        .args([
+            "-f",
+            dir.path().to_str().unwrap(),
+            "--input-format",
+            "vector-drawable",
+            "-o",
+            &format!("{}/", output.display()),
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Total: 1 files processed"));
+    assert!(fs::read_to_string(output.join("ic_square.svg"))
+        .unwrap()
+        .starts_with("<svg"));
+
+    svgn()
+        .args(["--input-format", "vector-drawable", "-s", "<svg/>"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "VectorDrawable input must have a <vector> root, not <svg>",
+        ));
+}
@@ -72,6 +72,7 @@ fn run_plugin_fixture_test(plugin_name: &str, fixture: &TestFixture) {
     let config = Config {
         plugins: vec![plugin_config],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: true,
             indent: 4,
@@ -203,6 +204,7 @@ fn test_multipass_optimization() {
             PluginConfig::new("removeEmptyAttrs".to_string()),
         ],
         multipass: true,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: true,
             indent: 4,
@@ -2,7 +2,7 @@
 
 //! Integration tests for svgn
 
-use svgn::config::{DataUriFormat, Js2SvgOptions, LineEnding, QuoteAttrsStyle};
+use svgn::config::{DataUriFormat, Js2SvgOptions, LineEnding, QuoteAttrsStyle, XmlDeclaration};
 use svgn::{optimize, Config, OptimizeOptions, PluginConfig};
 
 #[test]
@@ -36,6 +36,7 @@ fn test_full_optimization_pipeline() {
             PluginConfig::new("removeTitle".to_string()),
         ],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: false,
             indent: 2,
@@ -43,10 +44,18 @@ fn test_full_optimization_pipeline() {
             self_closing: true,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            ..Default::default()
         },
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let options = OptimizeOptions::new(config);
@@ -120,6 +129,7 @@ fn test_default_preset_pipeline() {
             PluginConfig::new("convertEllipseToCircle".to_string()),
         ],
         multipass: true,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: false,
             indent: 2,
@@ -127,10 +137,18 @@ fn test_default_preset_pipeline() {
             self_closing: true,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            ..Default::default()
         },
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let options = OptimizeOptions::new(config);
@@ -202,6 +220,7 @@ fn test_pretty_print_formatting() {
     let config = Config {
         plugins: vec![],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: true,
             indent: 2,
@@ -209,10 +228,18 @@ fn test_pretty_print_formatting() {
             self_closing: true,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            ..Default::default()
         },
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let options = OptimizeOptions::new(config);
@@ -274,3 +301,21 @@ fn test_datauri_output_formats() {
     assert!(result_unenc.data.contains("<svg"));
     assert!(result_unenc.data.contains("<circle"));
 }
+
+#[test]
+fn test_js2svg_quote_attrs_and_declaration() {
+    let mut config = Config::default();
+    config.js2svg.quote_attrs = QuoteAttrsStyle::Never;
+    config.js2svg.xml_declaration = XmlDeclaration::Force;
+
+    let result = optimize(
+        r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="10" height="10"/></svg>"#,
+        OptimizeOptions::new(config),
+    )
+    .unwrap();
+
+    assert_eq!(
+        result.data,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"><rect width=10 height=10 /></svg>"
+    );
+}
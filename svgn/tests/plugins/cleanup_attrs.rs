@@ -13,6 +13,7 @@ fn test_plugin(input: &str, expected: &str, params: Option<serde_json::Value>) {
             params,
         }],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: true,
             indent: 4,
@@ -30,6 +30,7 @@ fn test_optimization(
     let config = Config {
         plugins,
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: use_pretty,
             indent: 4,
@@ -37,10 +38,18 @@ fn test_optimization(
             self_closing: true,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            ..Default::default()
         },
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let options = OptimizeOptions::new(config);
@@ -128,10 +137,17 @@ fn test_convert_colors_basic() {
     let config = Config {
         plugins: vec![PluginConfig::new("convertColors".to_string())],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions::default(),
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let options = OptimizeOptions::new(config);
@@ -177,6 +193,7 @@ fn test_multipass_optimization() {
     let config = Config {
         plugins: vec![PluginConfig::new("collapseGroups".to_string())],
         multipass: true,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: true,
             indent: 4,
@@ -184,10 +201,18 @@ fn test_multipass_optimization() {
             self_closing: true,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            ..Default::default()
         },
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let options = OptimizeOptions::new(config);
@@ -227,10 +252,17 @@ fn test_error_resilience() {
                 PluginConfig::new("removeMetadata".to_string()),
             ],
             multipass: false,
+            a11y: false,
             js2svg: Js2SvgOptions::default(),
             path: None,
             datauri: None,
+            svgz: None,
+            size_metric: Default::default(),
             parser: Default::default(),
+            lint: Default::default(),
+            input_format: None,
+            format: Default::default(),
+            component: Default::default(),
         };
 
         let options = OptimizeOptions::new(config);
@@ -259,10 +291,17 @@ fn test_optimization_info() {
             PluginConfig::new("removeMetadata".to_string()),
         ],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions::default(),
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let options = OptimizeOptions::new(config);
@@ -284,6 +323,7 @@ fn test_pretty_print_vs_minified() {
     let pretty_config = Config {
         plugins: vec![],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: true,
             indent: 2,
@@ -291,10 +331,18 @@ fn test_pretty_print_vs_minified() {
             self_closing: true,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            ..Default::default()
         },
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let pretty_result = optimize(input, OptimizeOptions::new(pretty_config)).unwrap();
@@ -305,6 +353,7 @@ fn test_pretty_print_vs_minified() {
     let minified_config = Config {
         plugins: vec![],
         multipass: false,
+        a11y: false,
         js2svg: Js2SvgOptions {
             pretty: false,
             indent: 0,
@@ -312,10 +361,18 @@ fn test_pretty_print_vs_minified() {
             self_closing: true,
             eol: LineEnding::default(),
             final_newline: false,
+            preserve_formatting: false,
+            ..Default::default()
         },
         path: None,
         datauri: None,
+        svgz: None,
+        size_metric: Default::default(),
         parser: Default::default(),
+        lint: Default::default(),
+        input_format: None,
+        format: Default::default(),
+        component: Default::default(),
     };
 
     let minified_result = optimize(input, OptimizeOptions::new(minified_config)).unwrap();